@@ -0,0 +1,536 @@
+// End-to-end throughput of a single live poll with many participants. Starts the server binary
+// with in-memory storage, lets BENCH_PARTICIPANTS participants join (10 000 by default), connects
+// BENCH_SOCKETS of them (all by default) plus the host with a websocket and times every phase of
+// a poll, from joining over answering to the updates every socket has to receive.
+//
+//     cargo bench --bench participants
+//     BENCH_PARTICIPANTS=2000 BENCH_SOCKETS=500 cargo bench --bench participants
+//
+// The server and the benchmark each keep one file descriptor per socket open, so `ulimit -n`
+// has to be a bit higher than BENCH_SOCKETS.
+
+#![allow(clippy::needless_return)]
+
+use std::{
+    net::{SocketAddr, TcpListener},
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+use tokio_tungstenite::{
+    tungstenite::{client::IntoClientRequest, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const HOST_SESSION_ID: &str = "00000000-0000-4000-8000-000000000000";
+// Requests in flight at the same time, like that many participants clicking at the same moment
+const CONCURRENCY: usize = 64;
+const NUM_FREE_TEXT_TERMS: usize = 2000;
+const NUM_QUESTIONS: usize = 100;
+// Short enough that the throttled updates don't dominate the measured times
+const STATS_UPDATE_THROTTLE_MS: &str = "100";
+const PHASE_TIMEOUT: Duration = Duration::from_secs(300);
+
+const SLIDE_MC: usize = 0;
+const SLIDE_FT: usize = 1;
+const SLIDE_QA: usize = 2;
+const NUM_SLIDES: usize = 3;
+
+// Counted by the socket tasks, every socket counts every event only once
+#[derive(Default)]
+struct Counters {
+    slide_updates: [AtomicUsize; NUM_SLIDES],
+    leaderboards: AtomicUsize,
+    sockets_with_all_questions: AtomicUsize,
+    sockets_with_all_upvotes: AtomicUsize,
+    host_num_responses: [AtomicUsize; NUM_SLIDES],
+}
+
+struct Server {
+    process: Child,
+    addr: SocketAddr,
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+fn main() {
+    let num_participants = get_env_usize("BENCH_PARTICIPANTS", 10_000);
+    let num_sockets = get_env_usize("BENCH_SOCKETS", num_participants).min(num_participants);
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    runtime.block_on(run(num_participants, num_sockets));
+}
+
+async fn run(num_participants: usize, num_sockets: usize) {
+    let server = start_server(num_participants).await;
+    let addr = server.addr;
+    let counters = Arc::new(Counters::default());
+
+    println!("{num_participants} participants, {num_sockets} of them with a websocket\n");
+
+    let poll_definition = json!({
+        "enableLeaderboard": true,
+        "slides": [
+            {
+                "type": "mc",
+                "question": "Which one?",
+                "mcAnswers": [
+                    { "text": "A", "isCorrect": true },
+                    { "text": "B", "isCorrect": false },
+                ],
+            },
+            { "type": "ft", "question": "One word" },
+            { "type": "qa", "question": "Questions?" },
+        ],
+    });
+    let (status, poll_id) = HttpConnection::connect(addr)
+        .await
+        .request(
+            "POST",
+            "/start_poll",
+            HOST_SESSION_ID,
+            Some(("application/json", poll_definition.to_string())),
+        )
+        .await;
+    assert_eq!(status, 200, "Starting the poll failed: {poll_id}");
+
+    let session_ids = (0..num_participants)
+        .map(|player_index| format!("00000000-0000-4000-8000-{:012}", player_index + 1))
+        .collect::<Vec<_>>();
+
+    let (mut host_sink, host_stream) =
+        connect_socket(addr, &format!("/ws/host/{poll_id}"), HOST_SESSION_ID)
+            .await
+            .split();
+    tokio::spawn(read_host_socket(host_stream, counters.clone()));
+
+    let start = Instant::now();
+    run_requests(addr, &session_ids, |_| (format!("/p?c={poll_id}"), None)).await;
+    print_result("join", num_participants, start.elapsed());
+
+    let start = Instant::now();
+    let mut participant_sinks = Vec::with_capacity(num_sockets);
+    for session_id in &session_ids[..num_sockets] {
+        let (sink, stream) = connect_socket(addr, &format!("/ws/p/{poll_id}"), session_id)
+            .await
+            .split();
+        participant_sinks.push(sink);
+        tokio::spawn(read_participant_socket(
+            stream,
+            counters.clone(),
+            NUM_QUESTIONS.min(num_sockets),
+            num_sockets - 1,
+        ));
+    }
+    print_result("connect sockets", num_sockets, start.elapsed());
+
+    let start = Instant::now();
+    run_requests(addr, &session_ids, |player_index| {
+        let body = json!({ "slide_index": SLIDE_MC, "answer_indices": [player_index % 2] });
+        (
+            format!("/submit_mc_answer/{poll_id}"),
+            Some(("application/json", body.to_string())),
+        )
+    })
+    .await;
+    print_result("multiple choice answers", num_participants, start.elapsed());
+    wait_for(&counters.host_num_responses[SLIDE_MC], num_participants).await;
+    print_result(
+        "  until the host has all",
+        num_participants,
+        start.elapsed(),
+    );
+
+    let start = Instant::now();
+    send_message(
+        &mut host_sink,
+        "gotoSlide",
+        json!({ "slideIndex": SLIDE_FT }),
+    )
+    .await;
+    wait_for(&counters.slide_updates[SLIDE_FT], num_sockets).await;
+    print_result("slide change to every socket", num_sockets, start.elapsed());
+
+    let start = Instant::now();
+    run_requests(addr, &session_ids, |player_index| {
+        let body = format!(
+            "answer=Term{}&slide_index={SLIDE_FT}",
+            player_index % NUM_FREE_TEXT_TERMS
+        );
+        (
+            format!("/submit_ft_answer/{poll_id}"),
+            Some(("application/x-www-form-urlencoded", body)),
+        )
+    })
+    .await;
+    print_result("word cloud answers", num_participants, start.elapsed());
+    wait_for(&counters.host_num_responses[SLIDE_FT], num_participants).await;
+    print_result(
+        "  until the host has all",
+        num_participants,
+        start.elapsed(),
+    );
+
+    let start = Instant::now();
+    run_requests(addr, &session_ids, |_| {
+        let body = format!("emoji=heart&slide_index={SLIDE_FT}");
+        (
+            format!("/submit_emoji/{poll_id}"),
+            Some(("application/x-www-form-urlencoded", body)),
+        )
+    })
+    .await;
+    print_result("emojis", num_participants, start.elapsed());
+
+    let start = Instant::now();
+    send_message(&mut host_sink, "showLeaderboard", json!({})).await;
+    wait_for(&counters.leaderboards, num_sockets).await;
+    print_result("leaderboard to every socket", num_sockets, start.elapsed());
+
+    let start = Instant::now();
+    send_message(
+        &mut host_sink,
+        "gotoSlide",
+        json!({ "slideIndex": SLIDE_QA }),
+    )
+    .await;
+    wait_for(&counters.slide_updates[SLIDE_QA], num_sockets).await;
+    print_result("slide change to every socket", num_sockets, start.elapsed());
+
+    let num_questions = NUM_QUESTIONS.min(num_sockets);
+    let start = Instant::now();
+    for sink in participant_sinks.iter_mut().take(num_questions) {
+        let data = json!({ "slideIndex": SLIDE_QA, "text": "How does this scale?" });
+        send_message(sink, "submitQuestion", data).await;
+    }
+    wait_for(&counters.sockets_with_all_questions, num_sockets).await;
+    print_result("questions to every socket", num_questions, start.elapsed());
+
+    // Everybody upvotes the first question, only the upvote of its author is rejected
+    let start = Instant::now();
+    for sink in &mut participant_sinks {
+        let data = json!({ "slideIndex": SLIDE_QA, "questionIndex": 0 });
+        send_message(sink, "upvoteQuestion", data).await;
+    }
+    wait_for(&counters.sockets_with_all_upvotes, num_sockets).await;
+    print_result("upvotes to every socket", num_sockets - 1, start.elapsed());
+
+    drop(server);
+}
+
+async fn start_server(num_participants: usize) -> Server {
+    // Let the OS pick a free port for the server
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .unwrap();
+
+    let process = Command::new(env!("CARGO_BIN_EXE_svoote-server"))
+        .env("CONFIG_FILE", "/dev/null")
+        .env("BIND_ADDRESS", addr.to_string())
+        .env("LIVE_POLL_STORAGE", "memory")
+        .env("LIVE_POLL_PARTICIPANT_LIMIT", num_participants.to_string())
+        .env("STATS_UPDATE_THROTTLE_MS", STATS_UPDATE_THROTTLE_MS)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Could not start the server");
+    let server = Server { process, addr };
+
+    let start = Instant::now();
+    while TcpStream::connect(addr).await.is_err() {
+        assert!(start.elapsed() < PHASE_TIMEOUT, "The server did not start");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    return server;
+}
+
+// Sends one request per participant over CONCURRENCY keep-alive connections. Requests without a
+// body are GET requests, all others are POST requests.
+async fn run_requests(
+    addr: SocketAddr,
+    session_ids: &[String],
+    create_request: impl Fn(usize) -> (String, Option<(&'static str, String)>),
+) {
+    let next_index = Arc::new(AtomicUsize::new(0));
+    let requests = (0..session_ids.len())
+        .map(&create_request)
+        .zip(session_ids.iter().cloned())
+        .collect::<Vec<_>>();
+    let requests = Arc::new(requests);
+
+    let workers = (0..CONCURRENCY)
+        .map(|_| {
+            let next_index = next_index.clone();
+            let requests = requests.clone();
+            tokio::spawn(async move {
+                let mut connection = HttpConnection::connect(addr).await;
+                loop {
+                    let index = next_index.fetch_add(1, Ordering::Relaxed);
+                    let Some(((path, body), session_id)) = requests.get(index) else {
+                        return;
+                    };
+
+                    let method = if body.is_some() { "POST" } else { "GET" };
+                    let (status, response) = connection
+                        .request(method, path, session_id, body.clone())
+                        .await;
+                    assert_eq!(status, 200, "{method} {path} failed: {response}");
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for worker in workers {
+        worker.await.unwrap();
+    }
+}
+
+async fn connect_socket(addr: SocketAddr, path: &str, session_id: &str) -> WebSocket {
+    let mut request = format!("ws://{addr}{path}").into_client_request().unwrap();
+    request.headers_mut().insert(
+        "Cookie",
+        format!("session_id={session_id}").parse().unwrap(),
+    );
+
+    let (socket, _response) = tokio_tungstenite::connect_async(request)
+        .await
+        .unwrap_or_else(|e| panic!("Connecting to {path} failed: {e}"));
+
+    return socket;
+}
+
+async fn send_message(sink: &mut SplitSink<WebSocket, Message>, cmd: &str, data: Value) {
+    let msg = json!({ "cmd": cmd, "data": data }).to_string();
+    sink.send(Message::Text(msg)).await.unwrap();
+}
+
+async fn read_host_socket(mut stream: SplitStream<WebSocket>, counters: Arc<Counters>) {
+    while let Some(Ok(msg)) = stream.next().await {
+        let Some((cmd, data)) = parse_message(msg) else {
+            continue;
+        };
+
+        if cmd == "updateStats" {
+            let slide_index = data["slideIndex"].as_u64().unwrap_or(0) as usize;
+            let num_responses = data["numResponses"].as_u64().unwrap_or(0) as usize;
+            if let Some(counter) = counters.host_num_responses.get(slide_index) {
+                counter.fetch_max(num_responses, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+async fn read_participant_socket(
+    mut stream: SplitStream<WebSocket>,
+    counters: Arc<Counters>,
+    num_questions: usize,
+    num_upvotes: usize,
+) {
+    let mut seen_slides = [false; NUM_SLIDES];
+    let mut seen_leaderboard = false;
+    let mut seen_all_questions = false;
+    let mut seen_all_upvotes = false;
+
+    while let Some(Ok(msg)) = stream.next().await {
+        let Some((cmd, data)) = parse_message(msg) else {
+            continue;
+        };
+
+        let questions = match cmd.as_str() {
+            "updateSlide" => {
+                let slide_index = data["slideIndex"].as_u64().unwrap_or(0) as usize;
+                if slide_index < NUM_SLIDES {
+                    count_once(
+                        &mut seen_slides[slide_index],
+                        &counters.slide_updates[slide_index],
+                    );
+                }
+                data["slide"]["questions"].as_array().cloned()
+            }
+            "showLeaderboard" => {
+                count_once(&mut seen_leaderboard, &counters.leaderboards);
+                None
+            }
+            "updateQuestions" => data["questions"].as_array().cloned(),
+            _ => None,
+        };
+
+        if let Some(questions) = questions {
+            if questions.len() >= num_questions {
+                count_once(
+                    &mut seen_all_questions,
+                    &counters.sockets_with_all_questions,
+                );
+            }
+
+            let first_question_upvotes = questions
+                .iter()
+                .find(|question| question["index"] == 0)
+                .and_then(|question| question["upvotes"].as_u64())
+                .unwrap_or(0) as usize;
+            if first_question_upvotes >= num_upvotes && num_upvotes > 0 {
+                count_once(&mut seen_all_upvotes, &counters.sockets_with_all_upvotes);
+            }
+        }
+    }
+}
+
+fn count_once(seen: &mut bool, counter: &AtomicUsize) {
+    if !std::mem::replace(seen, true) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn parse_message(msg: Message) -> Option<(String, Value)> {
+    let Message::Text(text) = msg else {
+        return None;
+    };
+
+    let mut msg = serde_json::from_str::<Value>(&text).ok()?;
+    let cmd = msg["cmd"].as_str()?.to_string();
+    return Some((cmd, msg["data"].take()));
+}
+
+async fn wait_for(counter: &AtomicUsize, expected: usize) {
+    let start = Instant::now();
+    while counter.load(Ordering::Relaxed) < expected {
+        assert!(
+            start.elapsed() < PHASE_TIMEOUT,
+            "Timed out at {} of {expected}",
+            counter.load(Ordering::Relaxed)
+        );
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+}
+
+fn print_result(phase: &str, count: usize, elapsed: Duration) {
+    println!(
+        "{phase:<32} {count:>7} in {:>8.3} s  {:>10.0} / s",
+        elapsed.as_secs_f64(),
+        count as f64 / elapsed.as_secs_f64()
+    );
+}
+
+fn get_env_usize(name: &str, default: usize) -> usize {
+    return match std::env::var(name) {
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|_| panic!("{name} has to be a number")),
+        Err(_) => default,
+    };
+}
+
+// Just enough HTTP/1.1 for the requests of a participant, over one keep-alive connection
+struct HttpConnection {
+    stream: BufReader<TcpStream>,
+}
+
+impl HttpConnection {
+    async fn connect(addr: SocketAddr) -> Self {
+        let stream = TcpStream::connect(addr).await.unwrap();
+        stream.set_nodelay(true).unwrap();
+
+        return HttpConnection {
+            stream: BufReader::new(stream),
+        };
+    }
+
+    // Returns the status code and the response body
+    async fn request(
+        &mut self,
+        method: &str,
+        path: &str,
+        session_id: &str,
+        body: Option<(&str, String)>,
+    ) -> (u16, String) {
+        let mut request = format!(
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nCookie: session_id={session_id}\r\n"
+        );
+        if let Some((content_type, body)) = &body {
+            request += &format!(
+                "Content-Type: {content_type}\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+        } else {
+            request += "\r\n";
+        }
+        self.stream
+            .get_mut()
+            .write_all(request.as_bytes())
+            .await
+            .unwrap();
+
+        let mut status_line = String::new();
+        self.stream.read_line(&mut status_line).await.unwrap();
+        let status = status_line
+            .split(' ')
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .unwrap_or_else(|| panic!("Invalid status line {status_line:?}"));
+
+        let mut content_length = 0usize;
+        let mut is_chunked = false;
+        loop {
+            let mut header = String::new();
+            self.stream.read_line(&mut header).await.unwrap();
+            let header = header.trim_end().to_lowercase();
+            if header.is_empty() {
+                break;
+            }
+
+            if let Some(value) = header.strip_prefix("content-length:") {
+                content_length = value.trim().parse().unwrap();
+            } else if header == "transfer-encoding: chunked" {
+                is_chunked = true;
+            }
+        }
+
+        let mut response = Vec::new();
+        if is_chunked {
+            loop {
+                let mut chunk_size = String::new();
+                self.stream.read_line(&mut chunk_size).await.unwrap();
+                let chunk_size = usize::from_str_radix(chunk_size.trim(), 16).unwrap();
+
+                // Every chunk, including the last empty one, is followed by a line break
+                let mut chunk = vec![0u8; chunk_size + 2];
+                self.stream.read_exact(&mut chunk).await.unwrap();
+                response.extend_from_slice(&chunk[..chunk_size]);
+
+                if chunk_size == 0 {
+                    break;
+                }
+            }
+        } else {
+            response.resize(content_length, 0u8);
+            self.stream.read_exact(&mut response).await.unwrap();
+        }
+
+        return (status, String::from_utf8_lossy(&response).into_owned());
+    }
+}
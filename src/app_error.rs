@@ -1,13 +1,18 @@
 use axum::response::{IntoResponse, Response};
 use maud::html;
 
-use crate::html_page::{self, render_header};
+use crate::{
+    html_page::{self, render_header},
+    poll_definition::PollValidationError,
+};
 
 #[derive(Debug)]
 pub enum AppError {
     NotFound,
     BadRequest(String),
+    InvalidPollDefinition(Vec<PollValidationError>),
     Unauthorized(String),
+    ServiceUnavailable(String),
     OtherInternalServerError(String),
 }
 
@@ -27,9 +32,19 @@ impl IntoResponse for AppError {
             AppError::BadRequest(msg) => {
                 return (axum::http::StatusCode::BAD_REQUEST, msg).into_response();
             }
+            AppError::InvalidPollDefinition(errors) => {
+                return (
+                    axum::http::StatusCode::BAD_REQUEST,
+                    axum::Json(serde_json::json!({ "errors": errors })),
+                )
+                    .into_response();
+            }
             AppError::Unauthorized(msg) => {
                 return (axum::http::StatusCode::UNAUTHORIZED, msg).into_response();
             }
+            AppError::ServiceUnavailable(msg) => {
+                return (axum::http::StatusCode::SERVICE_UNAVAILABLE, msg).into_response();
+            }
             AppError::OtherInternalServerError(s) => {
                 error!("Other internal server error: {s}");
                 return (
@@ -50,7 +65,21 @@ impl core::fmt::Display for AppError {
         match self {
             Self::NotFound => write!(f, "Not Found")?,
             Self::BadRequest(s) => write!(f, "Bad request: {s}")?,
+            Self::InvalidPollDefinition(errors) => {
+                write!(f, "Invalid poll definition:")?;
+                for error in errors {
+                    match error.slide_index {
+                        Some(slide_index) => write!(
+                            f,
+                            " [slide {slide_index}, {}: {}]",
+                            error.field, error.reason
+                        )?,
+                        None => write!(f, " [{}: {}]", error.field, error.reason)?,
+                    }
+                }
+            }
             Self::Unauthorized(s) => write!(f, "Unauthorized: {s}")?,
+            Self::ServiceUnavailable(s) => write!(f, "Service unavailable: {s}")?,
             Self::OtherInternalServerError(s) => write!(f, "Internal server error: {s}")?,
         }
 
@@ -0,0 +1,296 @@
+use maud::{html, Markup};
+
+use crate::settings::settings;
+
+// Static SVG versions of the charts the host page draws in the browser, for printable reports.
+// All charts have a fixed coordinate system and scale with the width of their container.
+
+const CHART_WIDTH: f64 = 640f64;
+const LABEL_COLOR: &str = "#64748b";
+const GRID_COLOR: &str = "#e2e8f0";
+const FONT_FAMILY: &str = "Arial, Helvetica, sans-serif";
+// Rough average glyph width relative to the font size, used to lay out text without a font
+const GLYPH_WIDTH_FACTOR: f64 = 0.56;
+
+fn get_color(index: usize) -> &'static str {
+    return settings().color_rgb(index);
+}
+
+fn estimate_text_width(text: &str, font_size: f64) -> f64 {
+    return text.chars().count() as f64 * font_size * GLYPH_WIDTH_FACTOR;
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    return text.chars().take(max_chars - 1).chain(['…']).collect();
+}
+
+// One horizontal bar per row as (label, count, is_highlighted), highlighted rows are shown bold
+pub fn render_bar_chart(rows: &[(String, usize, bool)]) -> Markup {
+    const ROW_HEIGHT: f64 = 34f64;
+    const LABEL_WIDTH: f64 = 220f64;
+    const VALUE_WIDTH: f64 = 56f64;
+
+    let max_count = rows
+        .iter()
+        .map(|(_, count, _)| *count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let total_count = rows.iter().map(|(_, count, _)| *count).sum::<usize>();
+    let bar_area_width = CHART_WIDTH - LABEL_WIDTH - VALUE_WIDTH;
+    let height = (rows.len() as f64 * ROW_HEIGHT).max(ROW_HEIGHT);
+
+    return html! {
+        svg xmlns="http://www.w3.org/2000/svg" viewBox={ "0 0 " (CHART_WIDTH) " " (height) }
+            width="100%" font-family=(FONT_FAMILY) font-size="14"
+        {
+            @for (row_index, (label, count, is_highlighted)) in rows.iter().enumerate() {
+                @let y = row_index as f64 * ROW_HEIGHT;
+                @let bar_width = bar_area_width * *count as f64 / max_count as f64;
+                text x=(LABEL_WIDTH - 12f64) y=(y + ROW_HEIGHT / 2f64) text-anchor="end" dominant-baseline="central"
+                    fill="#334155" font-weight=(if *is_highlighted { "bold" } else { "normal" })
+                    { (truncate(label, 28)) @if *is_highlighted { " ✓" } }
+                rect x=(LABEL_WIDTH) y=(y + 6f64) width=(bar_area_width) height=(ROW_HEIGHT - 12f64) fill=(GRID_COLOR) rx="3" {}
+                rect x=(LABEL_WIDTH) y=(y + 6f64) width=(bar_width) height=(ROW_HEIGHT - 12f64) fill=(get_color(row_index)) rx="3" {}
+                text x=(CHART_WIDTH - VALUE_WIDTH + 8f64) y=(y + ROW_HEIGHT / 2f64) dominant-baseline="central" fill=(LABEL_COLOR) {
+                    (count)
+                    @if total_count > 0 {
+                        tspan font-size="11" { " (" (format!("{:.0}", *count as f64 / total_count as f64 * 100f64)) "%)" }
+                    }
+                }
+            }
+        }
+    };
+}
+
+// Pie chart with a legend on the right, (label, count) per slice
+pub fn render_pie_chart(slices: &[(String, usize)]) -> Markup {
+    const HEIGHT: f64 = 280f64;
+    const RADIUS: f64 = 120f64;
+    const CENTER_X: f64 = 140f64;
+    const CENTER_Y: f64 = HEIGHT / 2f64;
+    const LEGEND_X: f64 = 300f64;
+    const LEGEND_ROW_HEIGHT: f64 = 26f64;
+
+    let total_count = slices.iter().map(|(_, count)| *count).sum::<usize>();
+    let legend_top = CENTER_Y - slices.len() as f64 * LEGEND_ROW_HEIGHT / 2f64;
+
+    // Angles start at the top and go clockwise, like in the host page
+    let point_at = |angle: f64, radius: f64| {
+        (
+            CENTER_X + radius * angle.sin(),
+            CENTER_Y - radius * angle.cos(),
+        )
+    };
+
+    let mut start_angle = 0f64;
+    let mut paths = Vec::new();
+    for (slice_index, (_, count)) in slices.iter().enumerate() {
+        if *count == 0 {
+            continue;
+        }
+
+        let slice_angle = *count as f64 / total_count as f64 * std::f64::consts::TAU;
+        let end_angle = start_angle + slice_angle;
+        let (start_x, start_y) = point_at(start_angle, RADIUS);
+        let (end_x, end_y) = point_at(end_angle, RADIUS);
+        let (label_x, label_y) = point_at(start_angle + slice_angle / 2f64, RADIUS * 0.68);
+        let large_arc = if slice_angle > std::f64::consts::PI {
+            1
+        } else {
+            0
+        };
+
+        let path = if *count == total_count {
+            None
+        } else {
+            Some(format!(
+                "M {CENTER_X} {CENTER_Y} L {start_x:.2} {start_y:.2} A {RADIUS} {RADIUS} 0 {large_arc} 1 {end_x:.2} {end_y:.2} Z"
+            ))
+        };
+        paths.push((slice_index, *count, path, label_x, label_y));
+
+        start_angle = end_angle;
+    }
+
+    return html! {
+        svg xmlns="http://www.w3.org/2000/svg" viewBox={ "0 0 " (CHART_WIDTH) " " (HEIGHT) }
+            width="100%" font-family=(FONT_FAMILY) font-size="14"
+        {
+            @if total_count == 0 {
+                circle cx=(CENTER_X) cy=(CENTER_Y) r=(RADIUS) fill=(GRID_COLOR) {}
+            }
+            @for (slice_index, count, path, label_x, label_y) in &paths {
+                @match path {
+                    Some(path) => { path d=(path) fill=(get_color(*slice_index)) stroke="white" stroke-width="2" {} }
+                    None => { circle cx=(CENTER_X) cy=(CENTER_Y) r=(RADIUS) fill=(get_color(*slice_index)) {} }
+                }
+                text x=(format!("{label_x:.2}")) y=(format!("{label_y:.2}")) text-anchor="middle" dominant-baseline="central"
+                    fill="white" font-size="16" font-weight="bold" { (count) }
+            }
+            @for (slice_index, (label, count)) in slices.iter().enumerate() {
+                @let y = legend_top + slice_index as f64 * LEGEND_ROW_HEIGHT;
+                rect x=(LEGEND_X) y=(y + 6f64) width="14" height="14" rx="2" fill=(get_color(slice_index)) {}
+                text x=(LEGEND_X + 24f64) y=(y + 13f64) dominant-baseline="central" fill="#334155" {
+                    (truncate(label, 30))
+                    tspan fill=(LABEL_COLOR) { " · " (count) }
+                }
+            }
+        }
+    };
+}
+
+// Vertical columns with a label below each, for rating scales and numeric histograms
+pub fn render_column_chart(columns: &[(String, usize)]) -> Markup {
+    const HEIGHT: f64 = 240f64;
+    const LABEL_HEIGHT: f64 = 28f64;
+    const VALUE_HEIGHT: f64 = 22f64;
+
+    let max_count = columns
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let column_width = CHART_WIDTH / columns.len().max(1) as f64;
+    let bar_area_height = HEIGHT - LABEL_HEIGHT - VALUE_HEIGHT;
+    let font_size = if columns.len() > 10 { 10 } else { 13 };
+
+    return html! {
+        svg xmlns="http://www.w3.org/2000/svg" viewBox={ "0 0 " (CHART_WIDTH) " " (HEIGHT) }
+            width="100%" font-family=(FONT_FAMILY) font-size=(font_size)
+        {
+            line x1="0" y1=(HEIGHT - LABEL_HEIGHT) x2=(CHART_WIDTH) y2=(HEIGHT - LABEL_HEIGHT) stroke=(GRID_COLOR) {}
+            @for (column_index, (label, count)) in columns.iter().enumerate() {
+                @let x = column_index as f64 * column_width;
+                @let bar_height = bar_area_height * *count as f64 / max_count as f64;
+                @let bar_top = HEIGHT - LABEL_HEIGHT - bar_height;
+                rect x=(x + column_width * 0.15) y=(bar_top) width=(column_width * 0.7) height=(bar_height)
+                    fill=(get_color(1)) rx="3" {}
+                text x=(x + column_width / 2f64) y=(bar_top - 6f64) text-anchor="middle" fill="#334155" { (count) }
+                text x=(x + column_width / 2f64) y=(HEIGHT - LABEL_HEIGHT / 2f64) text-anchor="middle"
+                    dominant-baseline="central" fill=(LABEL_COLOR) { (label) }
+            }
+        }
+    };
+}
+
+struct PlacedTerm<'a> {
+    text: &'a str,
+    font_size: f64,
+    color_index: usize,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl PlacedTerm<'_> {
+    fn overlaps(&self, x: f64, y: f64, width: f64, height: f64) -> bool {
+        const PADDING: f64 = 4f64;
+
+        return (self.x - x).abs() * 2f64 < self.width + width + PADDING
+            && (self.y - y).abs() * 2f64 < self.height + height + PADDING;
+    }
+}
+
+// Places the terms on an elliptical spiral around the center, most frequent terms first.
+// Terms that don't fit anymore are left out, like in the host page.
+pub fn render_word_cloud(terms: &[(&str, usize)]) -> Markup {
+    const HEIGHT: f64 = 320f64;
+    const MIN_FONT_SIZE: f64 = 13f64;
+    const MAX_FONT_SIZE: f64 = 48f64;
+    const SPIRAL_STEP: f64 = 0.15;
+    const MAX_SPIRAL_STEPS: usize = 4000;
+    // Every term is checked against all placed ones, the rest wouldn't fit anyway
+    const MAX_TERMS: usize = 100;
+
+    let mut terms = terms.to_vec();
+    terms.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    terms.truncate(MAX_TERMS);
+    let max_count = terms.first().map(|(_, count)| *count).unwrap_or(0).max(1);
+
+    let mut placed_terms: Vec<PlacedTerm> = Vec::with_capacity(terms.len());
+    for (term_index, (text, count)) in terms.iter().enumerate() {
+        let font_size =
+            MIN_FONT_SIZE + (MAX_FONT_SIZE - MIN_FONT_SIZE) * *count as f64 / max_count as f64;
+        let width = estimate_text_width(text, font_size);
+        let height = font_size * 1.1;
+
+        if width > CHART_WIDTH {
+            continue;
+        }
+
+        for step in 0..MAX_SPIRAL_STEPS {
+            let angle = step as f64 * SPIRAL_STEP;
+            let radius = angle * 2f64;
+            // Wider than high, so the cloud fills the landscape chart area
+            let x = CHART_WIDTH / 2f64 + radius * angle.cos() * 1.8;
+            let y = HEIGHT / 2f64 + radius * angle.sin();
+
+            let is_inside = x - width / 2f64 >= 0f64
+                && x + width / 2f64 <= CHART_WIDTH
+                && y - height / 2f64 >= 0f64
+                && y + height / 2f64 <= HEIGHT;
+
+            if is_inside
+                && !placed_terms
+                    .iter()
+                    .any(|term| term.overlaps(x, y, width, height))
+            {
+                placed_terms.push(PlacedTerm {
+                    text,
+                    font_size,
+                    color_index: term_index,
+                    x,
+                    y,
+                    width,
+                    height,
+                });
+                break;
+            }
+        }
+    }
+
+    // textLength makes the rendered width match the estimated one, so terms never overlap
+    return html! {
+        svg xmlns="http://www.w3.org/2000/svg" viewBox={ "0 0 " (CHART_WIDTH) " " (HEIGHT) }
+            width="100%" font-family=(FONT_FAMILY) font-weight="500"
+        {
+            @for term in &placed_terms {
+                text x=(format!("{:.1}", term.x)) y=(format!("{:.1}", term.y))
+                    font-size=(format!("{:.1}", term.font_size))
+                    textLength=(format!("{:.1}", term.width)) lengthAdjust="spacingAndGlyphs"
+                    text-anchor="middle" dominant-baseline="central" fill=(get_color(term.color_index))
+                    { (term.text) }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_cloud_places_only_the_most_frequent_terms() {
+        let texts = (0..5000)
+            .map(|index| format!("t{index}"))
+            .collect::<Vec<_>>();
+        let terms = texts
+            .iter()
+            .enumerate()
+            .map(|(index, text)| (text.as_str(), index))
+            .collect::<Vec<_>>();
+
+        let svg = render_word_cloud(&terms).into_string();
+
+        assert!(svg.matches("<text ").count() <= 100);
+        assert!(svg.contains(">t4999</text>"));
+        assert!(!svg.contains(">t0</text>"));
+    }
+}
@@ -5,20 +5,55 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use axum_extra::extract::CookieJar;
-use maud::html;
+use maud::{html, PreEscaped};
 
 use crate::{
     app_error::AppError,
     html_page::{self, render_header},
-    select_language,
+    markdown::MARKDOWN_CLASSES,
+    select_language, self_hosting,
     svg_icons::SvgIcon,
 };
 
+// Self-hosted instances serve the legal pages of their operator, or nothing if none were supplied
+fn render_self_hosted_page(
+    title: &str,
+    l: &str,
+    content: Option<&String>,
+) -> Result<Response, AppError> {
+    let content = content.ok_or(AppError::NotFound)?;
+
+    return Ok(html_page::render_html_page(
+        title,
+        l,
+        html! {
+            (render_header(html!{}))
+            ."mx-6 sm:mx-14 my-24 text-slate-700" {
+                .{ "max-w-2xl mx-auto " (MARKDOWN_CLASSES) } { (PreEscaped(content)) }
+            }
+        },
+        true,
+    )
+    .into_response());
+}
+
+fn assert_not_self_hosted() -> Result<(), AppError> {
+    if self_hosting::is_self_hosted() {
+        return Err(AppError::NotFound);
+    }
+
+    return Ok(());
+}
+
 pub async fn get_privacy_policy_page(
     cookies: CookieJar,
     headers: HeaderMap,
 ) -> Result<Response, AppError> {
     let l = select_language(&cookies, &headers);
+    if let Some(legal_pages) = self_hosting::get_legal_pages() {
+        return render_self_hosted_page("Privacy policy", &l, legal_pages.privacy_policy.as_ref());
+    }
+
     return Ok(html_page::render_html_page(
         "Privacy policy - Svoote",
         &l,
@@ -61,6 +96,14 @@ pub async fn get_terms_of_service_page(
     headers: HeaderMap,
 ) -> Result<Response, AppError> {
     let l = select_language(&cookies, &headers);
+    if let Some(legal_pages) = self_hosting::get_legal_pages() {
+        return render_self_hosted_page(
+            "Terms of service",
+            &l,
+            legal_pages.terms_of_service.as_ref(),
+        );
+    }
+
     return Ok(html_page::render_html_page(
         "Terms of service - Svoote",
         &l,
@@ -122,6 +165,7 @@ pub async fn get_cookie_policy_page(
     cookies: CookieJar,
     headers: HeaderMap,
 ) -> Result<Response, AppError> {
+    assert_not_self_hosted()?;
     let l = select_language(&cookies, &headers);
     return Ok(html_page::render_html_page(
         "Cookie policy - Svoote",
@@ -155,6 +199,8 @@ pub async fn get_cookie_policy_page(
 }
 
 pub async fn get_contact_page() -> Result<Response, AppError> {
+    assert_not_self_hosted()?;
+
     return Ok(html_page::render_html_page(
         "Contact - Svoote",
         "en",
@@ -180,6 +226,7 @@ pub async fn get_manage_cookies_page(
     cookies: CookieJar,
     headers: HeaderMap,
 ) -> Result<Response, AppError> {
+    assert_not_self_hosted()?;
     let l = select_language(&cookies, &headers);
     return Ok(html_page::render_html_page(
         "Manage cookies - Svoote",
@@ -213,6 +260,17 @@ pub async fn get_manage_cookies_page(
     .into_response());
 }
 
+pub async fn get_imprint_page(
+    cookies: CookieJar,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let l = select_language(&cookies, &headers);
+    let imprint =
+        self_hosting::get_legal_pages().and_then(|legal_pages| legal_pages.imprint.as_ref());
+
+    return render_self_hosted_page("Imprint", &l, imprint);
+}
+
 pub async fn get_robots_txt() -> Response {
     return r#"
 User-agent: *
@@ -1,22 +1,28 @@
-pub const FREE_TEXT_MAX_CHAR_LENGTH: usize = 32;
-pub const LIVE_POLL_PARTICIPANT_LIMIT: usize = 100usize;
+pub const CUSTOM_PLAYER_NAME_LENGTH_LIMIT: usize = 32;
 
-pub const COLOR_PALETTE: &[&'static str] = &[
-    "bg-rose-500",
-    "bg-cyan-600",
-    "bg-lime-600",
-    "bg-fuchsia-600",
-    "bg-slate-600",
-    "bg-teal-600",
-];
+// Upper bound for the poll_max_mc_answers setting, answers are stored in arrays of this size
+pub const MC_ANSWERS_CAPACITY: usize = 16;
+pub const POLL_MAX_STR_LEN: usize = 1024;
+pub const RATING_MIN_SCALE: u8 = 2;
+pub const RATING_MAX_SCALE: u8 = 10;
+pub const NUMERIC_MAX_BUCKETS: usize = 20;
+pub const INFO_MAX_BODY_LENGTH: usize = 16 * 1024;
+pub const QA_MAX_QUESTION_LENGTH: usize = 280;
+pub const QA_MAX_QUESTIONS_PER_PLAYER: usize = 10;
+// The host gets the names of at most this many free text answers, the word cloud counts all of them
+pub const FREE_TEXT_ANSWER_LIST_SIZE: usize = 50;
 
-pub const COLOR_PALETTE_RGB: &[&'static str] = &[
-    "#f43f5e", "#0891b2", "#65a30d", "#c026d3", "#475569", "#0d9488",
-];
+pub const QR_CODE_DEFAULT_SIZE: u32 = 512;
+pub const QR_CODE_MIN_SIZE: u32 = 64;
+pub const QR_CODE_MAX_SIZE: u32 = 4096;
 
-pub const POLL_MAX_MC_ANSWERS: usize = 6;
-pub const POLL_MAX_SLIDES: usize = 32;
-//pub const POLL_MAX_STR_LEN: usize = 1024;
+pub const QUIZ_MIN_POINTS: usize = 50;
+pub const QUIZ_MAX_POINTS: usize = 100;
+pub const LEADERBOARD_SIZE: usize = 10;
+pub const QUIZ_SCORING_TIME_LIMIT: tokio::time::Duration = tokio::time::Duration::from_secs(60);
 
-pub const POLL_EXIT_TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(2 * 60 * 60); // 2 hours
-pub const STATS_UPDATE_THROTTLE: tokio::time::Duration = tokio::time::Duration::from_secs(2);
+// Every poll writes its snapshot at most this often, changes in between are merged into one write
+pub const LIVE_POLL_SAVE_INTERVAL: tokio::time::Duration = tokio::time::Duration::from_secs(1);
+pub const COUNTDOWN_MAX_DURATION: tokio::time::Duration = tokio::time::Duration::from_secs(60 * 60);
+// Sent to hosts and participants on shutdown, roughly how long a restart takes
+pub const SHUTDOWN_RECONNECT_DELAY: tokio::time::Duration = tokio::time::Duration::from_secs(5);
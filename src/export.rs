@@ -0,0 +1,591 @@
+use axum::{
+    extract::{Path, Query},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::CookieJar;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    app_error::AppError,
+    live_poll_store::{live_poll_store, ShortID},
+    session_id,
+    slide::{ResultSnapshot, Slide, SlideRound, SlideType},
+};
+
+const CSV_HEADER: &[&str] = &[
+    "slide",
+    "slide_type",
+    "question",
+    "round",
+    "player",
+    "category",
+    "item",
+    "detail",
+    "value",
+];
+
+#[derive(Deserialize)]
+pub struct ExportParams {
+    // Restricts the export to a single slide, so every slide can be downloaded as its own file
+    pub slide: Option<usize>,
+}
+
+pub async fn get_export(
+    cookies: CookieJar,
+    Path((poll_id, format)): Path<(ShortID, String)>,
+    Query(params): Query<ExportParams>,
+) -> Result<Response, AppError> {
+    let live_poll = live_poll_store().get(poll_id).ok_or(AppError::NotFound)?;
+    let (session_id, _cookies) = session_id::get_or_create_session_id(cookies);
+    session_id::assert_equal_ids(&session_id, &live_poll.host_session_id)?;
+
+    return live_poll
+        .read(move |live_poll| {
+            create_export_response(
+                poll_id,
+                &live_poll.slides,
+                live_poll.players.len(),
+                &format,
+                params.slide,
+            )
+        })
+        .await?;
+}
+
+// Also used for the exports of a report after the poll ended
+pub fn create_export_response(
+    poll_id: ShortID,
+    slides: &[Slide],
+    num_participants: usize,
+    format: &str,
+    slide_index: Option<usize>,
+) -> Result<Response, AppError> {
+    if slide_index.is_some_and(|slide_index| slide_index >= slides.len()) {
+        return Err(AppError::BadRequest(
+            "slide_index out of bounds".to_string(),
+        ));
+    }
+
+    let file_name = match slide_index {
+        Some(slide_index) => format!("svoote-{poll_id}-slide-{}", slide_index + 1),
+        None => format!("svoote-{poll_id}"),
+    };
+
+    let (content_type, file_name, body) = match format {
+        "json" => (
+            "application/json",
+            format!("{file_name}.json"),
+            create_poll_export_json(poll_id, slides, num_participants, slide_index).to_string(),
+        ),
+        "csv" => (
+            "text/csv; charset=utf-8",
+            format!("{file_name}.csv"),
+            create_poll_export_csv(slides, slide_index),
+        ),
+        _ => {
+            return Err(AppError::BadRequest(
+                "Export format has to be json or csv".to_string(),
+            ))
+        }
+    };
+
+    return Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{file_name}\""),
+            ),
+        ],
+        body,
+    )
+        .into_response());
+}
+
+// Players are only identified by their index, names and session ids are never exported
+pub fn create_poll_export_json(
+    poll_id: ShortID,
+    slides: &[Slide],
+    num_participants: usize,
+    slide_index: Option<usize>,
+) -> Value {
+    return json!({
+        "pollId": poll_id,
+        "numParticipants": num_participants,
+        "slides": slides
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| slide_index.is_none_or(|slide_index| slide_index == *index))
+            .map(|(index, slide)| create_slide_export_json(index, slide))
+            .collect::<Vec<_>>(),
+    });
+}
+
+fn create_slide_export_json(slide_index: usize, slide: &Slide) -> Value {
+    let answers = match &slide.slide_type {
+        SlideType::MultipleChoice(answers) => json!({
+            "answers": answers
+                .answers
+                .iter()
+                .zip(answers.answer_counts.iter())
+                .map(|((text, is_correct), count)| json!({
+                    "text": text,
+                    "isCorrect": is_correct,
+                    "count": count,
+                }))
+                .collect::<Vec<_>>(),
+            "allowMultipleAnswers": answers.allow_multiple_answers,
+            "playerAnswers": answers
+                .player_answers
+                .iter()
+                .map(|(player_index, answer)| json!({ "player": player_index, "answers": answer }))
+                .collect::<Vec<_>>(),
+        }),
+        SlideType::FreeText(answers) => json!({
+            "terms": answers
+                .word_cloud_terms
+                .iter()
+                .map(|term| json!({
+                    "text": term.preferred_spelling,
+                    "count": term.count,
+                    "spellings": term.spellings,
+                }))
+                .collect::<Vec<_>>(),
+            "playerAnswers": answers
+                .player_answers
+                .iter()
+                .map(|(player_index, answer)| json!({ "player": player_index, "answer": answer }))
+                .collect::<Vec<_>>(),
+        }),
+        SlideType::Rating(answers) => json!({
+            "scaleMax": answers.scale_max,
+            "lowLabel": answers.low_label,
+            "highLabel": answers.high_label,
+            "playerAnswers": answers
+                .player_answers
+                .iter()
+                .map(|(player_index, rating)| json!({ "player": player_index, "rating": rating }))
+                .collect::<Vec<_>>(),
+        }),
+        SlideType::NumericEstimate(answers) => json!({
+            "min": answers.min,
+            "max": answers.max,
+            "playerAnswers": answers
+                .player_answers
+                .iter()
+                .map(|(player_index, answer)| json!({ "player": player_index, "answer": answer }))
+                .collect::<Vec<_>>(),
+        }),
+        SlideType::Ranking(answers) => json!({
+            "options": answers.options,
+            "playerAnswers": answers
+                .player_answers
+                .iter()
+                .map(|(player_index, ordering)| {
+                    json!({ "player": player_index, "ranking": ordering })
+                })
+                .collect::<Vec<_>>(),
+        }),
+        SlideType::QandA(answers) => json!({
+            "questions": answers
+                .questions
+                .iter()
+                .map(|question| json!({
+                    "player": question.player_index,
+                    "text": question.text,
+                    "upvotes": question.upvotes,
+                    "isAnswered": question.is_answered,
+                    "isPinned": question.is_pinned,
+                    "isHidden": question.is_hidden,
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        SlideType::Info(content) => json!({ "markdown": content.markdown }),
+        SlideType::Undefined => Value::Null,
+    };
+
+    return json!({
+        "slideIndex": slide_index,
+        "type": get_slide_type_key(&slide.slide_type),
+        "question": slide.question,
+        "round": slide.get_round_number(),
+        "numResponses": slide.get_num_responses(),
+        "answers": answers,
+        "summary": slide.create_results_json(),
+        "emojis": create_emojis_json(&get_emoji_counts(slide)),
+        "rounds": slide
+            .rounds
+            .iter()
+            .map(|round| json!({
+                "round": round.round_number,
+                "numResponses": round.num_responses,
+                "results": create_snapshot_json(&round.results),
+                "emojis": create_emojis_json(&get_round_emoji_counts(round)),
+            }))
+            .collect::<Vec<_>>(),
+    });
+}
+
+fn create_snapshot_json(snapshot: &ResultSnapshot) -> Value {
+    return match snapshot {
+        ResultSnapshot::MultipleChoice {
+            answers,
+            answer_counts,
+        } => json!({
+            "answers": answers
+                .iter()
+                .zip(answer_counts.iter())
+                .map(|(text, count)| json!({ "text": text, "count": count }))
+                .collect::<Vec<_>>(),
+        }),
+        ResultSnapshot::FreeText { terms } => json!({
+            "terms": terms
+                .iter()
+                .map(|(_, spelling, count)| json!({ "text": spelling, "count": count }))
+                .collect::<Vec<_>>(),
+        }),
+        ResultSnapshot::Other(results) => results.clone(),
+    };
+}
+
+fn create_emojis_json(emojis: &[(&str, usize)]) -> Value {
+    return Value::Object(
+        emojis
+            .iter()
+            .map(|(emoji, count)| (emoji.to_string(), json!(count)))
+            .collect(),
+    );
+}
+
+fn get_emoji_counts(slide: &Slide) -> [(&'static str, usize); 5] {
+    return [
+        ("heart", slide.heart_emojis),
+        ("thumbsUp", slide.thumbs_up_emojis),
+        ("thumbsDown", slide.thumbs_down_emojis),
+        ("smileyFace", slide.smiley_face_emojis),
+        ("sadFace", slide.sad_face_emojis),
+    ];
+}
+
+fn get_round_emoji_counts(round: &SlideRound) -> [(&'static str, usize); 5] {
+    return [
+        ("heart", round.heart_emojis),
+        ("thumbsUp", round.thumbs_up_emojis),
+        ("thumbsDown", round.thumbs_down_emojis),
+        ("smileyFace", round.smiley_face_emojis),
+        ("sadFace", round.sad_face_emojis),
+    ];
+}
+
+// The same keys the host page uses for the slide types
+pub fn get_slide_type_key(slide_type: &SlideType) -> &'static str {
+    return match slide_type {
+        SlideType::Undefined => "empty",
+        SlideType::MultipleChoice(_) => "mc",
+        SlideType::FreeText(_) => "ft",
+        SlideType::Rating(_) => "rating",
+        SlideType::NumericEstimate(_) => "num",
+        SlideType::Ranking(_) => "rank",
+        SlideType::QandA(_) => "qa",
+        SlideType::Info(_) => "info",
+    };
+}
+
+// Long format with one value per row, so spreadsheets can filter and pivot over all slides at once
+pub fn create_poll_export_csv(slides: &[Slide], slide_index: Option<usize>) -> String {
+    let mut csv = CsvWriter::new();
+    csv.write_row(CSV_HEADER.iter().map(|column| column.to_string()).collect());
+
+    for (index, slide) in slides.iter().enumerate() {
+        if slide_index.is_some_and(|slide_index| slide_index != index) {
+            continue;
+        }
+
+        write_slide_csv_rows(&mut csv, index, slide);
+    }
+
+    return csv.finish();
+}
+
+fn write_slide_csv_rows(csv: &mut CsvWriter, slide_index: usize, slide: &Slide) {
+    // Archived rounds only keep their aggregated results
+    for round in &slide.rounds {
+        let mut row = |category: &str, item: &str, value: String| {
+            csv.write_slide_row(
+                slide_index,
+                slide,
+                round.round_number,
+                None,
+                [category, item, ""],
+                value,
+            );
+        };
+
+        match &round.results {
+            ResultSnapshot::MultipleChoice {
+                answers,
+                answer_counts,
+            } => {
+                for (text, count) in answers.iter().zip(answer_counts.iter()) {
+                    row("option_count", text, count.to_string());
+                }
+            }
+            ResultSnapshot::FreeText { terms } => {
+                for (_, spelling, count) in terms {
+                    row("term", spelling, count.to_string());
+                }
+            }
+            ResultSnapshot::Other(_) => {}
+        }
+
+        row("responses", "", round.num_responses.to_string());
+        for (emoji, count) in get_round_emoji_counts(round) {
+            row("emoji", emoji, count.to_string());
+        }
+    }
+
+    let round_number = slide.get_round_number();
+    let mut row =
+        |player_index: Option<usize>, category: &str, item: &str, detail: &str, value: String| {
+            csv.write_slide_row(
+                slide_index,
+                slide,
+                round_number,
+                player_index,
+                [category, item, detail],
+                value,
+            );
+        };
+
+    match &slide.slide_type {
+        SlideType::MultipleChoice(answers) => {
+            for ((text, is_correct), count) in
+                answers.answers.iter().zip(answers.answer_counts.iter())
+            {
+                row(
+                    None,
+                    "option_count",
+                    text,
+                    if *is_correct { "correct" } else { "" },
+                    count.to_string(),
+                );
+            }
+            for (player_index, answer) in answers.player_answers.iter() {
+                for answer_index in answer {
+                    let text = &answers.answers[*answer_index as usize].0;
+                    row(
+                        Some(*player_index),
+                        "answer",
+                        text,
+                        "",
+                        answer_index.to_string(),
+                    );
+                }
+            }
+        }
+        SlideType::FreeText(answers) => {
+            for term in &answers.word_cloud_terms {
+                row(
+                    None,
+                    "term",
+                    &term.preferred_spelling,
+                    "",
+                    term.count.to_string(),
+                );
+
+                let mut spellings = term.spellings.iter().collect::<Vec<_>>();
+                spellings.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+                for (spelling, count) in spellings {
+                    row(
+                        None,
+                        "spelling",
+                        &term.preferred_spelling,
+                        spelling,
+                        count.to_string(),
+                    );
+                }
+            }
+            for (player_index, answer) in answers.player_answers.iter() {
+                row(Some(*player_index), "answer", answer, "", String::new());
+            }
+        }
+        SlideType::Rating(answers) => {
+            for (rating, count) in answers.rating_counts.iter().enumerate() {
+                row(
+                    None,
+                    "rating_count",
+                    &(rating + 1).to_string(),
+                    "",
+                    count.to_string(),
+                );
+            }
+            for (player_index, rating) in answers.player_answers.iter() {
+                row(Some(*player_index), "answer", "", "", rating.to_string());
+            }
+        }
+        SlideType::NumericEstimate(answers) => {
+            let bucket_width = (answers.max - answers.min) / answers.bucket_counts.len() as f64;
+            for (bucket_index, count) in answers.bucket_counts.iter().enumerate() {
+                let lower = answers.min + bucket_index as f64 * bucket_width;
+                let bucket = format!("{lower}-{}", lower + bucket_width);
+                row(None, "bucket_count", &bucket, "", count.to_string());
+            }
+            for (player_index, answer) in answers.player_answers.iter() {
+                row(Some(*player_index), "answer", "", "", answer.to_string());
+            }
+        }
+        SlideType::Ranking(answers) => {
+            for (option, score) in answers.options.iter().zip(answers.borda_scores.iter()) {
+                row(None, "borda_score", option, "", score.to_string());
+            }
+            for (player_index, ordering) in answers.player_answers.iter() {
+                for (position, option_index) in ordering.iter().enumerate() {
+                    let option = &answers.options[*option_index as usize];
+                    row(
+                        Some(*player_index),
+                        "answer",
+                        option,
+                        "",
+                        (position + 1).to_string(),
+                    );
+                }
+            }
+        }
+        SlideType::QandA(answers) => {
+            for question in &answers.questions {
+                let state = match (question.is_hidden, question.is_answered) {
+                    (true, _) => "hidden",
+                    (false, true) => "answered",
+                    (false, false) => "",
+                };
+                row(
+                    Some(question.player_index),
+                    "question",
+                    &question.text,
+                    state,
+                    question.upvotes.to_string(),
+                );
+            }
+        }
+        SlideType::Undefined | SlideType::Info(_) => {}
+    }
+
+    row(
+        None,
+        "responses",
+        "",
+        "",
+        slide.get_num_responses().to_string(),
+    );
+    for (emoji, count) in get_emoji_counts(slide) {
+        row(None, "emoji", emoji, "", count.to_string());
+    }
+}
+
+struct CsvWriter {
+    csv: String,
+}
+
+impl CsvWriter {
+    fn new() -> Self {
+        return CsvWriter { csv: String::new() };
+    }
+
+    fn write_slide_row(
+        &mut self,
+        slide_index: usize,
+        slide: &Slide,
+        round_number: usize,
+        player_index: Option<usize>,
+        [category, item, detail]: [&str; 3],
+        value: String,
+    ) {
+        self.write_row(vec![
+            (slide_index + 1).to_string(),
+            get_slide_type_key(&slide.slide_type).to_string(),
+            escape_formula(&slide.question),
+            round_number.to_string(),
+            player_index
+                .map(|player_index| player_index.to_string())
+                .unwrap_or_default(),
+            category.to_string(),
+            escape_formula(item),
+            escape_formula(detail),
+            value,
+        ]);
+    }
+
+    fn write_row(&mut self, fields: Vec<String>) {
+        for (index, field) in fields.iter().enumerate() {
+            if index > 0 {
+                self.csv.push(',');
+            }
+
+            if field.contains([',', '"', '\n', '\r']) {
+                self.csv.push('"');
+                self.csv.push_str(&field.replace('"', "\"\""));
+                self.csv.push('"');
+            } else {
+                self.csv.push_str(field);
+            }
+        }
+        self.csv.push_str("\r\n");
+    }
+
+    fn finish(self) -> String {
+        return self.csv;
+    }
+}
+
+// Participant texts starting with one of these would be evaluated as formulas by spreadsheets
+fn escape_formula(text: &str) -> String {
+    if text.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        return format!("'{text}");
+    }
+
+    return text.to_string();
+}
+
+#[cfg(test)]
+mod tests {
+    use smartstring::SmartString;
+
+    use super::*;
+    use crate::slide::FreeTextLiveAnswers;
+
+    #[test]
+    fn formulas_are_escaped() {
+        for formula in ["=1+1", "+1", "-1", "@SUM(A1)", "\t=1", "\r=1"] {
+            assert_eq!(escape_formula(formula), format!("'{formula}"));
+        }
+
+        assert_eq!(escape_formula("1+1="), "1+1=");
+        assert_eq!(escape_formula(""), "");
+    }
+
+    #[test]
+    fn csv_escapes_participant_texts() {
+        let mut ft_answers = FreeTextLiveAnswers::new();
+        ft_answers
+            .submit_answer(0, SmartString::from("=HYPERLINK(\"x\")"), false)
+            .unwrap();
+        ft_answers
+            .submit_answer(1, SmartString::from("a, b"), false)
+            .unwrap();
+        let slide = Slide::new("-Question".to_string(), SlideType::FreeText(ft_answers));
+
+        let csv = create_poll_export_csv(&[slide], None);
+        let rows = csv.split("\r\n").collect::<Vec<_>>();
+
+        assert!(rows
+            .iter()
+            .any(|row| row.contains(r#""'=HYPERLINK(""x"")""#)));
+        assert!(rows.iter().any(|row| row.contains(r#""a, b""#)));
+        assert!(rows[1..]
+            .iter()
+            .filter(|row| !row.is_empty())
+            .all(|row| row.contains(",'-Question,")));
+    }
+}
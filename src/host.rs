@@ -1,7 +1,4 @@
-use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
-};
+use std::{collections::BTreeSet, sync::Mutex};
 
 use axum::{
     extract::{
@@ -16,16 +13,23 @@ use axum_extra::extract::CookieJar;
 use maud::html;
 use serde_json::{json, Value};
 use smartstring::SmartString;
-use tokio::select;
+use tokio::{select, sync::broadcast::error::RecvError};
 
 use crate::{
     app_error::AppError,
-    config::{COLOR_PALETTE, POLL_MAX_MC_ANSWERS, POLL_MAX_SLIDES, STATS_UPDATE_THROTTLE},
+    config::{
+        FREE_TEXT_ANSWER_LIST_SIZE, INFO_MAX_BODY_LENGTH, NUMERIC_MAX_BUCKETS, RATING_MAX_SCALE,
+        RATING_MIN_SCALE,
+    },
     html_page::{self, render_header},
-    live_poll::LivePoll,
-    live_poll_store::{ShortID, LIVE_POLL_STORE},
-    select_language, session_id,
-    slide::{FreeTextLiveAnswers, MultipleChoiceLiveAnswers, Slide, SlideType, WordCloudTerm},
+    live_poll::{Answer, LivePoll, LivePollCommand, LivePollHandle},
+    live_poll_store::{live_poll_store, ShortID},
+    markdown::{render_markdown, MARKDOWN_CLASSES},
+    poll_definition::PollDefinition,
+    report, select_language, self_hosting, session_id,
+    settings::settings,
+    shutdown,
+    slide::{Slide, SlideType},
     static_file,
     svg_icons::SvgIcon,
     wsmessage::WSMessage,
@@ -34,7 +38,9 @@ use crate::{
 pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Response, AppError> {
     let l = select_language(&cookies, &headers);
     let (session_id, cookies) = session_id::get_or_create_session_id(cookies);
-    let poll_is_live = LIVE_POLL_STORE.get_by_session_id(&session_id).is_some();
+    let poll_is_live = live_poll_store().get_by_session_id(&session_id).is_some();
+    let last_report_id = report::get_latest_report_id(&session_id);
+    let join_host_name = self_hosting::get_join_host_name(&headers);
 
     let html = html_page::render_html_page(
         "Svoote - Create Poll",
@@ -42,8 +48,23 @@ pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Res
         html! {
             script src=(static_file::get_path("qrcode.js")) {}
             @if poll_is_live { script { "document.pollAlreadyLive = true;" } }
+            @if let Some(report_id) = last_report_id { script { "document.lastReportUrl = '/report/" (report_id) "';" } }
             div x-data="poll" ."flex-1 flex flex-col" {
                 div ."block px-6 sm:px-14 py-5 flex justify-end bg-slate-900" {
+                    template x-if="isLive" {
+                        div ."mr-5 flex items-center gap-5 text-sm text-slate-300" {
+                            div ."flex items-center gap-3" title=(t!("export_results", locale=l)) {
+                                div ."size-4" { (SvgIcon::Download.render()) }
+                                a ":href"="'/export/' + code + '/csv'" download ."hover:text-white" { "CSV" }
+                                a ":href"="'/export/' + code + '/json'" download ."hover:text-white" { "JSON" }
+                            }
+                            div ."flex items-center gap-3" title=(t!("download_qr_code", locale=l)) {
+                                span { (t!("qr_code", locale=l)) }
+                                a ":href"="'/qr/' + code + '.svg?ec=Q'" download ."hover:text-white" { "SVG" }
+                                a ":href"="'/qr/' + code + '.png?size=1024&ec=Q'" download ."hover:text-white" { "PNG" }
+                            }
+                        }
+                    }
                     button #"start-stop-button"
                         "@click"="if (!isLive) startPoll(); else stopPoll();" ":disabled"="poll.slides.length == 0"
                         ."px-4.5 py-2 flex items-center justify-end gap-1.5 text-sm text-slate-700 font-medium rounded-full cursor-pointer transition-all duration-[200ms] disabled:pointer-events-none hover:shadow-none disabled:shadow-none"
@@ -57,7 +78,20 @@ pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Res
                             { ."size-3 bg-slate-50" {} }
                     }
                 }
+                div x-show="!isLive && reportUrl !== null" x-cloak ."mx-6 sm:mx-14 mt-4 px-4 py-3 flex items-center gap-2 text-sm bg-cyan-50 rounded-lg text-slate-600" {
+                    div ."size-4 shrink-0" { (SvgIcon::BarChart2.render()) }
+                    a ":href"="reportUrl" ."underline hover:text-slate-900" { (t!("view_last_report", locale=l)) }
+                }
+                div x-show="serverRestarting" x-cloak ."mx-6 sm:mx-14 mt-4 px-4 py-3 text-sm bg-orange-100 rounded-lg text-slate-600" {
+                    (t!("server_restarting", locale=l))
+                }
                 div ."hidden [@media_(max-width:520px)]:block mx-6 mb-4 px-4 py-3 text-sm bg-orange-100 rounded-lg text-slate-500" { (t!("screen_size_warning", locale=l)) }
+                div x-show="startPollErrors.length > 0" x-cloak ."mx-6 sm:mx-14 mt-4 px-4 py-3 text-sm bg-orange-100 rounded-lg text-slate-500" {
+                    p ."mb-1 font-medium text-slate-700" { (t!("start_poll_failed", locale=l)) }
+                    template x-for="error in startPollErrors" {
+                        p x-text={ "(error.slideIndex !== null ? '" (t!("slide", locale=l)) " ' + (error.slideIndex + 1) + ', ' : '') + (error.field !== '' ? error.field + ': ' : '') + error.reason" } {}
+                    }
+                }
                 div id="fullscreen-container"
                     "@fullscreenchange"="if (document.fullscreenElement == null) isFullscreen = false; else isFullscreen = true; $dispatch('fontsizechange');"
                     ":class"="'min-w-[520px] flex-1 px-6 sm:px-14 flex flex-col ' + (isFullscreen ? 'bg-slate-700 h-full justify-center' : 'bg-slate-100')"
@@ -77,7 +111,7 @@ pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Res
                                         { (SvgIcon::Settings.render()) }
                                     div x-show="open" x-cloak
                                         "@click.outside"="open = false"
-                                        ."absolute left-0 top-6 w-40 z-20 px-3 py-2 bg-white border rounded-lg shadow-lg"
+                                        ."absolute left-0 top-6 w-56 z-20 px-3 py-2 bg-white border rounded-lg shadow-lg"
                                     {
                                         button ."flex gap-2 items-center text-sm text-red-500 cursor-pointer disabled:cursor-default disabled:text-slate-300"
                                             "@click"="poll.slides.splice(poll.activeSlide, 1); open = false; gotoSlide(poll.activeSlide);"
@@ -86,6 +120,16 @@ pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Res
                                             ."size-4 shrink-0" { (SvgIcon::Trash2.render()) }
                                             (t!("delete_slide_btn_title", locale=l))
                                         }
+                                        hr ."my-2";
+                                        label ."flex gap-2 items-center text-sm text-slate-500" {
+                                            input x-model="poll.enableLeaderboard" "@change"="save()" ":disabled"="isLive" type="checkbox" ."accent-cyan-600";
+                                            (t!("quiz_mode", locale=l))
+                                        }
+                                        p ."ml-6 mt-1 text-xs text-slate-400" { (t!("quiz_mode_explanation", locale=l)) }
+                                        label ."mt-2 flex gap-2 items-center text-sm text-slate-500" {
+                                            input x-model="poll.allowCustomNames" "@change"="save()" ":disabled"="isLive" type="checkbox" ."accent-cyan-600";
+                                            (t!("allow_custom_names", locale=l))
+                                        }
                                     }
                                 }
                             }
@@ -100,9 +144,85 @@ pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Res
                                 }
                             }
                         }
-                        div ."w-[64em] h-[36em] shrink-0 px-[3em] py-[2.5em] flex gap-[3.5em] bg-white border rounded-lg"
+                        div ."relative w-[64em] h-[36em] shrink-0 px-[3em] py-[2.5em] flex gap-[3.5em] bg-white border rounded-lg"
                             ":style"="`font-size: ${fontScale}em;`"
                         {
+                            template x-if="leaderboard !== null" {
+                                div ."absolute inset-0 z-10 px-[3em] py-[2.5em] flex flex-col bg-white rounded-lg" {
+                                    h2 ."mb-[1em] text-[1.5em] text-slate-800 font-semibold" { (t!("leaderboard", locale=l)) }
+                                    template x-for="entry in leaderboard.leaderboard" {
+                                        div ."mb-[0.5em] flex items-center gap-[1em] text-[1.125em]" {
+                                            div ."w-[2em] text-slate-400 font-bold" x-text="entry.rank + '.'" {}
+                                            img ":src"="'/avatar/' + entry.avatarIndex" ."size-[1.75em] rounded-full border";
+                                            div ."flex-1 text-slate-700" x-text="entry.name" {}
+                                            div ."text-slate-600 font-medium" x-text={ "entry.score + ' " (t!("points", locale=l)) "'" } {}
+                                        }
+                                    }
+                                    p x-show="leaderboard.leaderboard.length == 0" ."text-[0.875em] text-slate-500" { (t!("leaderboard_empty", locale=l)) }
+                                }
+                            }
+                            template x-if="isLive && slide.comparison" {
+                                div ."absolute inset-0 z-10 px-[3em] py-[2em] flex flex-col bg-white rounded-lg overflow-y-auto" {
+                                    div ."mb-[1em] flex items-baseline justify-between gap-[1em]" {
+                                        h2 x-text="slide.question" ."text-[1.25em] text-slate-800" {}
+                                        button "@click"="compareRounds(null)" ."size-[1.25em] shrink-0 text-slate-400 hover:text-slate-600 cursor-pointer" { (SvgIcon::X.render()) }
+                                    }
+                                    table ."w-full text-[0.875em] text-slate-700" {
+                                        thead {
+                                            tr ."text-slate-500 text-left" {
+                                                th ."pb-[0.5em] font-medium" {}
+                                                th ."pb-[0.5em] w-[8em] font-medium text-right" x-text={ "'" (t!("round", locale=l)) " ' + slide.comparison.earlierRound + ' (' + slide.comparison.earlierNumResponses + ')'" } {}
+                                                th ."pb-[0.5em] w-[8em] font-medium text-right" x-text={ "'" (t!("round", locale=l)) " ' + slide.comparison.laterRound + ' (' + slide.comparison.laterNumResponses + ')'" } {}
+                                                th ."pb-[0.5em] w-[5em] font-medium text-right" { "Δ" }
+                                            }
+                                        }
+                                        tbody {
+                                            template x-for="row in (slide.comparison.comparison.answers || slide.comparison.comparison.terms)" {
+                                                tr ."border-t" {
+                                                    td ."py-[0.375em]" x-text="row.text" {}
+                                                    td ."py-[0.375em] text-right" x-text="row.before" {}
+                                                    td ."py-[0.375em] text-right" x-text="row.after" {}
+                                                    td ."py-[0.375em] text-right font-medium"
+                                                        ":class"="row.delta > 0 ? 'text-green-600' : (row.delta < 0 ? 'text-rose-600' : 'text-slate-400')"
+                                                        x-text="formatDelta(row.delta)" {}
+                                                }
+                                            }
+                                        }
+                                    }
+                                    template x-if="slide.comparison.comparison.appeared" {
+                                        div ."mt-[1.5em] grid grid-cols-2 gap-[1.5em] text-[0.875em]" {
+                                            div {
+                                                h3 ."mb-[0.5em] text-slate-500 font-medium" { (t!("terms_appeared", locale=l)) }
+                                                div ."flex flex-wrap gap-[0.375em]" {
+                                                    template x-for="term in slide.comparison.comparison.appeared" {
+                                                        span x-text="term" ."px-[0.5em] py-[0.125em] text-green-700 bg-green-50 rounded-full" {}
+                                                    }
+                                                }
+                                            }
+                                            div {
+                                                h3 ."mb-[0.5em] text-slate-500 font-medium" { (t!("terms_disappeared", locale=l)) }
+                                                div ."flex flex-wrap gap-[0.375em]" {
+                                                    template x-for="term in slide.comparison.comparison.disappeared" {
+                                                        span x-text="term" ."px-[0.5em] py-[0.125em] text-rose-700 bg-rose-50 rounded-full" {}
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            template x-if="isLive && slide.resultsHidden" {
+                                div ."absolute inset-0 z-10 px-[3em] py-[2.5em] flex flex-col items-center justify-center gap-[1em] bg-white rounded-lg" {
+                                    p x-text="slide.question" ."text-[1.5em] text-slate-800 text-center" {}
+                                    p x-text={ "slide.numResponses + ' " (t!("responses", locale=l)) "'" } ."text-[1.125em] text-slate-500" {}
+                                    button "@click"="revealResults()"
+                                        ."mt-[0.5em] px-[1.25em] py-[0.5em] flex items-center gap-[0.5em] text-white font-bold bg-cyan-600 rounded-full cursor-pointer hover:bg-cyan-700"
+                                    {
+                                        div ."size-[1.25em]" { (SvgIcon::Eye.render()) }
+                                        (t!("reveal_results", locale=l))
+                                    }
+                                }
+                            }
                             div ."w-full flex-1 flex flex-col" {
                                 template x-if="slide.type == 'mc'" {
                                     div ."relative h-full flex flex-col gap-[1.5em] justify-between" {
@@ -122,13 +242,16 @@ pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Res
                                                             ":disabled"="isLive"
                                                             ."w-full px-[0.25em] py-[0.125em] text-slate-700 bg-transparent outline-hidden"
                                                             ":class"="!isLive && 'focus:ring-2 ring-cyan-600 ring-offset-2 rounded-xs'";
-                                                        //button x-show="!isLive" "@click"="answer.isCorrect = !answer.isCorrect; save()" ":class"="answer.isCorrect ? 'text-green-600' : 'text-slate-300 hover:text-green-600'" ."size-6" { (SvgIcon::CheckSquare.render()) }
+                                                        button x-show="!isLive && poll.enableLeaderboard" "@click"="answer.isCorrect = !answer.isCorrect; save()"
+                                                            ":class"="answer.isCorrect ? 'text-green-600' : 'text-slate-300 hover:text-green-600'"
+                                                            title=(t!("mark_correct_answer", locale=l))
+                                                            ."size-[1.5em] shrink-0 cursor-pointer" { (SvgIcon::Check.render()) }
                                                         button x-show="!isLive" "@click"="slide.mcAnswers.splice(answer_index, 1); save(); $nextTick(() => { if (slide.mcChartType == 'pie') renderPieChart(); });" ."size-[1.5em] text-slate-300 cursor-pointer hover:text-slate-500" { (SvgIcon::Trash2.render()) }
                                                     }
                                                 }
                                                 button
                                                     "@click"={"slide.mcAnswers.push({ text: '', isCorrect: false }); save(); $nextTick(() => $el.previousSibling.children[1].focus()); if (slide.mcChartType == 'pie') renderPieChart();" }
-                                                    ":class"={ "(slide.mcAnswers.length >= " (POLL_MAX_MC_ANSWERS) ") && 'hidden'" }
+                                                    ":class"={ "(slide.mcAnswers.length >= " (settings().poll_max_mc_answers) ") && 'hidden'" }
                                                     ."ml-[1.5em] text-slate-700 underline cursor-pointer"
                                                     x-show="!isLive"
                                                     { (t!("add_answer_btn", locale=l)) }
@@ -136,7 +259,7 @@ pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Res
                                             div x-show="isLive" x-cloak ."sm:translate-x-[1.5em] -translate-y-[1em] flex flex-col items-center" {
                                                 div x-data="qrCode" x-effect="render($el, code)" ."mb-[0.75em] w-[4em] sm:w-[6em]" {}
                                                 div x-text="code !== null ? '#' + code : ''" ."text-[1.25em] text-slate-600 tracking-wide font-bold" {}
-                                                a x-show="code !== null" ."text-center text-[0.75em] text-indigo-500 underline" ":href"="'/p?c=' + code" { "svoote.com" }
+                                                a x-show="code !== null" ."text-center text-[0.75em] text-indigo-500 underline" ":href"="'/p?c=' + code" { (join_host_name) }
                                             }
                                         }
                                         template x-if="slide.mcChartType == 'bar'" {
@@ -187,19 +310,247 @@ pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Res
                                             div x-show="isLive" x-cloak ."sm:translate-x-[1.5em] -translate-y-[1em] flex flex-col items-center" {
                                                 div x-data="qrCode" x-effect="render($el, code)" ."mb-[0.75em] w-[6em]" {}
                                                 div x-text="code !== null ? '#' + code : ''" ."text-[1.25em] text-slate-600 tracking-wide font-bold" {}
-                                                a x-show="code !== null" ."text-center text-[0.75em] text-indigo-500 underline" ":href"="'/p?c=' + code" { "svoote.com" }
+                                                a x-show="code !== null" ."text-center text-[0.75em] text-indigo-500 underline" ":href"="'/p?c=' + code" { (join_host_name) }
                                             }
                                         }
-                                        div ."relative flex-1 mx-auto w-full" #"word-cloud"
+                                        div x-show="!showFTAnswerList" ."relative flex-1 mx-auto w-full" #"word-cloud"
                                             "@resize.window"="$nextTick(() => { renderWordCloud(); })"
                                             "@fontsizechange.window"="setTimeout(() => { renderWordCloud(); }, 500);"
                                             "@slidechange.window"="setTimeout(() => { renderWordCloud(); }, 500);"
                                             { }
+                                        div x-show="showFTAnswerList" ."flex-1 overflow-y-auto" {
+                                            template x-for="answer in (slide.stats !== null ? slide.stats.answers : [])" {
+                                                div ."mb-[0.5em] flex items-center gap-[0.75em]" {
+                                                    img ":src"="'/avatar/' + answer.avatarIndex" ."size-[1.5em] rounded-full border";
+                                                    div ."text-[0.875em] text-slate-500 font-medium" x-text="answer.name" {}
+                                                    div ."text-slate-700" x-text="answer.text" {}
+                                                }
+                                            }
+                                            div x-show="slide.stats !== null && slide.stats.numAnswers > slide.stats.answers.length"
+                                                x-text={ "slide.stats !== null ? '+' + (slide.stats.numAnswers - slide.stats.answers.length) + ' " (t!("more_answers", locale=l)) "' : ''" }
+                                                ."text-[0.875em] text-slate-500" {}
+                                        }
+                                        button x-show="isLive && slide.stats !== null && slide.stats.answers.length > 0"
+                                            "@click"="showFTAnswerList = !showFTAnswerList; if (!showFTAnswerList) $nextTick(() => { renderWordCloud(); });"
+                                            ."self-end text-[0.75em] text-slate-500 underline cursor-pointer"
+                                            x-text={ "showFTAnswerList ? '" (t!("show_word_cloud", locale=l)) "' : '" (t!("show_answers_with_names", locale=l)) "'" } {}
                                         div x-show="(slide.stats !== null ? slide.stats.terms : []).length == 0"
                                             ."absolute size-full inset-0 -z-10 p-[3em] flex items-center justify-center gap-[0.75em] text-slate-500 text-[0.875em]"
                                             { div ."size-[1em]" { (SvgIcon::Edit3.render()) } (t!("open_ended_explanation", locale=l)) }
                                     }
                                 }
+                                template x-if="slide.type == 'rating'" {
+                                    div ."h-full flex flex-col gap-[1em] justify-between" {
+                                        div ."flex gap-[1em]" {
+                                            div ."flex-1" {
+                                                div ."absolute pointer-events-none px-[0.55em] text-[1.25em] text-slate-300" x-show="slide.question.trim() === ''" { (t!("question_placeholder", locale=l)) }
+                                                span x-init="$el.innerText = slide.question"
+                                                    "@input"="slide.question = $el.innerText; save();"
+                                                    ":contenteditable"="!isLive"
+                                                    ."block mb-[0.75em] px-[0.5em] text-[1.25em] text-slate-800 bg-transparent outline-hidden"
+                                                    ":class"="!isLive && 'ring-1 ring-slate-200 ring-offset-4 rounded-xs focus:ring-2 focus:ring-cyan-600'" {}
+                                                p x-show="slide.stats !== null && slide.stats.numRatings > 0" ."px-[0.5em] text-[0.875em] text-slate-500"
+                                                    x-text={ "slide.stats === null || slide.stats.numRatings == 0 ? '' : '"
+                                                        (t!("mean", locale=l)) " ' + slide.stats.mean.toFixed(2) + ' · "
+                                                        (t!("median", locale=l)) " ' + slide.stats.median + ' · "
+                                                        (t!("standard_deviation", locale=l)) " ' + slide.stats.standardDeviation.toFixed(2) + ' · n = ' + slide.stats.numRatings" } {}
+                                            }
+                                            div x-show="isLive" x-cloak ."sm:translate-x-[1.5em] -translate-y-[1em] flex flex-col items-center" {
+                                                div x-data="qrCode" x-effect="render($el, code)" ."mb-[0.75em] w-[4em] sm:w-[6em]" {}
+                                                div x-text="code !== null ? '#' + code : ''" ."text-[1.25em] text-slate-600 tracking-wide font-bold" {}
+                                                a x-show="code !== null" ."text-center text-[0.75em] text-indigo-500 underline" ":href"="'/p?c=' + code" { (join_host_name) }
+                                            }
+                                        }
+                                        div ."flex-1 max-h-[12em] flex items-stretch justify-center gap-[0.75em]" {
+                                            template x-for="rating in slide.ratingScale" {
+                                                div ."w-[4em] flex flex-col" {
+                                                    div ."flex-1 flex flex-col justify-end items-center" {
+                                                        div ."relative w-[3em] transition-all duration-400 shadow-lg" .(settings().color_class(2))
+                                                            ":style"="`height: ${ statsBarHeight(slide, rating - 1) }%;`"
+                                                        {
+                                                            div x-text="slide.stats !== null ? slide.stats.counts[rating - 1] : 0"
+                                                                ."absolute w-full text-slate-600 text-center font-medium -translate-y-[1.75em]" {}
+                                                        }
+                                                    }
+                                                    div x-text="rating" ."mt-[0.5em] text-center text-slate-600 font-medium" {}
+                                                }
+                                            }
+                                        }
+                                        div ."flex justify-between text-[0.875em] text-slate-500" {
+                                            span x-text="slide.ratingLowLabel" {}
+                                            span x-text="slide.ratingHighLabel" {}
+                                        }
+                                    }
+                                }
+                                template x-if="slide.type == 'rank'" {
+                                    div ."h-full flex flex-col gap-[1em]" {
+                                        div ."flex gap-[1em]" {
+                                            div ."flex-1" {
+                                                div ."absolute pointer-events-none px-[0.55em] text-[1.25em] text-slate-300" x-show="slide.question.trim() === ''" { (t!("question_placeholder", locale=l)) }
+                                                span x-init="$el.innerText = slide.question"
+                                                    "@input"="slide.question = $el.innerText; save();"
+                                                    ":contenteditable"="!isLive"
+                                                    ."block mb-[0.75em] px-[0.5em] text-[1.25em] text-slate-800 bg-transparent outline-hidden"
+                                                    ":class"="!isLive && 'ring-1 ring-slate-200 ring-offset-4 rounded-xs focus:ring-2 focus:ring-cyan-600'" {}
+                                                template x-if="!isLive" {
+                                                    div {
+                                                        template x-for="(answer, answer_index) in slide.mcAnswers" {
+                                                            div ."mb-[0.375em] flex items-center gap-[0.5em]" {
+                                                                div x-text="(answer_index + 1) + '.'" ."ml-[0.5em] text-[0.875em] text-slate-400" {}
+                                                                input type="text" x-model="answer.text" "@input"="save();"
+                                                                    "@keydown.enter"="let next = $el.parentElement.nextSibling; if (next.tagName == 'DIV') next.children[1].focus(); else next.click();"
+                                                                    ."w-full px-[0.25em] py-[0.125em] text-slate-700 bg-transparent outline-hidden focus:ring-2 ring-cyan-600 ring-offset-2 rounded-xs";
+                                                                button "@click"="slide.mcAnswers.splice(answer_index, 1); save();" ."size-[1.5em] text-slate-300 cursor-pointer hover:text-slate-500" { (SvgIcon::Trash2.render()) }
+                                                            }
+                                                        }
+                                                        button
+                                                            "@click"="slide.mcAnswers.push({ text: '', isCorrect: false }); save(); $nextTick(() => $el.previousSibling.children[1].focus());"
+                                                            ":class"={ "(slide.mcAnswers.length >= " (settings().poll_max_mc_answers) ") && 'hidden'" }
+                                                            ."ml-[1.5em] text-slate-700 underline cursor-pointer"
+                                                            { (t!("add_option_btn", locale=l)) }
+                                                    }
+                                                }
+                                                p x-show="isLive && slide.stats !== null" ."px-[0.5em] text-[0.875em] text-slate-500"
+                                                    x-text={ "slide.stats === null ? '' : 'n = ' + slide.stats.numAnswers" } {}
+                                            }
+                                            div x-show="isLive" x-cloak ."sm:translate-x-[1.5em] -translate-y-[1em] flex flex-col items-center" {
+                                                div x-data="qrCode" x-effect="render($el, code)" ."mb-[0.75em] w-[4em] sm:w-[6em]" {}
+                                                div x-text="code !== null ? '#' + code : ''" ."text-[1.25em] text-slate-600 tracking-wide font-bold" {}
+                                                a x-show="code !== null" ."text-center text-[0.75em] text-indigo-500 underline" ":href"="'/p?c=' + code" { (join_host_name) }
+                                            }
+                                        }
+                                        template x-if="isLive" {
+                                            div ."flex-1 flex flex-col gap-[0.5em]" {
+                                                template x-for="(optionIndex, position) in rankingOrder(slide)" {
+                                                    div ."flex items-center gap-[0.75em]" {
+                                                        div ."w-[1.5em] text-slate-400 font-bold" x-text="(position + 1) + '.'" {}
+                                                        div ."w-[12em] text-slate-700 truncate"
+                                                            x-text={ "slide.mcAnswers[optionIndex].text != '' ? slide.mcAnswers[optionIndex].text : '" (t!("answer", locale=l)) " ' + (optionIndex + 1)" } {}
+                                                        div ."flex-1 h-[1.25em]" {
+                                                            div ."h-full transition-all duration-400 shadow-lg" .(settings().color_class(5))
+                                                                ":style"="`width: ${ rankingBarWidth(slide, optionIndex) }%;`" {}
+                                                        }
+                                                        div ."w-[9em] text-[0.875em] text-slate-500"
+                                                            x-text={ "slide.stats !== null && slide.stats.averagePositions[optionIndex] !== null ? '"
+                                                                (t!("average_position", locale=l)) " ' + formatNumber(slide.stats.averagePositions[optionIndex]) : ''" } {}
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                                template x-if="slide.type == 'info'" {
+                                    div ."h-full flex gap-[1em]" {
+                                        div ."flex-1 min-w-0 flex flex-col" {
+                                            div ."absolute pointer-events-none px-[0.55em] text-[1.5em] text-slate-300" x-show="slide.question.trim() === ''" { (t!("info_title_placeholder", locale=l)) }
+                                            span x-init="$el.innerText = slide.question"
+                                                "@input"="slide.question = $el.innerText; save();"
+                                                ":contenteditable"="!isLive"
+                                                ."block mb-[0.75em] px-[0.5em] text-[1.5em] text-slate-800 font-semibold bg-transparent outline-hidden"
+                                                ":class"="!isLive && 'ring-1 ring-slate-200 ring-offset-4 rounded-xs focus:ring-2 focus:ring-cyan-600'" {}
+                                            template x-if="!isLive" {
+                                                div ."flex-1 min-h-0 flex gap-[1em]" {
+                                                    textarea x-model="slide.infoBody" "@input"="save(); renderMarkdownPreview(slide);"
+                                                        maxlength=(INFO_MAX_BODY_LENGTH)
+                                                        placeholder=(t!("info_body_placeholder", locale=l))
+                                                        ."flex-1 min-w-0 px-[0.5em] py-[0.25em] text-[0.875em] text-slate-700 font-mono border rounded-xs resize-none outline-hidden focus:ring-2 focus:ring-cyan-600" {}
+                                                    div x-init="renderMarkdownPreview(slide)" x-html="slide.infoPreview"
+                                                        ."flex-1 min-w-0 overflow-y-auto px-[0.5em] text-slate-700" .(MARKDOWN_CLASSES) {}
+                                                }
+                                            }
+                                            template x-if="isLive" {
+                                                div x-html="slide.stats !== null ? slide.stats.html : ''"
+                                                    ."flex-1 overflow-y-auto px-[0.5em] text-slate-700" .(MARKDOWN_CLASSES) {}
+                                            }
+                                        }
+                                        div x-show="isLive" x-cloak ."sm:translate-x-[1.5em] -translate-y-[1em] flex flex-col items-center" {
+                                            div x-data="qrCode" x-effect="render($el, code)" ."mb-[0.75em] w-[4em] sm:w-[6em]" {}
+                                            div x-text="code !== null ? '#' + code : ''" ."text-[1.25em] text-slate-600 tracking-wide font-bold" {}
+                                            a x-show="code !== null" ."text-center text-[0.75em] text-indigo-500 underline" ":href"="'/p?c=' + code" { (join_host_name) }
+                                        }
+                                    }
+                                }
+                                template x-if="slide.type == 'qa'" {
+                                    div ."h-full flex flex-col gap-[1em]" {
+                                        div ."flex gap-[1em]" {
+                                            div ."flex-1" {
+                                                div ."absolute pointer-events-none px-[0.55em] text-[1.25em] text-slate-300" x-show="slide.question.trim() === ''" { (t!("qa_prompt_placeholder", locale=l)) }
+                                                span x-init="$el.innerText = slide.question"
+                                                    "@input"="slide.question = $el.innerText; save();"
+                                                    ":contenteditable"="!isLive"
+                                                    ."block mb-[0.75em] px-[0.5em] text-[1.25em] text-slate-800 bg-transparent outline-hidden"
+                                                    ":class"="!isLive && 'ring-1 ring-slate-200 ring-offset-4 rounded-xs focus:ring-2 focus:ring-cyan-600'" {}
+                                            }
+                                            div x-show="isLive" x-cloak ."sm:translate-x-[1.5em] -translate-y-[1em] flex flex-col items-center" {
+                                                div x-data="qrCode" x-effect="render($el, code)" ."mb-[0.75em] w-[4em] sm:w-[6em]" {}
+                                                div x-text="code !== null ? '#' + code : ''" ."text-[1.25em] text-slate-600 tracking-wide font-bold" {}
+                                                a x-show="code !== null" ."text-center text-[0.75em] text-indigo-500 underline" ":href"="'/p?c=' + code" { (join_host_name) }
+                                            }
+                                        }
+                                        div ."flex-1 overflow-y-auto px-[0.25em] py-[0.25em] flex flex-col gap-[0.5em]" {
+                                            template x-for="question in (slide.stats !== null ? slide.stats.questions : [])" ":key"="question.index" {
+                                                div ."px-[0.75em] py-[0.5em] flex items-center gap-[0.75em] border rounded-lg transition"
+                                                    ":class"="{ 'opacity-50': question.isHidden || question.isAnswered, 'ring-2 ring-cyan-600': question.isPinned }"
+                                                {
+                                                    div ."w-[3em] shrink-0 text-center text-slate-600 font-bold" x-text="'▲ ' + question.upvotes" {}
+                                                    div ."flex-1 text-slate-700 break-words" x-text="question.text" {}
+                                                    button "@click"="moderateQuestion(question.index, 'answer')" title=(t!("mark_answered", locale=l))
+                                                        ":class"="question.isAnswered ? 'text-green-600' : 'text-slate-300 hover:text-slate-500'"
+                                                        ."size-[1.25em] shrink-0 cursor-pointer" { (SvgIcon::Check.render()) }
+                                                    button "@click"="moderateQuestion(question.index, 'pin')" title=(t!("pin_question", locale=l))
+                                                        ":class"="question.isPinned ? 'text-cyan-600' : 'text-slate-300 hover:text-slate-500'"
+                                                        ."size-[1.25em] shrink-0 cursor-pointer" { (SvgIcon::Bookmark.render()) }
+                                                    button "@click"="moderateQuestion(question.index, 'hide')" title=(t!("hide_question", locale=l))
+                                                        ":class"="question.isHidden ? 'text-red-500' : 'text-slate-300 hover:text-slate-500'"
+                                                        ."size-[1.25em] shrink-0 cursor-pointer" { (SvgIcon::EyeOff.render()) }
+                                                }
+                                            }
+                                            div x-show="slide.stats === null || slide.stats.questions.length == 0"
+                                                ."flex-1 flex items-center justify-center gap-[0.75em] text-slate-500 text-[0.875em]"
+                                                { div ."size-[1em]" { (SvgIcon::MessageCircle.render()) } (t!("qa_explanation", locale=l)) }
+                                        }
+                                    }
+                                }
+                                template x-if="slide.type == 'num'" {
+                                    div ."h-full flex flex-col gap-[1em] justify-between" {
+                                        div ."flex gap-[1em]" {
+                                            div ."flex-1" {
+                                                div ."absolute pointer-events-none px-[0.55em] text-[1.25em] text-slate-300" x-show="slide.question.trim() === ''" { (t!("question_placeholder", locale=l)) }
+                                                span x-init="$el.innerText = slide.question"
+                                                    "@input"="slide.question = $el.innerText; save();"
+                                                    ":contenteditable"="!isLive"
+                                                    ."block mb-[0.75em] px-[0.5em] text-[1.25em] text-slate-800 bg-transparent outline-hidden"
+                                                    ":class"="!isLive && 'ring-1 ring-slate-200 ring-offset-4 rounded-xs focus:ring-2 focus:ring-cyan-600'" {}
+                                                p x-show="slide.stats !== null && slide.stats.numAnswers > 0" ."px-[0.5em] text-[0.875em] text-slate-500"
+                                                    x-text={ "slide.stats === null || slide.stats.numAnswers == 0 ? '' : '"
+                                                        (t!("min", locale=l)) " ' + formatNumber(slide.stats.min) + ' · "
+                                                        (t!("max", locale=l)) " ' + formatNumber(slide.stats.max) + ' · "
+                                                        (t!("mean", locale=l)) " ' + formatNumber(slide.stats.mean) + ' · "
+                                                        (t!("median", locale=l)) " ' + formatNumber(slide.stats.median) + ' · n = ' + slide.stats.numAnswers" } {}
+                                            }
+                                            div x-show="isLive" x-cloak ."sm:translate-x-[1.5em] -translate-y-[1em] flex flex-col items-center" {
+                                                div x-data="qrCode" x-effect="render($el, code)" ."mb-[0.75em] w-[4em] sm:w-[6em]" {}
+                                                div x-text="code !== null ? '#' + code : ''" ."text-[1.25em] text-slate-600 tracking-wide font-bold" {}
+                                                a x-show="code !== null" ."text-center text-[0.75em] text-indigo-500 underline" ":href"="'/p?c=' + code" { (join_host_name) }
+                                            }
+                                        }
+                                        div ."flex-1 max-h-[12em] flex items-stretch justify-center gap-[0.25em]" {
+                                            template x-for="bucketIndex in Math.max(1, slide.numericBuckets)" {
+                                                div ."flex-1 max-w-[5em] flex flex-col" {
+                                                    div ."flex-1 flex flex-col justify-end items-center" {
+                                                        div ."relative w-full transition-all duration-400 shadow-lg" .(settings().color_class(3))
+                                                            ":style"="`height: ${ statsBarHeight(slide, bucketIndex - 1) }%;`"
+                                                        {
+                                                            div x-text="slide.stats !== null ? slide.stats.counts[bucketIndex - 1] : 0"
+                                                                ."absolute w-full text-slate-600 text-center font-medium -translate-y-[1.75em]" {}
+                                                        }
+                                                    }
+                                                    div x-text="numericBucketLabel(slide, bucketIndex - 1)" ."mt-[0.5em] text-[0.625em] text-center text-slate-500 whitespace-nowrap" {}
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
                                 p x-show="poll.slides.length == 0" x-cloak ."absolute inset-0 px-6 size-full flex justify-center items-center text-slate-500 text-[0.875em]"
                                     { (t!("no_slides_notice", locale=l)) }
                             }
@@ -214,7 +565,7 @@ pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Res
                                     ":class"="slide.type == 'mc' && 'ring-2'"
                                 {
                                     div ."size-4 p-0.5 text-slate-100 rounded-xs"
-                                        ":class"={ "slide.type == 'mc' ? '" (COLOR_PALETTE[0]) "' : 'bg-slate-400'" }
+                                        ":class"={ "slide.type == 'mc' ? '" (settings().color_class(0)) "' : 'bg-slate-400'" }
                                         { (SvgIcon::BarChart2.render()) }
                                     "Multiple Choice"
                                 }
@@ -223,12 +574,62 @@ pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Res
                                     ."w-full px-2 py-1.5 flex items-center gap-2 text-slate-500 text-sm rounded ring-cyan-600 transition-all duration-100 cursor-pointer hover:bg-slate-100"
                                     ":class"="slide.type == 'ft' && 'ring-2'"
                                 {
-                                    //div ."size-4 p-0.5 text-slate-100 rounded-xs" .(COLOR_PALETTE[1]) { (SvgIcon::Edit3.render()) }
+                                    //div ."size-4 p-0.5 text-slate-100 rounded-xs" .(settings().color_class(1)) { (SvgIcon::Edit3.render()) }
                                     div ."size-4 p-0.5 text-slate-100 rounded-xs"
-                                        ":class"={ "slide.type == 'ft' ? '" (COLOR_PALETTE[1]) "' : 'bg-slate-400'" }
+                                        ":class"={ "slide.type == 'ft' ? '" (settings().color_class(1)) "' : 'bg-slate-400'" }
                                         { (SvgIcon::Edit3.render()) }
                                     (t!("open_ended_question", locale=l))
                                 }
+                                button
+                                    "@click"="slide.type = 'rating'; save();"
+                                    ."w-full px-2 py-1.5 flex items-center gap-2 text-slate-500 text-sm rounded ring-cyan-600 transition-all duration-100 cursor-pointer hover:bg-slate-100"
+                                    ":class"="slide.type == 'rating' && 'ring-2'"
+                                {
+                                    div ."size-4 p-0.5 text-slate-100 rounded-xs"
+                                        ":class"={ "slide.type == 'rating' ? '" (settings().color_class(2)) "' : 'bg-slate-400'" }
+                                        { (SvgIcon::Star.render()) }
+                                    (t!("rating_scale", locale=l))
+                                }
+                                button
+                                    "@click"="slide.type = 'num'; save();"
+                                    ."w-full px-2 py-1.5 flex items-center gap-2 text-slate-500 text-sm rounded ring-cyan-600 transition-all duration-100 cursor-pointer hover:bg-slate-100"
+                                    ":class"="slide.type == 'num' && 'ring-2'"
+                                {
+                                    div ."size-4 p-0.5 text-slate-100 rounded-xs"
+                                        ":class"={ "slide.type == 'num' ? '" (settings().color_class(3)) "' : 'bg-slate-400'" }
+                                        { (SvgIcon::Hash.render()) }
+                                    (t!("numeric_estimate", locale=l))
+                                }
+                                button
+                                    "@click"="slide.type = 'rank'; save();"
+                                    ."w-full px-2 py-1.5 flex items-center gap-2 text-slate-500 text-sm rounded ring-cyan-600 transition-all duration-100 cursor-pointer hover:bg-slate-100"
+                                    ":class"="slide.type == 'rank' && 'ring-2'"
+                                {
+                                    div ."size-4 p-0.5 text-slate-100 rounded-xs"
+                                        ":class"={ "slide.type == 'rank' ? '" (settings().color_class(5)) "' : 'bg-slate-400'" }
+                                        { (SvgIcon::List.render()) }
+                                    (t!("ranking", locale=l))
+                                }
+                                button
+                                    "@click"="slide.type = 'qa'; save();"
+                                    ."w-full px-2 py-1.5 flex items-center gap-2 text-slate-500 text-sm rounded ring-cyan-600 transition-all duration-100 cursor-pointer hover:bg-slate-100"
+                                    ":class"="slide.type == 'qa' && 'ring-2'"
+                                {
+                                    div ."size-4 p-0.5 text-slate-100 rounded-xs"
+                                        ":class"={ "slide.type == 'qa' ? '" (settings().color_class(4)) "' : 'bg-slate-400'" }
+                                        { (SvgIcon::MessageCircle.render()) }
+                                    (t!("audience_qa", locale=l))
+                                }
+                                button
+                                    "@click"="slide.type = 'info'; save(); $nextTick(() => renderMarkdownPreview(slide));"
+                                    ."w-full px-2 py-1.5 flex items-center gap-2 text-slate-500 text-sm rounded ring-cyan-600 transition-all duration-100 cursor-pointer hover:bg-slate-100"
+                                    ":class"="slide.type == 'info' && 'ring-2'"
+                                {
+                                    div ."size-4 p-0.5 text-slate-100 rounded-xs"
+                                        ":class"={ "slide.type == 'info' ? '" (settings().color_class(0)) "' : 'bg-slate-400'" }
+                                        { (SvgIcon::Info.render()) }
+                                    (t!("info_slide", locale=l))
+                                }
                             }
                             hr ."mx-3 my-5";
                             div x-show="slide.type == 'mc'" {
@@ -253,6 +654,63 @@ pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Res
                                     (t!("allow_multiple_answers", locale=l))
                                 }
                             }
+                            div x-show="slide.type == 'rating'" {
+                                h2 ."mb-3 px-3 text-sm text-slate-500 font-medium" { (t!("other_options", locale=l)) }
+                                label ."mx-3 mb-3 flex gap-3 items-center justify-between text-sm text-slate-500" {
+                                    (t!("rating_scale_max", locale=l))
+                                    select "x-model.number"="slide.ratingScale" "@change"="save()" ":disabled"="isLive" ."px-2 py-1 border rounded" {
+                                        @for scale_max in RATING_MIN_SCALE..=RATING_MAX_SCALE {
+                                            option value=(scale_max) { "1 – " (scale_max) }
+                                        }
+                                    }
+                                }
+                                label ."mx-3 mb-3 block text-sm text-slate-500" {
+                                    (t!("rating_low_label", locale=l))
+                                    input type="text" x-model="slide.ratingLowLabel" "@input"="save()" ":disabled"="isLive"
+                                        ."mt-1 w-full px-2 py-1 text-slate-700 border rounded outline-hidden focus:ring-2 focus:ring-cyan-600";
+                                }
+                                label ."mx-3 block text-sm text-slate-500" {
+                                    (t!("rating_high_label", locale=l))
+                                    input type="text" x-model="slide.ratingHighLabel" "@input"="save()" ":disabled"="isLive"
+                                        ."mt-1 w-full px-2 py-1 text-slate-700 border rounded outline-hidden focus:ring-2 focus:ring-cyan-600";
+                                }
+                            }
+                            div x-show="slide.type == 'num'" {
+                                h2 ."mb-3 px-3 text-sm text-slate-500 font-medium" { (t!("other_options", locale=l)) }
+                                div ."mx-3 mb-3 flex gap-3" {
+                                    label ."flex-1 block text-sm text-slate-500" {
+                                        (t!("numeric_min", locale=l))
+                                        input type="number" "x-model.number"="slide.numericMin" "@input"="save()" ":disabled"="isLive"
+                                            ."mt-1 w-full px-2 py-1 text-slate-700 border rounded outline-hidden focus:ring-2 focus:ring-cyan-600";
+                                    }
+                                    label ."flex-1 block text-sm text-slate-500" {
+                                        (t!("numeric_max", locale=l))
+                                        input type="number" "x-model.number"="slide.numericMax" "@input"="save()" ":disabled"="isLive"
+                                            ."mt-1 w-full px-2 py-1 text-slate-700 border rounded outline-hidden focus:ring-2 focus:ring-cyan-600";
+                                    }
+                                }
+                                label ."mx-3 flex gap-3 items-center justify-between text-sm text-slate-500" {
+                                    (t!("numeric_buckets", locale=l))
+                                    input type="number" min="1" max=(NUMERIC_MAX_BUCKETS) "x-model.number"="slide.numericBuckets" "@input"="save()" ":disabled"="isLive"
+                                        ."w-20 px-2 py-1 text-slate-700 border rounded outline-hidden focus:ring-2 focus:ring-cyan-600";
+                                }
+                            }
+                            div x-show="['mc', 'ft', 'rating', 'num', 'rank'].includes(slide.type)" {
+                                h2 ."mt-8 mb-3 px-3 text-sm text-slate-500 font-medium" { (t!("voting", locale=l)) }
+                                label ."mx-5 flex gap-3 items-center text-sm text-slate-500" {
+                                    input x-model="slide.allowAnswerChanges" "@change"="save()" ":disabled"="isLive" type="checkbox" ."accent-cyan-600";
+                                    (t!("allow_answer_changes", locale=l))
+                                }
+                                h2 ."mt-8 mb-3 px-3 text-sm text-slate-500 font-medium" { (t!("results", locale=l)) }
+                                label ."mx-5 mb-3 flex gap-3 items-center text-sm text-slate-500" {
+                                    input x-model="slide.hideResults" "@change"="save()" ":disabled"="isLive" type="checkbox" ."accent-cyan-600";
+                                    (t!("hide_results", locale=l))
+                                }
+                                label x-show="slide.hideResults" ."mx-5 flex gap-3 items-center text-sm text-slate-500" {
+                                    input x-model="slide.shareResults" "@change"="save()" ":disabled"="isLive" type="checkbox" ."accent-cyan-600";
+                                    (t!("share_results", locale=l))
+                                }
+                            }
                         }
                     }
                     //@if cfg!(debug_assertions) { button "@click"="runDemo()" { "Run demo" } }
@@ -298,7 +756,7 @@ pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Res
                                 title=(t!("delete_slide_btn_title", locale=l))
                                 { (SvgIcon::Trash2.render()) }
                             button "@click"="poll.slides.splice(poll.slides.length, 0, createSlide('mc')); $nextTick(() => { gotoSlide(poll.slides.length - 1) });"
-                                ":disabled"={ "isLive || poll.slides.length >= " (POLL_MAX_SLIDES) }
+                                ":disabled"={ "isLive || poll.slides.length >= " (settings().poll_max_slides) }
                                 ."-translate-x-1 size-6 cursor-pointer disabled:cursor-default"
                                 ":class"="isFullscreen ? 'disabled:text-slate-500' : 'disabled:text-slate-300'"
                                 title=(t!("add_slide_btn_title", locale=l)) { (SvgIcon::Plus.render()) }
@@ -321,7 +779,74 @@ pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Res
                         }
                     }*/
                     div ."h-12 mx-6 sm:mx-14 mt-2 mb-8 grid grid-cols-3 items-center gap-4" { // The fixed height stops ugly re-layout when a reaction smiley is first sent
-                        div { }
+                        div {
+                            template x-if="isLive && poll.slides[poll.activeSlide].type != 'info' && votingState.slideIndex == poll.activeSlide" {
+                                div ."flex items-center gap-2 text-sm" ":class"="isFullscreen ? 'text-slate-300' : 'text-slate-500'" {
+                                    button "@click"="setVotingOpen(!votingState.isOpen)"
+                                        ."p-2 size-8 rounded-full shadow-xs cursor-pointer hover:shadow-none"
+                                        ":class"="isFullscreen ? 'bg-slate-300 hover:bg-slate-100 text-slate-700' : 'bg-slate-100 hover:bg-slate-200'"
+                                        ":title"={ "votingState.isOpen ? '" (t!("close_voting", locale=l)) "' : '" (t!("open_voting", locale=l)) "'" }
+                                    {
+                                        template x-if="votingState.isOpen" { div { (SvgIcon::Unlock.render()) } }
+                                        template x-if="!votingState.isOpen" { div { (SvgIcon::Lock.render()) } }
+                                    }
+                                    template x-if="votingCountdown !== null" {
+                                        div ."flex items-center gap-1.5 font-mono font-bold" {
+                                            div ."size-4" { (SvgIcon::Clock.render()) }
+                                            span x-text="formatCountdown(votingCountdown)" {}
+                                        }
+                                    }
+                                    template x-if="votingCountdown === null && votingState.isOpen" {
+                                        div ."flex items-center gap-1" title=(t!("start_countdown", locale=l)) {
+                                            div ."size-4" { (SvgIcon::Clock.render()) }
+                                            @for seconds in [30, 60, 120] {
+                                                button "@click"={ "startCountdown(" (seconds) ")" }
+                                                    ."px-1.5 text-xs rounded-sm cursor-pointer"
+                                                    ":class"="isFullscreen ? 'hover:bg-slate-600' : 'hover:bg-slate-100'"
+                                                    { (format_countdown(seconds)) }
+                                            }
+                                        }
+                                    }
+                                    template x-if="!votingState.isOpen" {
+                                        span { (t!("voting_closed", locale=l)) }
+                                    }
+                                    div x-data="{ open: false }" ."relative" {
+                                        button "@click"="open = !open"
+                                            ."p-2 size-8 rounded-full shadow-xs cursor-pointer hover:shadow-none"
+                                            ":class"="isFullscreen ? 'bg-slate-300 hover:bg-slate-100 text-slate-700' : 'bg-slate-100 hover:bg-slate-200'"
+                                            title=(t!("reset_slide", locale=l))
+                                            { (SvgIcon::Refresh.render()) }
+                                        div x-show="open" x-cloak
+                                            "@click.outside"="open = false"
+                                            ."absolute left-0 bottom-10 w-64 z-20 px-3 py-2 text-slate-500 bg-white border rounded-lg shadow-lg"
+                                        {
+                                            p ."mb-2 text-xs" { (t!("reset_slide_explanation", locale=l)) }
+                                            button "@click"="resetSlide(); open = false;"
+                                                ."flex gap-2 items-center text-sm text-red-500 cursor-pointer"
+                                            {
+                                                ."size-4 shrink-0" { (SvgIcon::Refresh.render()) }
+                                                (t!("reset_slide", locale=l))
+                                            }
+                                        }
+                                    }
+                                    template x-if="poll.slides[poll.activeSlide].round > 1" {
+                                        span x-text={ "'" (t!("round", locale=l)) " ' + poll.slides[poll.activeSlide].round" } {}
+                                    }
+                                    template x-if="poll.slides[poll.activeSlide].round > 1 && ['mc', 'ft'].includes(poll.slides[poll.activeSlide].type)" {
+                                        select "@change"="compareRounds($event.target.value === '' ? null : Number($event.target.value))"
+                                            ."px-1 py-0.5 text-xs bg-transparent border rounded cursor-pointer"
+                                            title=(t!("compare_rounds", locale=l))
+                                        {
+                                            option value="" ":selected"="poll.slides[poll.activeSlide].comparisonRound == null" { (t!("compare_rounds", locale=l)) }
+                                            template x-for="round in poll.slides[poll.activeSlide].round - 1" {
+                                                option ":value"="round" ":selected"="poll.slides[poll.activeSlide].comparisonRound == round"
+                                                    x-text={ "'" (t!("compare_with_round", locale=l)) " ' + round" } {}
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
                         div ."flex justify-center items-center gap-5" {
                             button ."p-2 size-8 rounded-full shadow-xs cursor-pointer hover:shadow-none disabled:pointer-events-none disabled:text-slate-400"
                                 ":class"="isFullscreen ? 'bg-slate-300 hover:bg-slate-100' : 'bg-slate-100 hover:bg-slate-200'"
@@ -369,159 +894,81 @@ pub async fn get_host_page(cookies: CookieJar, headers: HeaderMap) -> Result<Res
     return Ok((cookies, html).into_response());
 }
 
+fn create_emoji_counts_ws_message(slide_index: usize, slide: &Slide) -> WSMessage {
+    return WSMessage {
+        cmd: SmartString::from("setEmojiCounts"),
+        data: json!({
+            "slideIndex": slide_index,
+            "emojis": {
+                "heart": slide.heart_emojis,
+                "thumbsUp": slide.thumbs_up_emojis,
+                "thumbsDown": slide.thumbs_down_emojis,
+                "smileyFace": slide.smiley_face_emojis,
+                "sadFace": slide.sad_face_emojis,
+            },
+        }),
+    };
+}
+
+fn format_countdown(seconds: usize) -> String {
+    return format!("{}:{:02}", seconds / 60, seconds % 60);
+}
+
 pub async fn post_start_poll(cookies: CookieJar, body: String) -> Result<Response, AppError> {
     let (session_id, _cookies) = session_id::get_or_create_session_id(cookies);
 
-    let poll = serde_json::from_str::<serde_json::Value>(&body)
-        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    if shutdown::is_shutting_down() {
+        return Err(AppError::ServiceUnavailable(
+            "The server is restarting, please try again in a moment".to_string(),
+        ));
+    }
 
-    //let enable_leaderboard = poll["enableLeaderboard"].as_bool().unwrap_or(false);
-    //let allow_custom_names = poll["allowCustomNames"].as_bool().unwrap_or(false);
+    let poll = PollDefinition::parse(&body)?;
 
-    let (poll_id, _live_poll) = match LIVE_POLL_STORE.get_by_session_id(&session_id) {
-        Some((poll_id, live_poll)) => (poll_id, live_poll),
+    let poll_id = match live_poll_store().get_by_session_id(&session_id) {
+        Some((poll_id, _live_poll)) => poll_id,
         None => {
-            let mut slides = Vec::new();
-
-            for slide in poll["slides"].as_array().ok_or(AppError::BadRequest(
-                "Poll needs to contain a 'slides' array".to_string(),
-            ))? {
-                match slide["type"].as_str().ok_or(AppError::BadRequest(
-                    "type field needs to be a string".to_string(),
-                ))? {
-                    "mc" => {
-                        let answers: Vec<(String, bool)> = slide["mcAnswers"]
-                            .as_array()
-                            .ok_or(AppError::BadRequest(
-                                "mcAnswers must be an array".to_string(),
-                            ))?
-                            .into_iter()
-                            .map(|mc_answer| {
-                                (
-                                    mc_answer["text"].as_str().unwrap_or_default().to_string(),
-                                    mc_answer["isCorrect"].as_bool().unwrap_or(false),
-                                )
-                            })
-                            .collect();
-
-                        slides.push(Slide {
-                            question: slide["question"]
-                                .as_str()
-                                .ok_or(AppError::BadRequest(
-                                    "Question field missing for slide".to_string(),
-                                ))?
-                                .to_string(),
-                            slide_type: SlideType::MultipleChoice(MultipleChoiceLiveAnswers {
-                                answer_counts: std::iter::repeat(0usize)
-                                    .take(answers.len())
-                                    .collect(),
-                                answers: answers.clone(),
-                                player_answers: Vec::new(),
-                                allow_multiple_answers: slide["allowMultipleMCAnswers"]
-                                    .as_bool()
-                                    .unwrap_or(false),
-                            }),
-                            player_scores: Vec::new(),
-                            player_emojis: Vec::new(),
-                            heart_emojis: 0,
-                            thumbs_up_emojis: 0,
-                            thumbs_down_emojis: 0,
-                            smiley_face_emojis: 0,
-                            sad_face_emojis: 0,
-                        });
-                    }
-                    "ft" => {
-                        /*let answers: Vec<SmartString<Compact>> = item["ftAnswers"]
-                        .as_array()
-                        .ok_or(AppError::BadRequest(
-                            "mcAnswers must be an array".to_string(),
-                        ))?
-                        .into_iter()
-                        .map(|ft_answer| {
-                            SmartString::from(ft_answer["text"].as_str().unwrap_or_default())
-                        })
-                        .collect();*/
-
-                        slides.push(Slide {
-                            question: slide["question"]
-                                .as_str()
-                                .ok_or(AppError::BadRequest(
-                                    "Question field missing for slide".to_string(),
-                                ))?
-                                .to_string(),
-                            slide_type: SlideType::FreeText(FreeTextLiveAnswers {
-                                //correct_answers: answers,
-                                player_answers: Vec::new(),
-                                word_cloud_terms: Vec::new(),
-                                max_term_count: 1usize,
-                            }),
-                            player_scores: Vec::new(),
-                            player_emojis: Vec::new(),
-                            heart_emojis: 0,
-                            thumbs_up_emojis: 0,
-                            thumbs_down_emojis: 0,
-                            smiley_face_emojis: 0,
-                            sad_face_emojis: 0,
-                        });
-                    }
-                    _ => slides.push(Slide {
-                        question: String::new(),
-                        slide_type: SlideType::Undefined,
-                        player_scores: Vec::new(),
-                        player_emojis: Vec::new(),
-                        heart_emojis: 0,
-                        thumbs_up_emojis: 0,
-                        thumbs_down_emojis: 0,
-                        smiley_face_emojis: 0,
-                        sad_face_emojis: 0,
-                    }),
-                }
-            }
-
-            if slides.len() == 0 {
-                slides.push(Slide {
-                    question: String::new(),
-                    slide_type: SlideType::Undefined,
-                    player_scores: Vec::new(),
-                    player_emojis: Vec::new(),
-                    heart_emojis: 0,
-                    thumbs_up_emojis: 0,
-                    thumbs_down_emojis: 0,
-                    smiley_face_emojis: 0,
-                    sad_face_emojis: 0,
-                });
-            }
-
-            let (poll_id, live_poll) = LivePoll::orchestrate(slides, session_id)?;
+            let (poll_id, _live_poll) = LivePoll::orchestrate(
+                poll.create_slides(),
+                session_id,
+                poll.enable_leaderboard,
+                poll.allow_custom_names,
+            )?;
 
-            live_poll
-                .lock()
-                .unwrap()
-                .start_poll_channel_sender
-                .take()
-                .map(|signal| {
-                    let _ = signal.send(());
-                });
-
-            (poll_id, live_poll)
+            poll_id
         }
     };
 
     return Ok(poll_id.to_string().into_response());
 }
 
+// Preview for info slides while editing, so the host sees exactly what participants will see
+pub async fn post_render_markdown(body: String) -> Result<Response, AppError> {
+    if body.chars().count() > INFO_MAX_BODY_LENGTH {
+        return Err(AppError::BadRequest(format!(
+            "Must not be longer than {INFO_MAX_BODY_LENGTH} characters"
+        )));
+    }
+
+    return Ok(render_markdown(&body).into_response());
+}
+
 pub async fn post_stop_poll(
     cookies: CookieJar,
     Path(poll_id): Path<ShortID>,
 ) -> Result<Response, AppError> {
-    let live_poll = LIVE_POLL_STORE.get(poll_id).ok_or(AppError::NotFound)?;
+    let live_poll = live_poll_store().get(poll_id).ok_or(AppError::NotFound)?;
     let (session_id, _cookies) = session_id::get_or_create_session_id(cookies);
-    session_id::assert_equal_ids(&session_id, &live_poll.lock().unwrap().host_session_id)?;
+    session_id::assert_equal_ids(&session_id, &live_poll.host_session_id)?;
 
-    let exit_channel = live_poll.lock().unwrap().exit_poll_channel_sender.clone();
-    let _ = exit_channel.send(()).await;
+    // Fails only if the poll already ended on its own, its report is stored in that case as well
+    let _ = live_poll.exit().await;
 
-    return Ok("Exited successfully".into_response());
+    if report::are_reports_enabled() {
+        return Ok(format!("/report/{}", live_poll.report_id).into_response());
+    }
+
+    return Ok(String::new().into_response());
 }
 
 pub async fn host_socket(
@@ -529,40 +976,40 @@ pub async fn host_socket(
     Path(poll_id): Path<ShortID>,
     cookies: CookieJar,
 ) -> Result<Response, AppError> {
-    let live_poll = LIVE_POLL_STORE.get(poll_id).ok_or(AppError::NotFound)?;
+    let live_poll = live_poll_store().get(poll_id).ok_or(AppError::NotFound)?;
     let (session_id, _cookies) = session_id::get_or_create_session_id(cookies);
-    session_id::assert_equal_ids(&session_id, &live_poll.lock().unwrap().host_session_id)?;
+    session_id::assert_equal_ids(&session_id, &live_poll.host_session_id)?;
 
     return Ok(ws.on_upgrade(|socket| handle_host_socket(socket, live_poll)));
 }
 
-async fn handle_host_socket(mut socket: WebSocket, live_poll: Arc<Mutex<LivePoll>>) {
-    let (
-        mut stats_updated_receiver,
-        mut slide_change_notification_receiver,
-        mut emoji_receiver,
-        slide_index_sender,
-    ) = {
-        let live_poll = live_poll.lock().unwrap();
-
-        (
-            live_poll
-                .stats_change_notification_channel_receiver
-                .resubscribe(),
-            live_poll
-                .slide_change_notification_channel_receiver
-                .resubscribe(),
-            live_poll.emoji_channel_receiver.resubscribe(),
-            live_poll.set_slide_index_channel_sender.clone(),
-        )
-    };
+async fn handle_host_socket(mut socket: WebSocket, live_poll: LivePollHandle) {
+    let mut stats_updated_receiver = live_poll
+        .channels
+        .stats_change_notification_channel_sender
+        .subscribe();
+    let mut slide_change_notification_receiver = live_poll
+        .channels
+        .slide_change_notification_channel_sender
+        .subscribe();
+    let mut emoji_receiver = live_poll.channels.emoji_channel_sender.subscribe();
+    let mut leaderboard_receiver = live_poll.channels.leaderboard_channel_sender.subscribe();
+    let mut voting_state_receiver = live_poll.channels.voting_state_channel_sender.subscribe();
+    let mut slide_update_receiver = live_poll.channels.slide_update_channel_sender.subscribe();
 
-    let mut last_sent_timepoint = tokio::time::Instant::now() - STATS_UPDATE_THROTTLE;
-    let mut throttled_msg = None;
+    // The archived round the host compares the live results of a slide with, as (slide_index, round)
+    let mut comparison_round: Option<(usize, usize)> = None;
+    let stats_update_throttle = settings().stats_update_throttle();
+    let mut last_sent_timepoint = tokio::time::Instant::now() - stats_update_throttle;
+    // Slides whose stats changed since the last update, the stats are only built when they are sent
+    let mut pending_stats_slide_indices = BTreeSet::new();
+    let mut are_all_stats_pending = false;
+    let mut needs_resync = false;
 
     loop {
-        let throttled_msg_sent_timeout = if throttled_msg.is_some() {
-            STATS_UPDATE_THROTTLE
+        let has_pending_stats = are_all_stats_pending || !pending_stats_slide_indices.is_empty();
+        let stats_update_timeout = if has_pending_stats {
+            stats_update_throttle
                 .checked_sub(tokio::time::Instant::now() - last_sent_timepoint)
                 .unwrap_or(tokio::time::Duration::from_secs(0))
         } else {
@@ -570,13 +1017,50 @@ async fn handle_host_socket(mut socket: WebSocket, live_poll: Arc<Mutex<LivePoll
         };
 
         select! {
+            _ = shutdown::requested() => {
+                let _ = socket.send(shutdown::create_server_restarting_ws_message().into()).await;
+                let _ = socket.send(Message::Close(None)).await;
+                return;
+            }
             msg = socket.recv() => {
                 if let Some(Ok(msg)) = msg {
                     if let Some(msg) = WSMessage::parse(msg) {
+                        let slide_index = msg.data["slideIndex"].as_u64().unwrap_or(u64::MAX) as usize;
+
                         match msg.cmd.as_ref() {
                             "gotoSlide" => {
                                 let slide_index = msg.data["slideIndex"].as_u64().unwrap_or(0u64) as usize;
-                                let _ = slide_index_sender.send(slide_index).await;
+                                live_poll.send(LivePollCommand::GotoSlide(slide_index)).await;
+                            }
+                            "setVotingOpen" => {
+                                let is_open = msg.data["isOpen"].as_bool().unwrap_or(true);
+                                live_poll.send(LivePollCommand::SetVotingOpen(slide_index, is_open)).await;
+                            }
+                            "startCountdown" => {
+                                let seconds = msg.data["seconds"].as_u64().unwrap_or(0);
+                                if seconds > 0 {
+                                    live_poll.send(LivePollCommand::StartCountdown(slide_index, tokio::time::Duration::from_secs(seconds))).await;
+                                }
+                            }
+                            "revealResults" => {
+                                live_poll.send(LivePollCommand::RevealResults(slide_index)).await;
+                            }
+                            "compareRounds" => {
+                                comparison_round = msg.data["round"].as_u64().map(|round| (slide_index, round as usize));
+
+                                let _ = live_poll.channels.stats_change_notification_channel_sender.send(slide_index);
+                            }
+                            "resetSlide" => {
+                                live_poll.send(LivePollCommand::ResetSlide(slide_index)).await;
+                            }
+                            "moderateQuestion" => {
+                                let question_index = msg.data["questionIndex"].as_u64().unwrap_or(u64::MAX) as usize;
+                                let action = msg.data["action"].as_str().unwrap_or("").to_string();
+
+                                live_poll.send(LivePollCommand::ModerateQuestion { slide_index, question_index, action }).await;
+                            }
+                            "showLeaderboard" => {
+                                live_poll.send(LivePollCommand::ShowLeaderboard).await;
                             }
                             _ => {}
                         }
@@ -586,91 +1070,123 @@ async fn handle_host_socket(mut socket: WebSocket, live_poll: Arc<Mutex<LivePoll
                 }
             }
             slide_index = stats_updated_receiver.recv() => {
-                if let Ok(slide_index) = slide_index {
-                    if slide_index < live_poll.lock().unwrap().slides.len() {
-                        let stats = match &live_poll.lock().unwrap().slides[slide_index].slide_type {
-                            SlideType::MultipleChoice(answers) => {
-                                json!({ "counts": answers.answer_counts })
-                            }
-                            SlideType::FreeText(answers) => {
-                                json!({
-                                    "terms": answers.word_cloud_terms
-                                        .iter()
-                                        .map(|term| (term.preferred_spelling.clone(), term.count))
-                                        .collect::<Vec<_>>(),
-                                    "maxCount": answers.max_term_count,
-                                })
-                            }
-                            _ => Value::Null
-                        };
+                match slide_index {
+                    Ok(slide_index) => {
+                        pending_stats_slide_indices.insert(slide_index);
+                    }
+                    // Only the slides that changed are unknown, so the stats of all slides are sent
+                    Err(RecvError::Lagged(_)) => are_all_stats_pending = true,
+                    Err(RecvError::Closed) => return,
+                }
+            }
+            slide_index = slide_change_notification_receiver.recv() => {
+                match slide_index {
+                    Ok(slide_index) => {
+                        let msgs = live_poll.read(move |live_poll| {
+                            live_poll.slides.get(slide_index).map(|slide| {
+                                (live_poll.create_voting_state_ws_message(slide_index), create_emoji_counts_ws_message(slide_index, slide))
+                            })
+                        }).await;
 
+                        match msgs {
+                            Ok(Some((voting_state_msg, msg))) => {
+                                let _  = socket.send(voting_state_msg.into()).await;
+                                let _  = socket.send(msg.into()).await;
+                            }
+                            Ok(None) => {}
+                            Err(_) => return,
+                        }
+                    }
+                    Err(RecvError::Lagged(_)) => needs_resync = true,
+                    Err(RecvError::Closed) => return,
+                }
+            }
+            emoji = emoji_receiver.recv() => {
+                match emoji {
+                    Ok((slide_index, emoji)) => {
                         let msg = WSMessage {
-                            cmd: SmartString::from("updateStats"),
+                            cmd: SmartString::from("newEmoji"),
                             data: json!({
                                 "slideIndex": slide_index,
-                                "stats": stats,
+                                "emoji": emoji,
                             })
                         }.into();
 
-                        if throttled_msg.is_none() &&
-                            tokio::time::Instant::now() - last_sent_timepoint > STATS_UPDATE_THROTTLE {
-                            let _  = socket.send(msg).await;
-                            last_sent_timepoint = tokio::time::Instant::now();
-                            throttled_msg = None;
-                        } else {
-                            throttled_msg = Some(msg);
+                        let _  = socket.send(msg).await;
+                    }
+                    // Missed emojis can't be shown anymore, the counts of the current slide catch up on them
+                    Err(RecvError::Lagged(_)) => {
+                        let Ok(msg) = live_poll.read(|live_poll| {
+                            create_emoji_counts_ws_message(live_poll.current_slide_index, live_poll.get_current_slide())
+                        }).await else {
+                            return;
+                        };
+                        let _  = socket.send(msg.into()).await;
+                    }
+                    Err(RecvError::Closed) => return,
+                }
+            }
+            slide_index = slide_update_receiver.recv() => {
+                match slide_index {
+                    Ok(slide_index) => {
+                        let msg = live_poll.read(move |live_poll| {
+                            live_poll.slides.get(slide_index).map(|slide| create_emoji_counts_ws_message(slide_index, slide))
+                        }).await;
+
+                        match msg {
+                            Ok(Some(msg)) => { let _  = socket.send(msg.into()).await; }
+                            Ok(None) => {}
+                            Err(_) => return,
                         }
                     }
-                } else {
-                    return;
+                    Err(RecvError::Lagged(_)) => needs_resync = true,
+                    Err(RecvError::Closed) => return,
                 }
             }
-            slide_index = slide_change_notification_receiver.recv() => {
-                if let Ok(slide_index) = slide_index {
-                    if slide_index < live_poll.lock().unwrap().slides.len() {
-                        let msg = {
-                            let slide = &live_poll.lock().unwrap().slides[slide_index];
-                            WSMessage {
-                                cmd: SmartString::from("setEmojiCounts"),
-                                data: json!({
-                                    "slideIndex": slide_index,
-                                    "emojis": {
-                                        "heart": slide.heart_emojis,
-                                        "thumbsUp": slide.thumbs_up_emojis,
-                                        "thumbsDown": slide.thumbs_down_emojis,
-                                        "smileyFace": slide.smiley_face_emojis,
-                                        "sadFace": slide.sad_face_emojis,
-                                    },
-                                })
-                            }.into()
+            slide_index = voting_state_receiver.recv() => {
+                match slide_index {
+                    Ok(slide_index) => {
+                        let Ok(msg) = live_poll.read(move |live_poll| live_poll.create_voting_state_ws_message(slide_index)).await else {
+                            return;
                         };
-
-                        let _  = socket.send(msg).await;
+                        let _  = socket.send(msg.into()).await;
                     }
-                } else {
-                    return;
+                    Err(RecvError::Lagged(_)) => needs_resync = true,
+                    Err(RecvError::Closed) => return,
                 }
             }
-            emoji = emoji_receiver.recv() => {
-                if let Ok((slide_index, emoji)) = emoji {
-                    let msg = WSMessage {
-                        cmd: SmartString::from("newEmoji"),
-                        data: json!({
-                            "slideIndex": slide_index,
-                            "emoji": emoji,
-                        })
-                    }.into();
-
-                    let _  = socket.send(msg).await;
-                } else {
-                    return;
+            leaderboard = leaderboard_receiver.recv() => {
+                match leaderboard {
+                    Ok(leaderboard) => {
+                        let _  = socket.send(leaderboard.create_ws_message(None).into()).await;
+                    }
+                    // The newer leaderboards are still queued, the next receive gets them
+                    Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => return,
                 }
             }
-            _ = tokio::time::sleep(throttled_msg_sent_timeout) => {
-                if let Some(msg) = throttled_msg.take() {
-                    let _  = socket.send(msg).await;
-                    last_sent_timepoint = tokio::time::Instant::now();
+            _ = tokio::time::sleep(stats_update_timeout), if has_pending_stats => {
+                let slide_indices = std::mem::take(&mut pending_stats_slide_indices);
+                let are_all_stats_pending = std::mem::take(&mut are_all_stats_pending);
+
+                let Ok(msgs) = live_poll.read(move |live_poll| {
+                    let slide_indices = if are_all_stats_pending {
+                        (0..live_poll.slides.len()).collect()
+                    } else {
+                        slide_indices
+                    };
+
+                    slide_indices
+                        .into_iter()
+                        .filter_map(|slide_index| create_stats_ws_message(live_poll, slide_index, comparison_round))
+                        .collect::<Vec<_>>()
+                }).await else {
+                    return;
+                };
+                for msg in msgs {
+                    let _  = socket.send(msg.into()).await;
                 }
+                last_sent_timepoint = tokio::time::Instant::now();
             }
             _ = tokio::time::sleep(tokio::time::Duration::from_secs(15)) => {
                 if socket.send(Message::Ping(Vec::new())).await.is_err() {
@@ -678,12 +1194,120 @@ async fn handle_host_socket(mut socket: WebSocket, live_poll: Arc<Mutex<LivePoll
                 }
             }
         };
+
+        // Updates were dropped for this socket, so the host gets the full state instead.
+        // Subscribing again first makes sure no update falls between the snapshot and the receivers.
+        if needs_resync {
+            needs_resync = false;
+            stats_updated_receiver = stats_updated_receiver.resubscribe();
+            slide_change_notification_receiver = slide_change_notification_receiver.resubscribe();
+            emoji_receiver = emoji_receiver.resubscribe();
+            leaderboard_receiver = leaderboard_receiver.resubscribe();
+            voting_state_receiver = voting_state_receiver.resubscribe();
+            slide_update_receiver = slide_update_receiver.resubscribe();
+
+            let Ok(msgs) = live_poll
+                .read(move |live_poll| create_host_snapshot(live_poll, comparison_round))
+                .await
+            else {
+                return;
+            };
+            for msg in msgs {
+                let _ = socket.send(msg.into()).await;
+            }
+
+            // The snapshot already contains the newest stats of all slides
+            pending_stats_slide_indices.clear();
+            are_all_stats_pending = false;
+            last_sent_timepoint = tokio::time::Instant::now();
+        }
     }
 }
 
+// The results of all slides plus the voting state and emojis of the current one
+fn create_host_snapshot(
+    live_poll: &LivePoll,
+    comparison_round: Option<(usize, usize)>,
+) -> Vec<WSMessage> {
+    let current_slide_index = live_poll.current_slide_index;
+
+    let mut msgs = (0..live_poll.slides.len())
+        .filter_map(|slide_index| create_stats_ws_message(live_poll, slide_index, comparison_round))
+        .collect::<Vec<_>>();
+    msgs.push(live_poll.create_voting_state_ws_message(current_slide_index));
+    msgs.push(create_emoji_counts_ws_message(
+        current_slide_index,
+        live_poll.get_current_slide(),
+    ));
+
+    return msgs;
+}
+
+// Runs inside the task of the poll, so the whole message is built from one consistent state
+fn create_stats_ws_message(
+    live_poll: &LivePoll,
+    slide_index: usize,
+    comparison_round: Option<(usize, usize)>,
+) -> Option<WSMessage> {
+    let slide = live_poll.slides.get(slide_index)?;
+
+    let stats = match &slide.slide_type {
+        SlideType::Info(content) => {
+            json!({ "html": content.html })
+        }
+        SlideType::QandA(answers) => {
+            json!({ "questions": answers.create_questions_json(None) })
+        }
+        _ if !slide.are_results_visible() => Value::Null,
+        SlideType::FreeText(answers) => {
+            // Sent on every stats update, so only the first answers are listed with their names
+            let mut stats = slide.create_results_json();
+            stats["numAnswers"] = answers.player_answers.len().into();
+            stats["answers"] = answers
+                .player_answers
+                .iter()
+                .take(FREE_TEXT_ANSWER_LIST_SIZE)
+                .map(|(player_index, answer)| {
+                    let player = live_poll.get_player(*player_index);
+                    json!({
+                        "name": player.get_name(),
+                        "avatarIndex": player.get_avatar_index(),
+                        "text": answer,
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into();
+            stats
+        }
+        _ => slide.create_results_json(),
+    };
+
+    let comparison = match comparison_round {
+        Some((comparison_slide_index, round))
+            if comparison_slide_index == slide_index && slide.are_results_visible() =>
+        {
+            slide.compare_rounds(round, None)
+        }
+        _ => None,
+    };
+
+    return Some(WSMessage {
+        cmd: SmartString::from("updateStats"),
+        data: json!({
+            "slideIndex": slide_index,
+            "stats": stats,
+            "resultsHidden": !slide.are_results_visible(),
+            "numResponses": slide.get_num_responses(),
+            "round": slide.get_round_number(),
+            "comparison": comparison,
+        }),
+    });
+}
+
+// Lets fake participants answer the current free text slide, to test the word cloud under load
 pub async fn get_bombardft(Path(poll_id): Path<ShortID>) -> Result<Response, AppError> {
     if cfg!(debug_assertions) {
-        let live_poll = LIVE_POLL_STORE.get(poll_id).ok_or(AppError::NotFound)?;
+        let live_poll = live_poll_store().get(poll_id).ok_or(AppError::NotFound)?;
 
         tokio::spawn(async move {
             let mut i = 0;
@@ -691,32 +1315,28 @@ pub async fn get_bombardft(Path(poll_id): Path<ShortID>) -> Result<Response, App
                 i += 1;
                 let _ = tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
 
-                let mut live_poll = live_poll.lock().unwrap();
-                if let SlideType::FreeText(answers) = &mut live_poll.get_current_slide().slide_type
-                {
-                    use rand::Rng;
-                    let mut rng = rand::thread_rng();
-
-                    answers.word_cloud_terms.push(WordCloudTerm {
-                        lowercase_text: SmartString::from(i.to_string()),
-                        count: 1,
-                        preferred_spelling: SmartString::from(i.to_string()),
-                        highest_spelling_count: 1,
-                        spellings: HashMap::new(),
-                    });
-
-                    let random_int = rng.gen_range::<usize, _>(0..20);
-                    if random_int < answers.word_cloud_terms.len() {
-                        answers.word_cloud_terms[random_int].count += 1;
-                        if answers.word_cloud_terms[random_int].count > answers.max_term_count {
-                            answers.max_term_count = answers.word_cloud_terms[random_int].count;
-                        }
-                    }
+                use rand::Rng;
+                let random_int = rand::thread_rng().gen_range::<usize, _>(0..40);
+                let answer = if random_int < 20 { random_int } else { i };
 
-                    let _ = live_poll
-                        .stats_change_notification_channel_sender
-                        .send(live_poll.current_slide_index);
-                }
+                let player_session_id = uuid::Uuid::new_v4();
+                let Ok(Some(_)) = live_poll.join(player_session_id).await else {
+                    return;
+                };
+                let Ok(slide_index) = live_poll
+                    .read(|live_poll| live_poll.current_slide_index)
+                    .await
+                else {
+                    return;
+                };
+
+                let _ = live_poll
+                    .submit_answer(
+                        player_session_id,
+                        slide_index,
+                        Answer::FreeText(SmartString::from(answer.to_string())),
+                    )
+                    .await;
             }
         });
 
@@ -737,26 +1357,33 @@ static STATS: Mutex<Option<WebsiteStats>> = Mutex::new(None);
 
 pub async fn get_stats() -> Result<Response, AppError> {
     use tokio::time::{Duration, Instant};
-    let mut stats = STATS.lock().unwrap();
 
-    if stats.is_none()
-        || stats
-            .as_ref()
-            .is_some_and(|stats| (Instant::now() - stats.timepoint) >= Duration::from_secs(5))
-    {
-        let polls = LIVE_POLL_STORE.polls.lock().unwrap();
+    let is_outdated = STATS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_none_or(|stats| (Instant::now() - stats.timepoint) >= Duration::from_secs(5));
 
-        *stats = Some(WebsiteStats {
+    // The polls are asked one after another, without holding the lock
+    if is_outdated {
+        let polls = live_poll_store().get_all();
+
+        let mut num_participants = 0;
+        for (_poll_id, live_poll) in &polls {
+            num_participants += live_poll
+                .read(|live_poll| live_poll.players.len())
+                .await
+                .unwrap_or(0);
+        }
+
+        *STATS.lock().unwrap() = Some(WebsiteStats {
             timepoint: Instant::now(),
             num_live_polls: polls.len(),
-            num_participants: polls
-                .iter()
-                .map(|p| p.1.lock().unwrap().players.len())
-                .sum::<usize>(),
+            num_participants,
         });
     }
 
-    if let Some(stats) = &*stats {
+    if let Some(stats) = &*STATS.lock().unwrap() {
         return Ok(html_page::render_html_page(
             "Svoote Live Stats",
             "en",
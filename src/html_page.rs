@@ -1,11 +1,7 @@
 use maud::{html, Markup, DOCTYPE};
 use smartstring::{Compact, SmartString};
 
-use crate::{
-    config::{COLOR_PALETTE, COLOR_PALETTE_RGB},
-    static_file,
-    svg_icons::SvgIcon,
-};
+use crate::{self_hosting, settings::settings, static_file, svg_icons::SvgIcon};
 
 pub fn render_html_page(
     title: &str,
@@ -13,6 +9,8 @@ pub fn render_html_page(
     main_content: maud::Markup,
     render_footer: bool,
 ) -> maud::Markup {
+    let legal_pages = self_hosting::get_legal_pages();
+
     html! {
         (DOCTYPE)
         html lang=(l) {
@@ -21,18 +19,20 @@ pub fn render_html_page(
                 meta name="viewport" content="width=device-width, initial-scale=1.0";
                 meta http-equiv="X-UA-Compatible" content="ie=edge";
                 meta name="description" content=(t!("html_description_meta", locale=l));
-                meta name="google-site-verification" content="43Qm55o4cXLfJSwfb_7gvXUUFYiYzs7zvKqUX46pk1c";
+                @if legal_pages.is_none() {
+                    meta name="google-site-verification" content="43Qm55o4cXLfJSwfb_7gvXUUFYiYzs7zvKqUX46pk1c";
+                }
                 title { (title) }
                 @if cfg!(debug_assertions) { script src=(static_file::get_path("tailwind_4_dev.js")) {} }
                 link rel="stylesheet" href=(static_file::get_path("bundle.css"));
                 script defer src=(static_file::get_path("app.js")) {}
                 link rel="icon" type="image/png" href="/img/svoote_icon_t.png";
-                @if let Ok(domain) = std::env::var("PLAUSIBLE_DOMAIN") {
+                @if let (None, Ok(domain)) = (legal_pages, std::env::var("PLAUSIBLE_DOMAIN")) {
                     @if !cfg!(debug_assertions) { script defer data-domain=(domain) src="https://plausible.io/js/script.js" {} }
                 }
                 script {
-                    "let colorPalette = [" @for color in COLOR_PALETTE { "'" (color) "'," } "];"
-                    "let colorPaletteRGB = [" @for color in COLOR_PALETTE_RGB { "'" (color) "'," } "];"
+                    "let colorPalette = [" @for color in &settings().color_palette { "'" (color) "'," } "];"
+                    "let colorPaletteRGB = [" @for color in &settings().color_palette_rgb { "'" (color) "'," } "];"
                 }
             }
             body ."group min-h-screen flex flex-col text-slate-700" {
@@ -49,11 +49,26 @@ pub fn render_html_page(
 
                             a href="/" ."hover:underline" { (t!("home", locale=l)) }
                             a href="/host" ."hover:underline" { (t!("create_poll", locale=l)) }
-                            a href="/data-privacy" ."hover:underline" { (t!("data_privacy", locale=l)) }
-                            a href="/terms-of-service" ."hover:underline" { (t!("terms_of_service", locale=l)) }
-                            a href="/cookie-policy" ."hover:underline" { (t!("cookie_policy", locale=l)) }
-                            a href="/manage-cookies" ."hover:underline" { (t!("manage_cookies", locale=l)) }
-                            a href="/contact" ."hover:underline" { (t!("contact", locale=l)) }
+                            @match legal_pages {
+                                None => {
+                                    a href="/data-privacy" ."hover:underline" { (t!("data_privacy", locale=l)) }
+                                    a href="/terms-of-service" ."hover:underline" { (t!("terms_of_service", locale=l)) }
+                                    a href="/cookie-policy" ."hover:underline" { (t!("cookie_policy", locale=l)) }
+                                    a href="/manage-cookies" ."hover:underline" { (t!("manage_cookies", locale=l)) }
+                                    a href="/contact" ."hover:underline" { (t!("contact", locale=l)) }
+                                }
+                                Some(legal_pages) => {
+                                    @if legal_pages.privacy_policy.is_some() {
+                                        a href="/data-privacy" ."hover:underline" { (t!("data_privacy", locale=l)) }
+                                    }
+                                    @if legal_pages.terms_of_service.is_some() {
+                                        a href="/terms-of-service" ."hover:underline" { (t!("terms_of_service", locale=l)) }
+                                    }
+                                    @if legal_pages.imprint.is_some() {
+                                        a href="/imprint" ."hover:underline" { (t!("imprint", locale=l)) }
+                                    }
+                                }
+                            }
                         }
                         div ."flex justify-center gap-4" {
                             div ."flex gap-1.5 items-center" { div ."size-3.5" { (SvgIcon::Globe.render()) } (t!("language_preference", locale=l)) }
@@ -62,26 +77,29 @@ pub fn render_html_page(
                         }
                     }
                 }
-                div x-cloak x-data="{ cookiesAccepted: false }" x-show="!cookiesAccepted"
-                    x-init="let local = JSON.parse(localStorage.getItem('cookiesAccepted')); cookiesAccepted = local !== null ? local : false;"
-                    ."fixed max-w-2xl mx-8 bottom-12 right-0 sm:right-8 px-7 py-4 bg-white border border-cyan-600 shadow-xl"
-                {
-                    h1 ."mb-3 flex items-center gap-2 text-slate-700 text-xl font-medium"
-                        { div ."size-5" { (SvgIcon::Cookie.render()) } "Cookies" }
-                    p ."mb-4 text-slate-500 text-sm" {
-                        (t!("cookie_banner_text", locale=l)) a href="/cookie-policy" ."underline" { "Cookie Policy"} "."
-                    }
-                    div class="mb-6 sm:mb-0 flex gap-2" {
-                        input type="checkbox" id="disabled-switch" class="peer hidden" disabled {}
-                        label for="disabled-switch"
-                            class="w-10 h-6 flex items-center bg-gray-300 rounded-full p-1"
-                            { div class="w-4 h-4 bg-gray-500 rounded-full shadow-md translate-x-4" {} }
-                            span class="text-gray-500" { (t!("necessary_cookies", locale=l)) }
-                    }
-                    div ."flex flex-wrap sm:justify-end gap-4" {
-                        button "@click"="cookiesAccepted = true; localStorage.setItem('cookiesAccepted', 'true');"
-                            ."w-full sm:w-auto px-5 py-2 bg-cyan-700 text-white text-sm font-semibold shadow-xl cursor-pointer hover:bg-cyan-600"
-                            { (t!("cookie_banner_accept", locale=l)) }
+                // Self-hosted instances only set the strictly necessary cookies and need no banner
+                @if legal_pages.is_none() {
+                    div x-cloak x-data="{ cookiesAccepted: false }" x-show="!cookiesAccepted"
+                        x-init="let local = JSON.parse(localStorage.getItem('cookiesAccepted')); cookiesAccepted = local !== null ? local : false;"
+                        ."fixed max-w-2xl mx-8 bottom-12 right-0 sm:right-8 px-7 py-4 bg-white border border-cyan-600 shadow-xl"
+                    {
+                        h1 ."mb-3 flex items-center gap-2 text-slate-700 text-xl font-medium"
+                            { div ."size-5" { (SvgIcon::Cookie.render()) } "Cookies" }
+                        p ."mb-4 text-slate-500 text-sm" {
+                            (t!("cookie_banner_text", locale=l)) a href="/cookie-policy" ."underline" { "Cookie Policy"} "."
+                        }
+                        div class="mb-6 sm:mb-0 flex gap-2" {
+                            input type="checkbox" id="disabled-switch" class="peer hidden" disabled {}
+                            label for="disabled-switch"
+                                class="w-10 h-6 flex items-center bg-gray-300 rounded-full p-1"
+                                { div class="w-4 h-4 bg-gray-500 rounded-full shadow-md translate-x-4" {} }
+                                span class="text-gray-500" { (t!("necessary_cookies", locale=l)) }
+                        }
+                        div ."flex flex-wrap sm:justify-end gap-4" {
+                            button "@click"="cookiesAccepted = true; localStorage.setItem('cookiesAccepted', 'true');"
+                                ."w-full sm:w-auto px-5 py-2 bg-cyan-700 text-white text-sm font-semibold shadow-xl cursor-pointer hover:bg-cyan-600"
+                                { (t!("cookie_banner_accept", locale=l)) }
+                        }
                     }
                 }
             }
@@ -104,8 +122,10 @@ pub fn render_header(top_right_content: Markup) -> Markup {
 pub fn render_start_page_menu_bar(l: &SmartString<Compact>) -> Markup {
     return html! {
         ."max-md:hidden md:flex items-center gap-8 text-xs text-slate-500 font-semibold" {
-            a href="/#why" { (t!("why_svoote_nq", locale=l)) }
-            a href="/#pricing" { (t!("pricing_short", locale=l)) }
+            @if !self_hosting::is_self_hosted() {
+                a href="/#why" { (t!("why_svoote_nq", locale=l)) }
+                a href="/#pricing" { (t!("pricing_short", locale=l)) }
+            }
             button ."cursor-pointer" onclick="document.getElementById('poll-id-input').focus()" { (t!("join_btn_desc", locale=l)) }
         }
         ."flex items-center" {
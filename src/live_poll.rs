@@ -1,109 +1,689 @@
+use arrayvec::ArrayVec;
+use serde_json::{json, Value};
 use smartstring::{Compact, SmartString};
 use std::collections::BTreeMap;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use tokio::select;
 use tokio::sync::{broadcast, mpsc, oneshot};
-use tokio::time::Instant;
+use tokio::time::{Duration, Instant};
 use uuid::Uuid;
 
 use crate::app_error::AppError;
-use crate::config::{LIVE_POLL_PARTICIPANT_LIMIT, POLL_EXIT_TIMEOUT};
-use crate::live_poll_store::{ShortID, LIVE_POLL_STORE};
-use crate::play::Player;
-use crate::slide::Slide;
+use crate::config::{
+    COUNTDOWN_MAX_DURATION, LEADERBOARD_SIZE, LIVE_POLL_SAVE_INTERVAL, MC_ANSWERS_CAPACITY,
+};
+use crate::live_poll_store::{live_poll_store, ShortID};
+use crate::persistence::PersistedLivePoll;
+use crate::play::{ParticipantSlide, ParticipantUpdate, Player};
+use crate::report;
+use crate::settings::settings;
+use crate::shutdown;
+use crate::slide::{ParticipantQuestions, QandALiveAnswers, Slide, SlideType};
+use crate::wsmessage::WSMessage;
 
+// Every live poll is owned by its own task (see LivePoll::run). Handlers never touch the state
+// directly, they send commands through a LivePollHandle and the task applies them one after
+// another, so all changes to a poll happen in the order they arrived.
 pub struct LivePoll {
     pub host_session_id: Uuid,
+    // The results are kept under this id as a read-only report after the poll ended
+    pub report_id: Uuid,
     pub slides: Vec<Slide>,
     pub player_indices: BTreeMap<Uuid, usize>,
     pub players: Vec<Player>,
     pub current_slide_index: usize,
     pub current_item_start_time: tokio::time::Instant,
-    pub start_poll_channel_sender: Option<oneshot::Sender<()>>,
-    pub set_slide_index_channel_sender: mpsc::Sender<usize>,
-    pub slide_change_notification_channel_receiver: broadcast::Receiver<usize>,
+    // Set while a countdown for the current slide is running, voting closes when it is reached
+    pub voting_deadline: Option<Instant>,
+    pub channels: LivePollChannels,
+    pub leaderboard_enabled: bool,
+    pub allow_custom_player_names: bool,
+    // Set by the first change after the last snapshot, the task persists the poll once it is reached
+    save_deadline: Option<Instant>,
+    // Q&A slide whose questions changed since the last update to the participants. Every upvote
+    // changes them, so participants get them at most once per stats update throttle.
+    questions_update_slide_index: Option<usize>,
+    last_questions_update: Instant,
+}
+
+// Notifications about changes of a poll, the sockets subscribe to these
+#[derive(Clone)]
+pub struct LivePollChannels {
+    pub slide_change_notification_channel_sender: broadcast::Sender<usize>,
     pub stats_change_notification_channel_sender: broadcast::Sender<usize>,
-    pub stats_change_notification_channel_receiver: broadcast::Receiver<usize>,
+    pub voting_state_channel_sender: broadcast::Sender<usize>,
     pub emoji_channel_sender: broadcast::Sender<(usize, SmartString<Compact>)>,
-    pub emoji_channel_receiver: broadcast::Receiver<(usize, SmartString<Compact>)>,
-    pub exit_poll_channel_sender: mpsc::Sender<()>,
-    //pub leaderboard_enabled: bool,
-    //pub allow_custom_player_names: bool,
+    pub leaderboard_channel_sender: broadcast::Sender<Arc<Leaderboard>>,
+    // All questions of a Q&A slide after they changed, participants build their view from these
+    // without asking the task
+    pub questions_channel_sender: broadcast::Sender<(usize, Arc<ParticipantQuestions>)>,
+    // The content of a slide changed without a slide change, e.g. results were revealed or reset
+    pub slide_update_channel_sender: broadcast::Sender<usize>,
+    // The current slide and voting state as participants see them, built once by the task instead
+    // of every participant socket asking for them
+    pub participant_channel_sender: broadcast::Sender<ParticipantUpdate>,
+}
+
+type Reply<T> = oneshot::Sender<Result<T, AppError>>;
+
+pub enum LivePollCommand {
+    // Replies with the player, or None if the participant limit is reached
+    Join {
+        player_session_id: Uuid,
+        reply: oneshot::Sender<Option<Player>>,
+    },
+    SetPlayerProfile {
+        player_session_id: Uuid,
+        name: SmartString<Compact>,
+        avatar_index: usize,
+        reply: Reply<Player>,
+    },
+    SubmitAnswer {
+        player_session_id: Uuid,
+        slide_index: usize,
+        answer: Answer,
+        reply: Reply<()>,
+    },
+    SubmitEmoji {
+        player_session_id: Uuid,
+        slide_index: usize,
+        emoji: SmartString<Compact>,
+        reply: Reply<()>,
+    },
+    SubmitQuestion {
+        player_index: usize,
+        slide_index: usize,
+        text: String,
+        reply: Reply<()>,
+    },
+    UpvoteQuestion {
+        player_index: usize,
+        slide_index: usize,
+        question_index: usize,
+        reply: Reply<()>,
+    },
+    GotoSlide(usize),
+    SetVotingOpen(usize, bool),
+    StartCountdown(usize, Duration),
+    RevealResults(usize),
+    ResetSlide(usize),
+    ModerateQuestion {
+        slide_index: usize,
+        question_index: usize,
+        action: String,
+    },
+    ShowLeaderboard,
+    // Runs a read-only view of the poll inside the task, e.g. to build a websocket message.
+    // The closure must not wait on the poll itself, it would never get an answer.
+    Read(Box<dyn FnOnce(&LivePoll) + Send>),
+    // Writes the current state through the storage backend, replies once it is written
+    Persist(oneshot::Sender<()>),
+    // Ends the poll, replies once its report is stored
+    Exit(oneshot::Sender<()>),
+}
+
+impl LivePollCommand {
+    fn is_host_command(&self) -> bool {
+        return matches!(
+            self,
+            LivePollCommand::GotoSlide(_)
+                | LivePollCommand::SetVotingOpen(_, _)
+                | LivePollCommand::StartCountdown(_, _)
+                | LivePollCommand::RevealResults(_)
+                | LivePollCommand::ResetSlide(_)
+                | LivePollCommand::ModerateQuestion { .. }
+                | LivePollCommand::ShowLeaderboard
+        );
+    }
+}
+
+pub enum Answer {
+    MultipleChoice(ArrayVec<u8, MC_ANSWERS_CAPACITY>),
+    FreeText(SmartString<Compact>),
+    Rating(u8),
+    NumericEstimate(f64),
+    Ranking(ArrayVec<u8, MC_ANSWERS_CAPACITY>),
+}
+
+impl Answer {
+    fn get_item_name(&self) -> &'static str {
+        return match self {
+            Answer::MultipleChoice(_) => "multiple choice",
+            Answer::FreeText(_) => "free text",
+            Answer::Rating(_) => "rating",
+            Answer::NumericEstimate(_) => "numeric estimate",
+            Answer::Ranking(_) => "ranking",
+        };
+    }
+}
+
+// Cheap to clone, every handler and socket gets its own copy from the store.
+// Fields that never change while the poll is live are kept here, so they can be read without
+// a round trip to the task.
+#[derive(Clone)]
+pub struct LivePollHandle {
+    pub host_session_id: Uuid,
+    pub report_id: Uuid,
+    pub allow_custom_player_names: bool,
+    pub channels: LivePollChannels,
+    command_sender: mpsc::Sender<LivePollCommand>,
+}
+
+impl LivePollHandle {
+    // Fails with NotFound if the poll ended before the command was processed
+    async fn request<T>(
+        &self,
+        create_command: impl FnOnce(oneshot::Sender<T>) -> LivePollCommand,
+    ) -> Result<T, AppError> {
+        let (reply_sender, reply_receiver) = oneshot::channel();
+
+        self.command_sender
+            .send(create_command(reply_sender))
+            .await
+            .map_err(|_| AppError::NotFound)?;
+
+        return reply_receiver.await.map_err(|_| AppError::NotFound);
+    }
+
+    // For commands without a reply, these are dropped silently if the poll ended
+    pub async fn send(&self, command: LivePollCommand) {
+        let _ = self.command_sender.send(command).await;
+    }
+
+    pub async fn read<T: Send + 'static>(
+        &self,
+        read: impl FnOnce(&LivePoll) -> T + Send + 'static,
+    ) -> Result<T, AppError> {
+        return self
+            .request(|reply| {
+                LivePollCommand::Read(Box::new(move |live_poll| {
+                    let _ = reply.send(read(live_poll));
+                }))
+            })
+            .await;
+    }
+
+    pub async fn join(&self, player_session_id: Uuid) -> Result<Option<Player>, AppError> {
+        return self
+            .request(|reply| LivePollCommand::Join {
+                player_session_id,
+                reply,
+            })
+            .await;
+    }
+
+    pub async fn set_player_profile(
+        &self,
+        player_session_id: Uuid,
+        name: SmartString<Compact>,
+        avatar_index: usize,
+    ) -> Result<Player, AppError> {
+        return self
+            .request(|reply| LivePollCommand::SetPlayerProfile {
+                player_session_id,
+                name,
+                avatar_index,
+                reply,
+            })
+            .await?;
+    }
+
+    pub async fn submit_answer(
+        &self,
+        player_session_id: Uuid,
+        slide_index: usize,
+        answer: Answer,
+    ) -> Result<(), AppError> {
+        return self
+            .request(|reply| LivePollCommand::SubmitAnswer {
+                player_session_id,
+                slide_index,
+                answer,
+                reply,
+            })
+            .await?;
+    }
+
+    pub async fn submit_emoji(
+        &self,
+        player_session_id: Uuid,
+        slide_index: usize,
+        emoji: SmartString<Compact>,
+    ) -> Result<(), AppError> {
+        return self
+            .request(|reply| LivePollCommand::SubmitEmoji {
+                player_session_id,
+                slide_index,
+                emoji,
+                reply,
+            })
+            .await?;
+    }
+
+    pub async fn submit_question(
+        &self,
+        player_index: usize,
+        slide_index: usize,
+        text: String,
+    ) -> Result<(), AppError> {
+        return self
+            .request(|reply| LivePollCommand::SubmitQuestion {
+                player_index,
+                slide_index,
+                text,
+                reply,
+            })
+            .await?;
+    }
+
+    pub async fn upvote_question(
+        &self,
+        player_index: usize,
+        slide_index: usize,
+        question_index: usize,
+    ) -> Result<(), AppError> {
+        return self
+            .request(|reply| LivePollCommand::UpvoteQuestion {
+                player_index,
+                slide_index,
+                question_index,
+                reply,
+            })
+            .await?;
+    }
+
+    pub async fn persist(&self) -> Result<(), AppError> {
+        return self.request(LivePollCommand::Persist).await;
+    }
+
+    pub async fn exit(&self) -> Result<(), AppError> {
+        return self.request(LivePollCommand::Exit).await;
+    }
 }
 
 impl LivePoll {
     pub fn orchestrate(
         slides: Vec<Slide>,
         host_session_id: Uuid,
-        //leaderboard_enabled: bool,
-        //allow_custom_player_names: bool,
-    ) -> Result<(ShortID, Arc<Mutex<Self>>), AppError> {
-        let (start_poll_channel_sender, start_poll_channel_receiver) = oneshot::channel::<()>();
-        let (set_slide_index_channel_sender, mut set_slide_index_channel_receiver) =
-            mpsc::channel(16);
-        let (slide_change_notification_channel_sender, slide_change_notification_channel_receiver) =
-            broadcast::channel(16);
-        let (stats_change_notification_channel_sender, stats_change_notification_channel_receiver) =
-            broadcast::channel(16);
-        let (emoji_channel_sender, emoji_channel_receiver) = broadcast::channel(16);
-        let (exit_poll_channel_sender, mut exit_poll_channel_receiver) = mpsc::channel(16);
-
-        let (poll_id, live_poll) = LIVE_POLL_STORE.insert(LivePoll {
+        leaderboard_enabled: bool,
+        allow_custom_player_names: bool,
+    ) -> Result<(ShortID, LivePollHandle), AppError> {
+        let mut live_poll = Self::new(host_session_id, slides);
+        live_poll.leaderboard_enabled = leaderboard_enabled;
+        live_poll.allow_custom_player_names = allow_custom_player_names;
+
+        return Self::spawn(None, live_poll);
+    }
+
+    // Recreates a poll from a snapshot written before a server restart. Hosts and participants
+    // reconnect with their old session ids to the same ShortID and find their results intact.
+    pub fn restore(
+        persisted_poll: PersistedLivePoll,
+    ) -> Result<(ShortID, LivePollHandle), AppError> {
+        if persisted_poll.slides.is_empty() {
+            return Err(AppError::BadRequest(
+                "Persisted poll does not contain any slides".to_string(),
+            ));
+        }
+
+        let mut live_poll = Self::new(persisted_poll.host_session_id, persisted_poll.slides);
+        live_poll.current_slide_index = persisted_poll
+            .current_slide_index
+            .min(live_poll.slides.len() - 1);
+        live_poll.report_id = persisted_poll.report_id;
+        live_poll.player_indices = persisted_poll.player_indices;
+        live_poll.players = persisted_poll.players;
+        live_poll.leaderboard_enabled = persisted_poll.leaderboard_enabled;
+        live_poll.allow_custom_player_names = persisted_poll.allow_custom_player_names;
+
+        return Self::spawn(Some(persisted_poll.poll_id), live_poll);
+    }
+
+    fn new(host_session_id: Uuid, slides: Vec<Slide>) -> Self {
+        let capacity = settings().notification_channel_capacity;
+        let channels = LivePollChannels {
+            slide_change_notification_channel_sender: broadcast::channel(capacity).0,
+            stats_change_notification_channel_sender: broadcast::channel(capacity).0,
+            voting_state_channel_sender: broadcast::channel(capacity).0,
+            emoji_channel_sender: broadcast::channel(capacity).0,
+            leaderboard_channel_sender: broadcast::channel(capacity).0,
+            questions_channel_sender: broadcast::channel(capacity).0,
+            slide_update_channel_sender: broadcast::channel(capacity).0,
+            participant_channel_sender: broadcast::channel(capacity).0,
+        };
+
+        return LivePoll {
             host_session_id,
+            report_id: Uuid::new_v4(),
             slides,
             player_indices: BTreeMap::new(),
             players: Vec::new(),
             current_slide_index: 0usize,
             current_item_start_time: Instant::now(),
-            start_poll_channel_sender: Some(start_poll_channel_sender),
-            set_slide_index_channel_sender,
-            slide_change_notification_channel_receiver,
-            stats_change_notification_channel_sender: stats_change_notification_channel_sender
-                .clone(),
-            stats_change_notification_channel_receiver,
-            emoji_channel_sender,
-            emoji_channel_receiver,
-            exit_poll_channel_sender,
-            //leaderboard_enabled,
-            //allow_custom_player_names,
-        })?;
-
-        let return_live_poll_handle = live_poll.clone();
-
-        tokio::spawn(async move {
-            let _live_poll_drop = RmLivePollOnDrop {
-                poll_id,
-                host_session_id,
-            };
-            let _ = start_poll_channel_receiver.await;
-
-            loop {
-                select! {
-                    slide_index = set_slide_index_channel_receiver.recv() => {
-                        if let Some(mut slide_index) = slide_index {
-                            let mut live_poll = live_poll.lock().unwrap();
-                            if slide_index >= live_poll.slides.len() {
-                                slide_index = 0;
-                            }
-
-                            live_poll.current_slide_index = slide_index;
-                            live_poll.current_item_start_time = Instant::now();
-
-                            let _ = slide_change_notification_channel_sender.send(slide_index);
-                            let _ = stats_change_notification_channel_sender.send(slide_index);
-                        }
+            voting_deadline: None,
+            channels,
+            leaderboard_enabled: false,
+            allow_custom_player_names: false,
+            save_deadline: None,
+            questions_update_slide_index: None,
+            last_questions_update: Instant::now() - settings().stats_update_throttle(),
+        };
+    }
+
+    fn spawn(
+        poll_id: Option<ShortID>,
+        live_poll: LivePoll,
+    ) -> Result<(ShortID, LivePollHandle), AppError> {
+        let (command_sender, command_receiver) = mpsc::channel(settings().command_queue_capacity);
+
+        let handle = LivePollHandle {
+            host_session_id: live_poll.host_session_id,
+            report_id: live_poll.report_id,
+            allow_custom_player_names: live_poll.allow_custom_player_names,
+            channels: live_poll.channels.clone(),
+            command_sender,
+        };
+
+        let poll_id = match poll_id {
+            Some(poll_id) => {
+                live_poll_store().insert_with_id(poll_id, handle.clone())?;
+                poll_id
+            }
+            None => live_poll_store().insert(handle.clone())?,
+        };
+
+        tokio::spawn(live_poll.run(poll_id, command_receiver));
+
+        return Ok((poll_id, handle));
+    }
+
+    async fn run(
+        mut self,
+        poll_id: ShortID,
+        mut command_receiver: mpsc::Receiver<LivePollCommand>,
+    ) {
+        let mut live_poll_drop = RmLivePollOnDrop {
+            poll_id,
+            host_session_id: self.host_session_id,
+            has_ended: false,
+        };
+
+        self.notify_state_changed();
+
+        // Participants alone don't keep a poll alive, only the host does
+        let mut exit_deadline = Instant::now() + settings().poll_exit_timeout();
+        let mut exit_reply = None;
+
+        loop {
+            select! {
+                command = command_receiver.recv() => {
+                    if command.as_ref().is_some_and(LivePollCommand::is_host_command) {
+                        exit_deadline = Instant::now() + settings().poll_exit_timeout();
                     }
-                    _ = exit_poll_channel_receiver.recv() => {
-                        break;
+
+                    match command {
+                        Some(LivePollCommand::Persist(reply)) => {
+                            self.save(poll_id).await;
+                            let _ = reply.send(());
+                        }
+                        Some(LivePollCommand::Exit(reply)) => {
+                            exit_reply = Some(reply);
+                            break;
+                        }
+                        None => break,
+                        Some(command) => self.handle_command(command),
                     }
-                    _ = tokio::time::sleep(POLL_EXIT_TIMEOUT) => {
-                        break;
+                }
+                _ = tokio::time::sleep_until(self.voting_deadline.unwrap_or_else(Instant::now)), if self.voting_deadline.is_some() => {
+                    let slide_index = self.current_slide_index;
+                    self.set_voting_open(slide_index, false, None);
+                }
+                _ = tokio::time::sleep_until(self.last_questions_update + settings().stats_update_throttle()), if self.questions_update_slide_index.is_some() => {
+                    self.send_questions_update();
+                }
+                // Changes that arrive while a snapshot is pending are merged into it
+                _ = tokio::time::sleep_until(self.save_deadline.unwrap_or_else(Instant::now)), if self.save_deadline.is_some() => {
+                    self.save(poll_id).await;
+                }
+                _ = tokio::time::sleep_until(exit_deadline) => {
+                    break;
+                }
+            };
+        }
+
+        // Freeze the results before the poll is removed from the store
+        report::store_report(poll_id, &self);
+        live_poll_drop.has_ended = true;
+
+        if let Some(reply) = exit_reply {
+            let _ = reply.send(());
+        }
+    }
+
+    fn handle_command(&mut self, command: LivePollCommand) {
+        match command {
+            LivePollCommand::Join {
+                player_session_id,
+                reply,
+            } => {
+                let player = self
+                    .get_or_create_player(&player_session_id)
+                    .map(|player_index| self.players[player_index].clone());
+                let _ = reply.send(player);
+            }
+            LivePollCommand::SetPlayerProfile {
+                player_session_id,
+                name,
+                avatar_index,
+                reply,
+            } => {
+                let _ =
+                    reply.send(self.set_player_profile(&player_session_id, &name, avatar_index));
+            }
+            LivePollCommand::SubmitAnswer {
+                player_session_id,
+                slide_index,
+                answer,
+                reply,
+            } => {
+                let _ = reply.send(self.submit_answer(&player_session_id, slide_index, answer));
+            }
+            LivePollCommand::SubmitEmoji {
+                player_session_id,
+                slide_index,
+                emoji,
+                reply,
+            } => {
+                let _ = reply.send(self.submit_emoji(&player_session_id, slide_index, emoji));
+            }
+            LivePollCommand::SubmitQuestion {
+                player_index,
+                slide_index,
+                text,
+                reply,
+            } => {
+                let result = self
+                    .get_open_qa_answers(slide_index)
+                    .and_then(|qa_answers| qa_answers.submit_question(player_index, &text))
+                    .map(|_| self.notify_questions_changed(slide_index));
+                let _ = reply.send(result);
+            }
+            LivePollCommand::UpvoteQuestion {
+                player_index,
+                slide_index,
+                question_index,
+                reply,
+            } => {
+                let result = self
+                    .get_open_qa_answers(slide_index)
+                    .and_then(|qa_answers| qa_answers.toggle_upvote(player_index, question_index))
+                    .map(|_| self.notify_questions_changed(slide_index));
+                let _ = reply.send(result);
+            }
+            LivePollCommand::GotoSlide(slide_index) => self.goto_slide(slide_index),
+            LivePollCommand::SetVotingOpen(slide_index, is_open) => {
+                self.set_voting_open(slide_index, is_open, None);
+            }
+            LivePollCommand::StartCountdown(slide_index, duration) => {
+                let deadline = Instant::now() + duration.min(COUNTDOWN_MAX_DURATION);
+                self.set_voting_open(slide_index, true, Some(deadline));
+            }
+            LivePollCommand::RevealResults(slide_index) => self.reveal_results(slide_index),
+            LivePollCommand::ResetSlide(slide_index) => self.reset_slide(slide_index),
+            LivePollCommand::ModerateQuestion {
+                slide_index,
+                question_index,
+                action,
+            } => {
+                if let Some(SlideType::QandA(qa_answers)) = self
+                    .slides
+                    .get_mut(slide_index)
+                    .map(|slide| &mut slide.slide_type)
+                {
+                    if qa_answers.moderate(question_index, &action).is_ok() {
+                        self.notify_questions_changed(slide_index);
                     }
-                };
+                }
             }
-        });
+            LivePollCommand::ShowLeaderboard => {
+                if self.leaderboard_enabled {
+                    let leaderboard = Arc::new(self.create_leaderboard());
+                    let _ = self.channels.leaderboard_channel_sender.send(leaderboard);
+                }
+            }
+            LivePollCommand::Read(read) => read(self),
+            LivePollCommand::Persist(_) | LivePollCommand::Exit(_) => {}
+        }
+    }
+
+    // The file write happens on the blocking pool instead of a runtime thread. The task waits for
+    // it, so the snapshots of a poll are written in order and never after it was removed.
+    async fn save(&mut self, poll_id: ShortID) {
+        self.save_deadline = None;
+
+        if let Some(snapshot) = live_poll_store().create_snapshot(poll_id, self) {
+            let write = move || live_poll_store().write_snapshot(poll_id, &snapshot);
+            if let Err(e) = tokio::task::spawn_blocking(write).await {
+                error!("Error writing snapshot of live poll {poll_id}: {e}");
+            }
+        }
+    }
+
+    // Schedules the task to persist this poll through the storage backend. A snapshot contains
+    // every answer of the poll, so it is written at most once per LIVE_POLL_SAVE_INTERVAL.
+    pub fn notify_state_changed(&mut self) {
+        self.save_deadline
+            .get_or_insert_with(|| Instant::now() + LIVE_POLL_SAVE_INTERVAL);
+    }
+
+    fn goto_slide(&mut self, mut slide_index: usize) {
+        if slide_index >= self.slides.len() {
+            slide_index = 0;
+        }
+
+        self.current_slide_index = slide_index;
+        self.current_item_start_time = Instant::now();
+        self.voting_deadline = None;
+        self.notify_state_changed();
+
+        let _ = self
+            .channels
+            .slide_change_notification_channel_sender
+            .send(slide_index);
+        let _ = self
+            .channels
+            .stats_change_notification_channel_sender
+            .send(slide_index);
+        self.send_participant_slide(slide_index);
+        self.send_participant_voting_state(slide_index);
+    }
+
+    // The countdown only ever belongs to the current slide and is not persisted
+    fn set_voting_open(&mut self, slide_index: usize, is_open: bool, deadline: Option<Instant>) {
+        let Some(slide) = self.slides.get_mut(slide_index) else {
+            return;
+        };
+
+        slide.is_voting_open = is_open;
+
+        if slide_index == self.current_slide_index {
+            self.voting_deadline = deadline;
+        }
+
+        self.notify_state_changed();
+        let _ = self.channels.voting_state_channel_sender.send(slide_index);
+        self.send_participant_voting_state(slide_index);
+    }
+
+    fn reveal_results(&mut self, slide_index: usize) {
+        let Some(slide) = self.slides.get_mut(slide_index) else {
+            return;
+        };
+
+        slide.results_revealed = true;
+        let share_results = slide.share_results_with_participants;
+
+        self.notify_state_changed();
+        let _ = self
+            .channels
+            .stats_change_notification_channel_sender
+            .send(slide_index);
+        if share_results {
+            let _ = self.channels.slide_update_channel_sender.send(slide_index);
+            self.send_participant_slide(slide_index);
+        }
+    }
+
+    fn reset_slide(&mut self, slide_index: usize) {
+        let Some(slide) = self.slides.get_mut(slide_index) else {
+            return;
+        };
+
+        slide.start_new_round();
+
+        // Answer times of the new round count from the reset, not from the slide change
+        if slide_index == self.current_slide_index {
+            self.current_item_start_time = Instant::now();
+        }
+
+        self.notify_state_changed();
+        let _ = self
+            .channels
+            .stats_change_notification_channel_sender
+            .send(slide_index);
+        let _ = self.channels.slide_update_channel_sender.send(slide_index);
+        self.send_participant_slide(slide_index);
+
+        // A new round starts with open voting and without a countdown
+        self.set_voting_open(slide_index, true, None);
+    }
+
+    // Participants only see the current slide, changes of other slides reach them on the slide change
+    fn send_participant_slide(&self, slide_index: usize) {
+        if slide_index != self.current_slide_index
+            || self.channels.participant_channel_sender.receiver_count() == 0
+        {
+            return;
+        }
 
-        return Ok((poll_id, return_live_poll_handle));
+        let slide = ParticipantSlide::new(slide_index, self.get_current_slide(), None);
+        let _ = self
+            .channels
+            .participant_channel_sender
+            .send(ParticipantUpdate::Slide(Arc::new(slide)));
+    }
+
+    fn send_participant_voting_state(&self, slide_index: usize) {
+        if self.channels.participant_channel_sender.receiver_count() == 0 {
+            return;
+        }
+
+        let _ = self
+            .channels
+            .participant_channel_sender
+            .send(ParticipantUpdate::VotingState(
+                self.create_voting_state_ws_message(slide_index),
+            ));
     }
 
     pub fn get_or_create_player(&mut self, player_session_id: &Uuid) -> Option<usize> {
@@ -111,7 +691,7 @@ impl LivePoll {
             return Some(player_index);
         }
 
-        if self.players.len() >= LIVE_POLL_PARTICIPANT_LIMIT {
+        if self.players.len() >= settings().live_poll_participant_limit {
             return None;
         }
 
@@ -119,52 +699,351 @@ impl LivePoll {
         let new_player = Player::new(new_player_idx);
 
         self.player_indices
-            .insert(player_session_id.clone(), new_player_idx);
+            .insert(*player_session_id, new_player_idx);
         self.players.push(new_player);
 
-        for item in &mut self.slides {
-            item.add_player();
+        self.notify_state_changed();
+
+        return Some(new_player_idx);
+    }
+
+    fn set_player_profile(
+        &mut self,
+        player_session_id: &Uuid,
+        name: &str,
+        avatar_index: usize,
+    ) -> Result<Player, AppError> {
+        let player_index = self.get_player_index(player_session_id)?;
+
+        if !self.allow_custom_player_names && !name.trim().is_empty() {
+            return Err(AppError::BadRequest(
+                "Custom names are not allowed in this poll".to_string(),
+            ));
         }
 
-        //let _ = self.ch_players_updated_send.send(());
+        // Validate the name before changing anything, so a request either applies completely or not at all
+        Player::validate_name(name)?;
 
-        return Some(new_player_idx);
+        let player = self.get_player_mut(player_index);
+        player.set_avatar_index(avatar_index)?;
+        player.set_name(name)?;
+        let player = player.clone();
+
+        // Names are shown in the free text results of the host, so these need an update
+        self.notify_state_changed();
+        let _ = self
+            .channels
+            .stats_change_notification_channel_sender
+            .send(self.current_slide_index);
+
+        return Ok(player);
+    }
+
+    fn submit_answer(
+        &mut self,
+        player_session_id: &Uuid,
+        slide_index: usize,
+        answer: Answer,
+    ) -> Result<(), AppError> {
+        let player_index = self.get_player_index(player_session_id)?;
+        self.check_voting_open(slide_index)?;
+
+        let start_time = self.get_current_slide_start_time();
+        let slide = &mut self.slides[slide_index];
+        let allow_answer_changes = slide.allow_answer_changes;
+
+        match (&mut slide.slide_type, answer) {
+            (SlideType::MultipleChoice(mc_answers), Answer::MultipleChoice(answer_indices)) => {
+                let score = mc_answers.submit_answer(
+                    player_index,
+                    answer_indices,
+                    start_time,
+                    allow_answer_changes,
+                )?;
+
+                // A changed answer replaces the score of the previous one, even if it is not correct anymore
+                slide.submit_score(player_index, score);
+            }
+            (SlideType::FreeText(ft_answers), Answer::FreeText(text)) => {
+                ft_answers.submit_answer(player_index, text, allow_answer_changes)?;
+            }
+            (SlideType::Rating(rating_answers), Answer::Rating(rating)) => {
+                rating_answers.submit_answer(player_index, rating, allow_answer_changes)?;
+            }
+            (SlideType::NumericEstimate(numeric_answers), Answer::NumericEstimate(value)) => {
+                numeric_answers.submit_answer(player_index, value, allow_answer_changes)?;
+            }
+            (SlideType::Ranking(ranking_answers), Answer::Ranking(ordering)) => {
+                ranking_answers.submit_answer(player_index, ordering, allow_answer_changes)?;
+            }
+            (_, answer) => {
+                return Err(AppError::BadRequest(format!(
+                    "This is not a {} item",
+                    answer.get_item_name()
+                )));
+            }
+        }
+
+        self.notify_state_changed();
+        let _ = self
+            .channels
+            .stats_change_notification_channel_sender
+            .send(slide_index);
+
+        return Ok(());
+    }
+
+    fn submit_emoji(
+        &mut self,
+        player_session_id: &Uuid,
+        slide_index: usize,
+        emoji: SmartString<Compact>,
+    ) -> Result<(), AppError> {
+        let player_index = self.get_player_index(player_session_id)?;
+        if slide_index >= self.slides.len() {
+            return Err(AppError::BadRequest(
+                "slide_index out of bounds".to_string(),
+            ));
+        }
+
+        let slide = &mut self.slides[slide_index];
+        if slide.player_emojis.contains_key(&player_index) {
+            return Err(AppError::BadRequest("Emoji already submitted".to_string()));
+        }
+
+        match emoji.as_str() {
+            "heart" => slide.heart_emojis += 1,
+            "thumbsUp" => slide.thumbs_up_emojis += 1,
+            "thumbsDown" => slide.thumbs_down_emojis += 1,
+            "smileyFace" => slide.smiley_face_emojis += 1,
+            "sadFace" => slide.sad_face_emojis += 1,
+            _ => return Err(AppError::BadRequest("Unknown emoji".to_string())),
+        }
+
+        slide.player_emojis.insert(player_index, emoji.clone());
+
+        self.notify_state_changed();
+        let _ = self
+            .channels
+            .emoji_channel_sender
+            .send((slide_index, emoji));
+
+        return Ok(());
+    }
+
+    // Participants can only ask and upvote while the Q&A slide is shown and open
+    fn get_open_qa_answers(
+        &mut self,
+        slide_index: usize,
+    ) -> Result<&mut QandALiveAnswers, AppError> {
+        self.check_voting_open(slide_index)?;
+
+        return match &mut self.slides[slide_index].slide_type {
+            SlideType::QandA(qa_answers) => Ok(qa_answers),
+            _ => Err(AppError::BadRequest("This is not a Q&A item".to_string())),
+        };
+    }
+
+    fn notify_questions_changed(&mut self, slide_index: usize) {
+        self.notify_state_changed();
+        let _ = self
+            .channels
+            .stats_change_notification_channel_sender
+            .send(slide_index);
+
+        if self.questions_update_slide_index != Some(slide_index) {
+            self.send_questions_update();
+            self.questions_update_slide_index = Some(slide_index);
+        }
+    }
+
+    // The questions are serialized once for all participants instead of every socket reading them
+    fn send_questions_update(&mut self) {
+        let Some(slide_index) = self.questions_update_slide_index.take() else {
+            return;
+        };
+        self.last_questions_update = Instant::now();
+
+        if self.channels.questions_channel_sender.receiver_count() == 0 {
+            return;
+        }
+
+        if let Some(SlideType::QandA(qa_answers)) =
+            self.slides.get(slide_index).map(|slide| &slide.slide_type)
+        {
+            let _ = self.channels.questions_channel_sender.send((
+                slide_index,
+                Arc::new(qa_answers.create_participant_questions(None)),
+            ));
+        }
     }
 
     pub fn get_player_index(&self, player_session_id: &Uuid) -> Result<usize, AppError> {
         return self
             .player_indices
-            .get(&player_session_id)
-            .map(|index| *index)
+            .get(player_session_id)
+            .copied()
             .ok_or(AppError::BadRequest(
                 "Player with this auth token did not join the poll yet".to_string(),
             ));
     }
 
-    pub fn get_player<'a>(&'a self, player_index: usize) -> &'a Player {
+    pub fn get_player(&self, player_index: usize) -> &Player {
         return &self.players[player_index];
     }
 
-    /*pub fn get_player_mut<'a>(&'a mut self, player_index: usize) -> &'a mut Player {
+    pub fn get_player_mut(&mut self, player_index: usize) -> &mut Player {
         return &mut self.players[player_index];
-    }*/
+    }
 
-    pub fn get_current_slide<'a>(&'a mut self) -> &'a mut Slide {
-        return &mut self.slides[self.current_slide_index];
+    pub fn get_current_slide(&self) -> &Slide {
+        return &self.slides[self.current_slide_index];
     }
 
     pub fn get_current_slide_start_time(&self) -> tokio::time::Instant {
-        return self.current_item_start_time.clone();
+        return self.current_item_start_time;
+    }
+
+    // Answers are only accepted for the slide that is currently shown, while its voting is open
+    pub fn check_voting_open(&self, slide_index: usize) -> Result<(), AppError> {
+        if slide_index >= self.slides.len() {
+            return Err(AppError::BadRequest(
+                "slide_index out of bounds".to_string(),
+            ));
+        }
+
+        if slide_index != self.current_slide_index {
+            return Err(AppError::BadRequest(
+                "This slide is currently not active".to_string(),
+            ));
+        }
+
+        if !self.slides[slide_index].is_voting_open {
+            return Err(AppError::BadRequest(
+                "Voting for this slide is closed".to_string(),
+            ));
+        }
+
+        return Ok(());
+    }
+
+    pub fn create_voting_state_ws_message(&self, slide_index: usize) -> WSMessage {
+        let remaining_seconds = match self.voting_deadline {
+            Some(deadline) if slide_index == self.current_slide_index => Some(
+                deadline
+                    .saturating_duration_since(Instant::now())
+                    .as_secs_f64(),
+            ),
+            _ => None,
+        };
+
+        return WSMessage {
+            cmd: SmartString::from("votingState"),
+            data: json!({
+                "slideIndex": slide_index,
+                "isOpen": self.slides.get(slide_index).map(|slide| slide.is_voting_open).unwrap_or(false),
+                "remainingSeconds": remaining_seconds,
+            }),
+        };
+    }
+
+    // Cumulative quiz scores over all slides as (player_index, score), highest score first
+    pub fn get_leaderboard(&self) -> Vec<(usize, usize)> {
+        let mut scores = vec![0usize; self.players.len()];
+        for slide in &self.slides {
+            for (player_index, score) in slide.get_player_scores() {
+                scores[player_index] += score;
+            }
+        }
+
+        let mut leaderboard = scores.into_iter().enumerate().collect::<Vec<_>>();
+        leaderboard.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        return leaderboard;
+    }
+
+    pub fn create_leaderboard(&self) -> Leaderboard {
+        let leaderboard = self.get_leaderboard();
+
+        let mut top_entries = Vec::with_capacity(LEADERBOARD_SIZE);
+        let mut player_positions = vec![(0usize, 0usize); self.players.len()];
+        let mut last_position: Option<(usize, usize)> = None;
+
+        for (position, (player_index, score)) in leaderboard.iter().enumerate() {
+            // Players with the same score share the same rank
+            let rank = match last_position {
+                Some((rank, last_score)) if last_score == *score => rank,
+                _ => position + 1,
+            };
+            last_position = Some((rank, *score));
+            player_positions[*player_index] = (rank, *score);
+
+            if position < LEADERBOARD_SIZE {
+                top_entries.push(json!({
+                    "playerIndex": player_index,
+                    "name": self.players[*player_index].get_name(),
+                    "avatarIndex": self.players[*player_index].get_avatar_index(),
+                    "rank": rank,
+                    "score": score,
+                }));
+            }
+        }
+
+        return Leaderboard {
+            top_entries: Value::Array(top_entries),
+            player_positions,
+        };
+    }
+}
+
+// Built once by the task when the host shows the leaderboard, every socket only adds the
+// position of its own player
+pub struct Leaderboard {
+    top_entries: Value,
+    // (rank, score) by player index
+    player_positions: Vec<(usize, usize)>,
+}
+
+impl Leaderboard {
+    // The host gets the top of the leaderboard, participants additionally get their own position
+    pub fn create_ws_message(&self, player_index: Option<usize>) -> WSMessage {
+        let own_position = player_index
+            .and_then(|player_index| self.player_positions.get(player_index))
+            .map(|(rank, score)| json!({ "rank": rank, "score": score }));
+
+        return WSMessage {
+            cmd: SmartString::from("showLeaderboard"),
+            data: json!({
+                "leaderboard": self.top_entries,
+                "player": own_position,
+            }),
+        };
+    }
+}
+
+pub fn restore_live_polls() {
+    for persisted_poll in live_poll_store().load() {
+        let poll_id = persisted_poll.poll_id;
+        match LivePoll::restore(persisted_poll) {
+            Ok(_) => info!("Restored live poll {poll_id}"),
+            Err(e) => error!("Error restoring live poll {poll_id}: {e}"),
+        }
     }
 }
 
 pub struct RmLivePollOnDrop {
     pub poll_id: ShortID,
     pub host_session_id: Uuid,
+    pub has_ended: bool,
 }
 
 impl Drop for RmLivePollOnDrop {
     fn drop(&mut self) {
-        LIVE_POLL_STORE.remove(&self.host_session_id, self.poll_id);
+        // Polls that are interrupted by a shutdown stay persisted and are restored after the restart
+        if !self.has_ended && shutdown::is_shutting_down() {
+            return;
+        }
+
+        live_poll_store().remove(&self.host_session_id, self.poll_id);
     }
 }
@@ -1,70 +1,141 @@
 use std::{
     collections::BTreeMap,
-    sync::{Arc, Mutex},
+    sync::{Mutex, OnceLock},
 };
 
 use uuid::Uuid;
 
-use crate::{app_error::AppError, live_poll::LivePoll};
+use crate::{
+    app_error::AppError,
+    live_poll::{LivePoll, LivePollHandle},
+    persistence::PersistedLivePoll,
+};
 
 pub type ShortID = u32;
 
-pub static LIVE_POLL_STORE: LivePollStore = LivePollStore::new();
+static LIVE_POLL_STORE: OnceLock<Box<dyn LivePollStorage>> = OnceLock::new();
+
+// Storage backend for live polls. The handlers only go through this trait, so the backend can be
+// chosen per deployment (see init()).
+pub trait LivePollStorage: Send + Sync {
+    fn get(&self, id: ShortID) -> Option<LivePollHandle>;
+
+    // Inserts the poll under a new random short id
+    fn insert(&self, live_poll: LivePollHandle) -> Result<ShortID, AppError>;
+
+    // Inserts the poll under a fixed short id, used when restoring persisted polls
+    fn insert_with_id(&self, id: ShortID, live_poll: LivePollHandle) -> Result<(), AppError>;
+
+    fn remove(&self, host_session_id: &Uuid, id: ShortID);
+
+    fn get_by_session_id(&self, host_session_id: &Uuid) -> Option<(ShortID, LivePollHandle)>;
+
+    fn get_all(&self) -> Vec<(ShortID, LivePollHandle)>;
+
+    // Serializes the current state of a poll, called by the task of the poll whenever it changed.
+    // Backends that don't persist polls return None.
+    fn create_snapshot(&self, _id: ShortID, _live_poll: &LivePoll) -> Option<Vec<u8>> {
+        return None;
+    }
+
+    // Persists a snapshot from create_snapshot(). This may block, it runs on the blocking pool.
+    fn write_snapshot(&self, _id: ShortID, _snapshot: &[u8]) {}
+
+    // Returns all polls that were persisted before the last shutdown
+    fn load(&self) -> Vec<PersistedLivePoll> {
+        return Vec::new();
+    }
+
+    // Persists the serialized report of an ended poll, see report::sync_report(). This may block,
+    // it runs on the blocking pool.
+    fn write_report(&self, _report_id: &Uuid, _report: &[u8]) {}
+
+    fn delete_report(&self, _report_id: &Uuid) {}
+
+    // Returns all serialized reports that were persisted before the last shutdown
+    fn load_reports(&self) -> Vec<(Uuid, Vec<u8>)> {
+        return Vec::new();
+    }
+}
+
+pub fn init(storage: Box<dyn LivePollStorage>) {
+    if LIVE_POLL_STORE.set(storage).is_err() {
+        panic!("live_poll_store::init() called more than once");
+    }
+}
+
+pub fn live_poll_store() -> &'static dyn LivePollStorage {
+    return LIVE_POLL_STORE
+        .get()
+        .expect("live_poll_store::init() needs to be called before accessing the store")
+        .as_ref();
+}
 
-pub struct LivePollStore {
-    pub polls: Mutex<BTreeMap<ShortID, Arc<Mutex<LivePoll>>>>,
-    pub session_lookup: Mutex<BTreeMap<Uuid, ShortID>>,
+pub struct InMemoryLivePollStore {
+    polls: Mutex<BTreeMap<ShortID, LivePollHandle>>,
+    session_lookup: Mutex<BTreeMap<Uuid, ShortID>>,
 }
 
-impl LivePollStore {
+impl InMemoryLivePollStore {
     pub const fn new() -> Self {
-        return LivePollStore {
+        return InMemoryLivePollStore {
             polls: Mutex::new(BTreeMap::new()),
             session_lookup: Mutex::new(BTreeMap::new()),
         };
     }
+}
 
-    pub fn get(&self, id: ShortID) -> Option<Arc<Mutex<LivePoll>>> {
-        return self
-            .polls
-            .lock()
-            .unwrap()
-            .get(&id)
-            .map(|live_poll| live_poll.clone());
+impl LivePollStorage for InMemoryLivePollStore {
+    fn get(&self, id: ShortID) -> Option<LivePollHandle> {
+        return self.polls.lock().unwrap().get(&id).cloned();
     }
 
-    pub fn insert(&self, live_poll: LivePoll) -> Result<(ShortID, Arc<Mutex<LivePoll>>), AppError> {
+    fn insert(&self, live_poll: LivePollHandle) -> Result<ShortID, AppError> {
         use rand::Rng;
         let mut rng = rand::thread_rng();
 
-        let host_session_id = live_poll.host_session_id.clone();
-
-        let live_poll = Arc::new(Mutex::new(live_poll));
+        let host_session_id = live_poll.host_session_id;
         let mut polls = self.polls.lock().unwrap();
 
-        let random_id = (0..1000).map(|_| rng.gen_range::<ShortID, _>(1000..10_000)).find(|id| !polls.contains_key(&id))
-            .unwrap_or((0..1000).map(|_| rng.gen_range::<ShortID, _>(10_000..1_000_000)).find(|id| !polls.contains_key(&id))
+        let random_id = (0..1000).map(|_| rng.gen_range::<ShortID, _>(1000..10_000)).find(|id| !polls.contains_key(id))
+            .unwrap_or((0..1000).map(|_| rng.gen_range::<ShortID, _>(10_000..1_000_000)).find(|id| !polls.contains_key(id))
         .ok_or(AppError::OtherInternalServerError("Could not find a short id (between 1000 and 999 999 while creating a new live quiz."
                 .to_string()))?);
 
-        polls.insert(random_id, live_poll.clone());
+        polls.insert(random_id, live_poll);
         self.session_lookup
             .lock()
             .unwrap()
             .insert(host_session_id, random_id);
 
-        return Ok((random_id, live_poll));
+        return Ok(random_id);
+    }
+
+    fn insert_with_id(&self, id: ShortID, live_poll: LivePollHandle) -> Result<(), AppError> {
+        let host_session_id = live_poll.host_session_id;
+        let mut polls = self.polls.lock().unwrap();
+
+        if polls.contains_key(&id) {
+            return Err(AppError::OtherInternalServerError(format!(
+                "A live poll with the short id {id} already exists"
+            )));
+        }
+
+        polls.insert(id, live_poll);
+        self.session_lookup
+            .lock()
+            .unwrap()
+            .insert(host_session_id, id);
+
+        return Ok(());
     }
 
-    pub fn remove(&self, host_session_id: &Uuid, id: ShortID) {
+    fn remove(&self, host_session_id: &Uuid, id: ShortID) {
         self.polls.lock().unwrap().remove(&id);
         self.session_lookup.lock().unwrap().remove(host_session_id);
     }
 
-    pub fn get_by_session_id(
-        &self,
-        host_session_id: &Uuid,
-    ) -> Option<(ShortID, Arc<Mutex<LivePoll>>)> {
+    fn get_by_session_id(&self, host_session_id: &Uuid) -> Option<(ShortID, LivePollHandle)> {
         let poll_id = match self.session_lookup.lock().unwrap().get(host_session_id) {
             Some(poll_id) => *poll_id,
             None => return None,
@@ -76,4 +147,14 @@ impl LivePollStore {
             return None;
         }
     }
+
+    fn get_all(&self) -> Vec<(ShortID, LivePollHandle)> {
+        return self
+            .polls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(poll_id, live_poll)| (*poll_id, live_poll.clone()))
+            .collect();
+    }
 }
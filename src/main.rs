@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return)]
+
 #[macro_use]
 extern crate log;
 
@@ -6,15 +8,26 @@ extern crate rust_i18n;
 i18n!("locales", fallback = "en");
 
 mod app_error;
+mod charts;
 mod compliance;
 mod config;
+mod export;
 mod host;
 mod html_page;
 //mod illustrations;
 mod live_poll;
 mod live_poll_store;
+mod markdown;
+mod persistence;
 mod play;
+mod player_map;
+mod poll_definition;
+mod qr_code;
+mod report;
+mod self_hosting;
 mod session_id;
+mod settings;
+mod shutdown;
 mod slide;
 mod start_page;
 mod static_file;
@@ -29,7 +42,11 @@ use axum::routing::{get, post};
 
 use app_error::AppError;
 use axum_extra::extract::CookieJar;
+use live_poll_store::{InMemoryLivePollStore, LivePollStorage};
+use persistence::FileLivePollStore;
+use settings::LivePollStorageKind;
 use smartstring::{Compact, SmartString};
+use std::future::IntoFuture;
 
 fn main() {
     let runtime = tokio::runtime::Builder::new_multi_thread()
@@ -44,8 +61,29 @@ fn main() {
         error!("Error parsing .env-file: {}", e);
     }
 
+    if let Err(e) = settings::init() {
+        error!("Invalid configuration: {}", e);
+        std::process::exit(1);
+    }
+
+    let storage: Box<dyn LivePollStorage> = match settings::settings().live_poll_storage {
+        LivePollStorageKind::Memory => Box::new(InMemoryLivePollStore::new()),
+        LivePollStorageKind::File => Box::new(FileLivePollStore::new(
+            settings::settings().poll_data_dir.clone(),
+        )),
+    };
+    live_poll_store::init(storage);
+
+    if let Err(e) = self_hosting::init() {
+        error!("Invalid configuration: {}", e);
+        std::process::exit(1);
+    }
+
     runtime.block_on(async {
-        let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
+        live_poll::restore_live_polls();
+        report::restore_reports();
+
+        let addr = settings::settings().bind_address;
         let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
 
         info!("Listening on http://{}", addr);
@@ -55,14 +93,34 @@ fn main() {
             .route("/host", get(host::get_host_page))
             .route("/p", get(play::get_play_page))
             .route("/poll_exists/:poll_id", get(play::get_poll_exists))
+            .route("/qr/:file_name", get(qr_code::get_qr_code))
             .route("/start_poll", post(host::post_start_poll))
             .route("/stop_poll/:poll_id", post(host::post_stop_poll))
+            .route("/export/:poll_id/:format", get(export::get_export))
+            .route("/report/:report_id", get(report::get_report_page))
+            .route(
+                "/report/:report_id/print",
+                get(report::get_report_print_page),
+            )
+            .route("/report/:report_id/share", post(report::post_share_report))
+            .route(
+                "/report/:report_id/export/:format",
+                get(report::get_report_export),
+            )
+            .route("/render_markdown", post(host::post_render_markdown))
             .route("/ws/host/:poll_id", get(host::host_socket))
             .route("/ws/p/:poll_id", get(play::play_socket))
             .route("/submit_mc_answer/:poll_id", post(play::post_mc_answer))
             .route("/submit_ft_answer/:poll_id", post(play::post_ft_answer))
+            .route("/submit_rating/:poll_id", post(play::post_rating))
+            .route("/submit_ranking/:poll_id", post(play::post_ranking))
+            .route(
+                "/submit_numeric_answer/:poll_id",
+                post(play::post_numeric_answer),
+            )
             .route("/submit_emoji/:poll_id", post(play::post_emoji))
-            //.route("/name_avatar/:poll_id", post(play::post_name_avatar))
+            .route("/name_avatar/:poll_id", post(play::post_name_avatar))
+            .route("/avatar/:avatar_index", get(play::get_avatar))
             .route("/static/:file_name", get(static_file::http_get_static_file))
             .route("/data-privacy", get(compliance::get_privacy_policy_page))
             .route(
@@ -72,12 +130,23 @@ fn main() {
             .route("/cookie-policy", get(compliance::get_cookie_policy_page))
             .route("/manage-cookies", get(compliance::get_manage_cookies_page))
             .route("/contact", get(compliance::get_contact_page))
+            .route("/imprint", get(compliance::get_imprint_page))
             .route("/robots.txt", get(compliance::get_robots_txt))
             .route("/bombardft/:poll_id", get(host::get_bombardft))
             .route("/stats", get(host::get_stats))
             .fallback(get(get_fallback));
 
-        axum::serve(listener, routes).await.unwrap();
+        tokio::spawn(shutdown::listen_for_signals());
+        let server = axum::serve(listener, routes).with_graceful_shutdown(shutdown::requested());
+
+        tokio::select! {
+            result = server.into_future() => result.unwrap(),
+            _ = shutdown::grace_period_elapsed() => {
+                warn!("Shutdown grace period elapsed, closing the remaining connections");
+            }
+        }
+
+        shutdown::flush_live_polls().await;
     })
 }
 
@@ -97,7 +166,7 @@ pub fn select_language(cookies: &CookieJar, headers: &HeaderMap) -> SmartString<
     }
     if let Some(lang_header) = headers.get(ACCEPT_LANGUAGE) {
         if let Ok(lang_header) = lang_header.to_str() {
-            if let Some(lang) = intersection(lang_header, &["en", "de"]).get(0) {
+            if let Some(lang) = intersection(lang_header, &["en", "de"]).first() {
                 return SmartString::from(lang);
             }
         }
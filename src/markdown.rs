@@ -0,0 +1,23 @@
+use std::collections::HashSet;
+
+use pulldown_cmark::{html, Options, Parser};
+
+// Rendered markdown can't carry any classes, so the elements are styled from the container
+pub const MARKDOWN_CLASSES: &str = "break-words [&_h1]:mb-[0.5em] [&_h1]:text-[1.5em] [&_h1]:font-semibold [&_h2]:mb-[0.5em] [&_h2]:text-[1.25em] [&_h2]:font-semibold [&_h3]:mb-[0.5em] [&_h3]:font-semibold [&_p]:mb-[0.75em] [&_ul]:mb-[0.75em] [&_ul]:ml-[1.5em] [&_ul]:list-disc [&_ol]:mb-[0.75em] [&_ol]:ml-[1.5em] [&_ol]:list-decimal [&_a]:text-cyan-600 [&_a]:underline [&_code]:px-[0.25em] [&_code]:bg-slate-100 [&_code]:rounded-xs [&_blockquote]:pl-[1em] [&_blockquote]:border-l-4 [&_blockquote]:text-slate-500 [&_img]:max-h-[12em] [&_table]:mb-[0.75em] [&_td]:px-[0.5em] [&_td]:border [&_th]:px-[0.5em] [&_th]:border";
+
+pub fn render_markdown(markdown: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
+
+    let mut unsanitized_html = String::new();
+    html::push_html(&mut unsanitized_html, Parser::new_ext(markdown, options));
+
+    // Markdown may contain raw html, so the output always goes through the sanitizer
+    return ammonia::Builder::default()
+        .url_schemes(HashSet::from(["http", "https", "mailto"]))
+        .link_rel(Some("noopener noreferrer nofollow"))
+        .set_tag_attribute_value("a", "target", "_blank")
+        .clean(&unsanitized_html)
+        .to_string();
+}
@@ -0,0 +1,224 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    app_error::AppError,
+    live_poll::{LivePoll, LivePollHandle},
+    live_poll_store::{InMemoryLivePollStore, LivePollStorage, ShortID},
+    play::Player,
+    slide::Slide,
+};
+
+#[derive(Serialize)]
+struct LivePollSnapshot<'a> {
+    poll_id: ShortID,
+    host_session_id: &'a Uuid,
+    report_id: &'a Uuid,
+    slides: &'a Vec<Slide>,
+    player_indices: &'a BTreeMap<Uuid, usize>,
+    players: &'a Vec<Player>,
+    current_slide_index: usize,
+    leaderboard_enabled: bool,
+    allow_custom_player_names: bool,
+}
+
+#[derive(Deserialize)]
+pub struct PersistedLivePoll {
+    pub poll_id: ShortID,
+    pub host_session_id: Uuid,
+    #[serde(default = "Uuid::new_v4")]
+    pub report_id: Uuid,
+    pub slides: Vec<Slide>,
+    pub player_indices: BTreeMap<Uuid, usize>,
+    pub players: Vec<Player>,
+    pub current_slide_index: usize,
+    #[serde(default)]
+    pub leaderboard_enabled: bool,
+    #[serde(default)]
+    pub allow_custom_player_names: bool,
+}
+
+// Keeps the live polls in memory and additionally writes every poll as one json snapshot file
+// into data_dir whenever its state changes. On startup, all snapshots found in there are
+// restored as live polls again. Reports of ended polls are kept in data_dir/reports.
+pub struct FileLivePollStore {
+    memory: InMemoryLivePollStore,
+    data_dir: PathBuf,
+}
+
+impl FileLivePollStore {
+    pub fn new(data_dir: PathBuf) -> Self {
+        return FileLivePollStore {
+            memory: InMemoryLivePollStore::new(),
+            data_dir,
+        };
+    }
+
+    fn get_snapshot_path(&self, poll_id: ShortID) -> PathBuf {
+        return self.data_dir.join(format!("{poll_id}.json"));
+    }
+
+    fn get_reports_dir(&self) -> PathBuf {
+        return self.data_dir.join("reports");
+    }
+
+    fn get_report_path(&self, report_id: &Uuid) -> PathBuf {
+        return self.get_reports_dir().join(format!("{report_id}.json"));
+    }
+
+    fn delete_snapshot(&self, poll_id: ShortID) {
+        delete_file(&self.get_snapshot_path(poll_id));
+    }
+}
+
+// Writes to a temporary file first and renames it afterwards, so a crash in the middle of
+// writing never leaves a truncated file behind.
+fn write_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("json.tmp");
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&tmp_path, content)?;
+    return std::fs::rename(&tmp_path, path);
+}
+
+fn delete_file(path: &Path) {
+    if let Err(e) = std::fs::remove_file(path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            error!("Error deleting {:?}: {e}", path);
+        }
+    }
+}
+
+// All .json files in dir, a missing dir is treated as empty
+fn read_json_files(dir: &Path) -> Vec<(PathBuf, std::io::Result<Vec<u8>>)> {
+    let dir = match std::fs::read_dir(dir) {
+        Ok(dir) => dir,
+        Err(_) => return Vec::new(),
+    };
+
+    return dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .map(|path| {
+            let content = std::fs::read(&path);
+            (path, content)
+        })
+        .collect();
+}
+
+impl LivePollStorage for FileLivePollStore {
+    fn get(&self, id: ShortID) -> Option<LivePollHandle> {
+        return self.memory.get(id);
+    }
+
+    fn insert(&self, live_poll: LivePollHandle) -> Result<ShortID, AppError> {
+        return self.memory.insert(live_poll);
+    }
+
+    fn insert_with_id(&self, id: ShortID, live_poll: LivePollHandle) -> Result<(), AppError> {
+        return self.memory.insert_with_id(id, live_poll);
+    }
+
+    fn remove(&self, host_session_id: &Uuid, id: ShortID) {
+        self.memory.remove(host_session_id, id);
+        self.delete_snapshot(id);
+    }
+
+    fn get_by_session_id(&self, host_session_id: &Uuid) -> Option<(ShortID, LivePollHandle)> {
+        return self.memory.get_by_session_id(host_session_id);
+    }
+
+    fn get_all(&self) -> Vec<(ShortID, LivePollHandle)> {
+        return self.memory.get_all();
+    }
+
+    fn create_snapshot(&self, id: ShortID, live_poll: &LivePoll) -> Option<Vec<u8>> {
+        let snapshot = serde_json::to_vec(&LivePollSnapshot {
+            poll_id: id,
+            host_session_id: &live_poll.host_session_id,
+            report_id: &live_poll.report_id,
+            slides: &live_poll.slides,
+            player_indices: &live_poll.player_indices,
+            players: &live_poll.players,
+            current_slide_index: live_poll.current_slide_index,
+            leaderboard_enabled: live_poll.leaderboard_enabled,
+            allow_custom_player_names: live_poll.allow_custom_player_names,
+        });
+
+        return match snapshot {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => {
+                error!("Error serializing live poll {id}: {e}");
+                None
+            }
+        };
+    }
+
+    fn write_snapshot(&self, poll_id: ShortID, snapshot: &[u8]) {
+        let path = self.get_snapshot_path(poll_id);
+        if let Err(e) = write_file(&path, snapshot) {
+            error!(
+                "Error writing snapshot of live poll {poll_id} to {:?}: {e}",
+                path
+            );
+        }
+    }
+
+    fn load(&self) -> Vec<PersistedLivePoll> {
+        let mut polls = Vec::new();
+
+        for (path, content) in read_json_files(&self.data_dir) {
+            let poll = content.map_err(|e| e.to_string()).and_then(|bytes| {
+                serde_json::from_slice::<PersistedLivePoll>(&bytes).map_err(|e| e.to_string())
+            });
+
+            match poll {
+                Ok(poll) => polls.push(poll),
+                Err(e) => error!("Error loading live poll snapshot {:?}: {e}", path),
+            }
+        }
+
+        return polls;
+    }
+
+    fn write_report(&self, report_id: &Uuid, report: &[u8]) {
+        let path = self.get_report_path(report_id);
+        if let Err(e) = write_file(&path, report) {
+            error!("Error writing report {report_id} to {:?}: {e}", path);
+        }
+    }
+
+    fn delete_report(&self, report_id: &Uuid) {
+        delete_file(&self.get_report_path(report_id));
+    }
+
+    fn load_reports(&self) -> Vec<(Uuid, Vec<u8>)> {
+        let mut reports = Vec::new();
+
+        for (path, content) in read_json_files(&self.get_reports_dir()) {
+            let report_id = path
+                .file_stem()
+                .and_then(|file_stem| file_stem.to_str())
+                .and_then(|file_stem| file_stem.parse::<Uuid>().ok());
+
+            match (report_id, content) {
+                (Some(report_id), Ok(content)) => reports.push((report_id, content)),
+                (None, _) => error!("Report file {:?} is not named after a report id", path),
+                (_, Err(e)) => error!("Error loading report {:?}: {e}", path),
+            }
+        }
+
+        return reports;
+    }
+}
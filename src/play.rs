@@ -1,11 +1,15 @@
 use crate::{
     app_error::AppError,
-    config::{FREE_TEXT_MAX_CHAR_LENGTH, LIVE_POLL_PARTICIPANT_LIMIT, POLL_MAX_MC_ANSWERS},
+    config::{CUSTOM_PLAYER_NAME_LENGTH_LIMIT, MC_ANSWERS_CAPACITY, QA_MAX_QUESTION_LENGTH},
     html_page::{self, render_header},
-    live_poll::LivePoll,
-    live_poll_store::{ShortID, LIVE_POLL_STORE},
+    live_poll::{Answer, LivePoll, LivePollHandle},
+    live_poll_store::{live_poll_store, ShortID},
+    markdown::MARKDOWN_CLASSES,
+    player_map::PlayerMap,
     select_language, session_id,
-    slide::{Slide, SlideType, WordCloudTerm},
+    settings::settings,
+    shutdown,
+    slide::{ParticipantQuestions, Slide, SlideType},
     start_page::render_join_form,
     wsmessage::WSMessage,
 };
@@ -22,18 +26,18 @@ use axum::{
 use axum_extra::extract::CookieJar;
 
 use maud::html;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use smartstring::{Compact, SmartString};
-use std::{
-    collections::HashMap,
-    fmt::Write,
-    sync::{Arc, Mutex},
+use std::fmt::Write;
+use std::sync::Arc;
+use tokio::{
+    select,
+    sync::broadcast::error::{RecvError, TryRecvError},
 };
-use tokio::select;
 
 pub async fn get_poll_exists(Path(poll_id): Path<ShortID>) -> Result<Response, AppError> {
-    let live_poll = LIVE_POLL_STORE.get(poll_id);
+    let live_poll = live_poll_store().get(poll_id);
 
     if live_poll.is_some() {
         return Ok("true".into_response());
@@ -54,16 +58,10 @@ pub async fn get_play_page(
 ) -> Result<Response, AppError> {
     let l = select_language(&cookies, &headers);
     let poll_id_str = params.c.clone().unwrap_or(SmartString::new());
-    let poll_id: Option<ShortID> = params
-        .c
-        .clone()
-        .map(|poll_id| poll_id.parse().ok())
-        .flatten();
+    let poll_id: Option<ShortID> = params.c.clone().and_then(|poll_id| poll_id.parse().ok());
 
     let (session_id, cookies) = session_id::get_or_create_session_id(cookies);
-    let live_poll = poll_id
-        .map(|poll_id| LIVE_POLL_STORE.get(poll_id))
-        .flatten();
+    let live_poll = poll_id.and_then(|poll_id| live_poll_store().get(poll_id));
 
     if live_poll.is_none() {
         let html = html_page::render_html_page(
@@ -93,19 +91,77 @@ pub async fn get_play_page(
     }
 
     let live_poll = live_poll.unwrap();
-    let mut live_poll = live_poll.lock().unwrap();
+    let allow_custom_player_names = live_poll.allow_custom_player_names;
 
     let html = html_page::render_html_page(
         "Svoote",
         &l,
-        match live_poll.get_or_create_player(&session_id) {
-            Some(player_index) => {
-                let _player = live_poll.get_player(player_index);
+        match live_poll.join(session_id).await? {
+            Some(player) => {
                 html! {
                     script { "document.code = " (poll_id.unwrap_or(0)) ";" }
                     (render_header(html! {}))
-                    div x-data="participant" ."mt-12 mb-20 mx-6 sm:mx-14" {
+                    div x-data="participant" ."mt-12 mb-20 mx-6 sm:mx-14"
+                        data-name=(player.get_name())
+                        data-custom-name=(player.get_custom_name().as_ref().map(|name| name.as_str()).unwrap_or(""))
+                        data-avatar-index=(player.get_avatar_index())
+                    {
                         div ."w-full max-w-96 mx-auto" {
+                            div x-show="serverRestarting" x-cloak ."mb-6 px-4 py-3 text-sm bg-orange-100 rounded-lg text-slate-600" {
+                                (t!("server_restarting", locale=l))
+                            }
+                            div ."mb-8 flex items-center gap-3" {
+                                img ":src"="'/avatar/' + player.avatarIndex" ."size-10 rounded-full border";
+                                div ."flex-1 text-slate-700 font-medium" x-text="player.name" {}
+                                button x-show="!isEditingPlayer" "@click"="editPlayer()" ."text-sm text-cyan-600 underline cursor-pointer" {
+                                    @if allow_custom_player_names { (t!("change_name_avatar", locale=l)) } @else { (t!("change_avatar", locale=l)) }
+                                }
+                            }
+                            template x-if="isEditingPlayer" {
+                                div ."mb-8 p-4 ring-2 ring-slate-300 rounded-lg" {
+                                    @if allow_custom_player_names {
+                                        input type="text"
+                                            x-model="playerForm.name"
+                                            ":placeholder"="player.name"
+                                            maxlength=(CUSTOM_PLAYER_NAME_LENGTH_LIMIT)
+                                            ."w-full mb-4 px-3 py-1.5 text-slate-700 font-medium ring-2 ring-slate-500 rounded-lg outline-hidden focus:ring-4 focus:ring-cyan-600 transition";
+                                    }
+                                    div ."grid grid-cols-5 gap-2" {
+                                        @for (avatar_index, (avatar_name, _)) in AVATARS.iter().enumerate() {
+                                            button "@click"={ "playerForm.avatarIndex = " (avatar_index) }
+                                                ":class"={ "playerForm.avatarIndex == " (avatar_index) " ? 'ring-4 ring-cyan-600' : 'ring-1 ring-slate-300'" }
+                                                ."rounded-full overflow-hidden cursor-pointer transition"
+                                                title=(avatar_name)
+                                                { img src={ "/avatar/" (avatar_index) } alt=(avatar_name) ."size-full"; }
+                                        }
+                                    }
+                                    p x-show="playerFormError !== null" x-text="playerFormError" ."mt-3 text-sm text-red-500" {}
+                                    div ."mt-4 flex gap-4 justify-end" {
+                                        button "@click"="isEditingPlayer = false" ."text-sm text-slate-500 cursor-pointer" { (t!("cancel", locale=l)) }
+                                        button "@click"={ "submitNameAvatar(" (poll_id_str) ")" }
+                                            ."px-4 py-1 text-sm text-white font-bold bg-cyan-600 rounded-full cursor-pointer hover:bg-cyan-700"
+                                            { (t!("save", locale=l)) }
+                                    }
+                                }
+                            }
+                            template x-if="leaderboard !== null" {
+                                div ."mb-8 px-4 py-3 text-center ring-2 ring-cyan-600 rounded-lg" {
+                                    p ."text-sm text-slate-500" { (t!("your_score", locale=l)) }
+                                    p ."text-2xl text-slate-700 font-bold" x-text="leaderboard.player !== null ? leaderboard.player.score : 0" {}
+                                    p x-show="leaderboard.player !== null" ."text-sm text-slate-500"
+                                        x-text={ "'" (t!("your_rank", locale=l)) " ' + (leaderboard.player !== null ? leaderboard.player.rank : '')" } {}
+                                }
+                            }
+                            template x-if="currentSlide.slideType != 'empty' && currentSlide.slideType != 'info' && (!isVotingOpen || votingCountdown !== null)" {
+                                div ."mb-6 px-4 py-2 flex justify-center items-center gap-2 text-sm font-medium rounded-lg"
+                                    ":class"="isVotingOpen ? 'text-cyan-700 bg-cyan-50' : 'text-slate-600 bg-slate-100'"
+                                {
+                                    template x-if="!isVotingOpen" { span { (t!("voting_closed", locale=l)) } }
+                                    template x-if="isVotingOpen" {
+                                        span x-text={ "'" (t!("time_left", locale=l)) " ' + formatCountdown(votingCountdown)" } {}
+                                    }
+                                }
+                            }
                             template x-if="currentSlide.slideType == 'null'" { div {} }
                             template x-if="currentSlide.slideType == 'mc'" {
                                 div {
@@ -118,13 +174,17 @@ pub async fn get_play_page(
                                     }
                                     div ."relative mt-7 h-10" {
                                         button x-show="!currentSlide.submitted"
-                                            ":disabled"="(currentSlide.allowMultipleMCAnswers && currentSlide.selectedAnswer.length === 0) || (!currentSlide.allowMultipleMCAnswers && currentSlide.selectedAnswer === '')"
+                                            ":disabled"="!isVotingOpen || (currentSlide.allowMultipleMCAnswers && currentSlide.selectedAnswer.length === 0) || (!currentSlide.allowMultipleMCAnswers && currentSlide.selectedAnswer === '')"
                                             "@click"={ "submitMCAnswer(" (poll_id_str) ")" }
                                             ."absolute size-full inset-0 flex items-center justify-center text-white font-bold bg-cyan-600 rounded-full cursor-pointer disabled:cursor-default hover:bg-cyan-700 disabled:bg-slate-300"
                                             { (t!("submit", locale=l)) }
                                         div x-show="currentSlide.submitted"
-                                            ."absolute size-full inset-0 flex items-center justify-center text-slate-500 text-sm"
-                                            { (t!("answer_submitted", locale=l)) }
+                                            ."absolute size-full inset-0 flex items-center justify-center gap-2 text-slate-500 text-sm"
+                                        {
+                                            (t!("answer_submitted", locale=l))
+                                            button x-show="currentSlide.allowAnswerChanges && isVotingOpen" "@click"="currentSlide.submitted = false"
+                                                ."text-cyan-600 underline cursor-pointer" { (t!("change_answer", locale=l)) }
+                                        }
                                     }
                                 }
                             }
@@ -140,16 +200,211 @@ pub async fn get_play_page(
                                     div ."relative mt-5 h-10" {
                                     button x-show="!currentSlide.submitted"
                                         x-ref="ftSubmitButton"
-                                        ":disabled"="currentSlide.selectedAnswer === ''"
+                                        ":disabled"="!isVotingOpen || currentSlide.selectedAnswer === ''"
                                         "@click"={ "submitFTAnswer(" (poll_id_str) ")" }
                                         ."absolute size-full inset-0 flex items-center justify-center text-white font-bold bg-cyan-600 rounded-full cursor-pointer disabled:cursor-default hover:bg-cyan-700 disabled:bg-slate-300"
                                         { (t!("submit", locale=l)) }
                                     div x-show="currentSlide.submitted"
-                                        ."absolute size-full inset-0 flex items-center justify-center text-slate-500 text-sm"
-                                        { (t!("answer_submitted", locale=l)) }
+                                        ."absolute size-full inset-0 flex items-center justify-center gap-2 text-slate-500 text-sm"
+                                    {
+                                        (t!("answer_submitted", locale=l))
+                                        button x-show="currentSlide.allowAnswerChanges && isVotingOpen" "@click"="currentSlide.submitted = false"
+                                            ."text-cyan-600 underline cursor-pointer" { (t!("change_answer", locale=l)) }
+                                    }
+                                    }
+                                }
+                            }
+                            template x-if="currentSlide.slideType == 'rating'" {
+                                div {
+                                    h1 x-init="$el.innerText = currentSlide.question" x-effect="$el.innerText = currentSlide.question" ."mb-5 text-lg text-slate-700 font-medium" {}
+                                    div ."flex gap-1.5" {
+                                        template x-for="rating in currentSlide.scaleMax" {
+                                            label ."flex-1 py-1.5 flex justify-center text-slate-700 font-medium ring-2 ring-slate-500 has-checked:ring-4 has-checked:ring-cyan-600 rounded-lg cursor-pointer transition" {
+                                                input type="radio" "x-model.number"="currentSlide.selectedAnswer" ":value"="rating" ":disabled"="currentSlide.submitted" ."hidden";
+                                                span x-text="rating" {}
+                                            }
+                                        }
+                                    }
+                                    div ."mt-2 flex justify-between gap-4 text-xs text-slate-500" {
+                                        span x-text="currentSlide.lowLabel" {}
+                                        span ."text-right" x-text="currentSlide.highLabel" {}
+                                    }
+                                    div ."relative mt-7 h-10" {
+                                        button x-show="!currentSlide.submitted"
+                                            ":disabled"="!isVotingOpen || currentSlide.selectedAnswer === null"
+                                            "@click"={ "submitRating(" (poll_id_str) ")" }
+                                            ."absolute size-full inset-0 flex items-center justify-center text-white font-bold bg-cyan-600 rounded-full cursor-pointer disabled:cursor-default hover:bg-cyan-700 disabled:bg-slate-300"
+                                            { (t!("submit", locale=l)) }
+                                        div x-show="currentSlide.submitted"
+                                            ."absolute size-full inset-0 flex items-center justify-center gap-2 text-slate-500 text-sm"
+                                        {
+                                            (t!("answer_submitted", locale=l))
+                                            button x-show="currentSlide.allowAnswerChanges && isVotingOpen" "@click"="currentSlide.submitted = false"
+                                                ."text-cyan-600 underline cursor-pointer" { (t!("change_answer", locale=l)) }
+                                        }
                                     }
                                 }
                             }
+                            template x-if="currentSlide.slideType == 'info'" {
+                                div {
+                                    h1 x-init="$el.innerText = currentSlide.question" x-effect="$el.innerText = currentSlide.question" ."mb-4 text-xl text-slate-800 font-semibold" {}
+                                    div x-html="currentSlide.html" ."text-slate-700" .(MARKDOWN_CLASSES) {}
+                                }
+                            }
+                            template x-if="currentSlide.slideType == 'qa'" {
+                                div {
+                                    h1 x-init="$el.innerText = currentSlide.question" x-effect="$el.innerText = currentSlide.question" ."mb-5 text-lg text-slate-700 font-medium" {}
+                                    textarea x-model="newQuestion" maxlength=(QA_MAX_QUESTION_LENGTH) rows="3"
+                                        placeholder=(t!("your_question", locale=l))
+                                        ."w-full px-4 py-1.5 text-slate-700 ring-2 ring-slate-500 rounded-lg outline-hidden focus:ring-4 focus:ring-cyan-600 transition" {}
+                                    p x-show="requestError !== null" x-text="requestError" ."mt-2 text-sm text-red-500" {}
+                                    div ."relative mt-4 h-10" {
+                                        button ":disabled"="!isVotingOpen || newQuestion.trim() === ''"
+                                            "@click"="submitQuestion()"
+                                            ."absolute size-full inset-0 flex items-center justify-center text-white font-bold bg-cyan-600 rounded-full cursor-pointer disabled:cursor-default hover:bg-cyan-700 disabled:bg-slate-300"
+                                            { (t!("ask_question", locale=l)) }
+                                    }
+                                    div ."mt-8" {
+                                        template x-for="question in currentSlide.questions" ":key"="question.index" {
+                                            div ."mb-3 px-3 py-2 flex items-start gap-3 ring-1 ring-slate-300 rounded-lg"
+                                                ":class"="question.isPinned && 'ring-2 ring-cyan-600'"
+                                            {
+                                                button "@click"="upvoteQuestion(question.index)" ":disabled"="!isVotingOpen || question.isOwn"
+                                                    ":class"="question.isUpvoted ? 'text-cyan-600' : 'text-slate-400'"
+                                                    ."w-8 flex flex-col items-center text-sm font-bold cursor-pointer disabled:cursor-default"
+                                                {
+                                                    span { "▲" }
+                                                    span x-text="question.upvotes" {}
+                                                }
+                                                div ."flex-1" {
+                                                    p ."text-slate-700 break-words" x-text="question.text" {}
+                                                    p x-show="question.isAnswered" ."text-xs text-green-600" { (t!("question_answered", locale=l)) }
+                                                    p x-show="question.isOwn" ."text-xs text-slate-400" { (t!("own_question", locale=l)) }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            template x-if="currentSlide.slideType == 'rank'" {
+                                div {
+                                    h1 x-init="$el.innerText = currentSlide.question" x-effect="$el.innerText = currentSlide.question" ."mb-2 text-lg text-slate-700 font-medium" {}
+                                    p ."mb-4 text-xs text-slate-500" { (t!("ranking_explanation", locale=l)) }
+                                    template x-for="(optionIndex, position) in currentSlide.selectedAnswer" ":key"="optionIndex" {
+                                        div ."w-full mb-3 px-3 py-1.5 flex gap-2 items-center ring-2 ring-slate-500 rounded-lg bg-white transition"
+                                            ":draggable"="!currentSlide.submitted"
+                                            "@dragstart"="draggedRankingPosition = position"
+                                            "@dragover.prevent"=""
+                                            "@drop.prevent"="moveRankingOption(draggedRankingPosition, position); draggedRankingPosition = null"
+                                            ":class"="currentSlide.submitted ? 'opacity-70' : 'cursor-grab'"
+                                        {
+                                            div ."w-5 text-slate-400 font-bold" x-text="(position + 1) + '.'" {}
+                                            div ."flex-1 text-slate-700 font-medium" x-text="currentSlide.options[optionIndex]" {}
+                                            button x-show="!currentSlide.submitted" "@click"="moveRankingOption(position, position - 1)" ":disabled"="position == 0"
+                                                ."px-1 text-slate-500 cursor-pointer disabled:text-slate-200" { "↑" }
+                                            button x-show="!currentSlide.submitted" "@click"="moveRankingOption(position, position + 1)" ":disabled"="position == currentSlide.selectedAnswer.length - 1"
+                                                ."px-1 text-slate-500 cursor-pointer disabled:text-slate-200" { "↓" }
+                                        }
+                                    }
+                                    div ."relative mt-7 h-10" {
+                                        button x-show="!currentSlide.submitted"
+                                            ":disabled"="!isVotingOpen"
+                                            "@click"={ "submitRanking(" (poll_id_str) ")" }
+                                            ."absolute size-full inset-0 flex items-center justify-center text-white font-bold bg-cyan-600 rounded-full cursor-pointer disabled:cursor-default hover:bg-cyan-700 disabled:bg-slate-300"
+                                            { (t!("submit", locale=l)) }
+                                        div x-show="currentSlide.submitted"
+                                            ."absolute size-full inset-0 flex items-center justify-center gap-2 text-slate-500 text-sm"
+                                        {
+                                            (t!("answer_submitted", locale=l))
+                                            button x-show="currentSlide.allowAnswerChanges && isVotingOpen" "@click"="currentSlide.submitted = false"
+                                                ."text-cyan-600 underline cursor-pointer" { (t!("change_answer", locale=l)) }
+                                        }
+                                    }
+                                }
+                            }
+                            template x-if="currentSlide.slideType == 'num'" {
+                                div {
+                                    h1 x-init="$el.innerText = currentSlide.question" x-effect="$el.innerText = currentSlide.question" ."mb-5 text-lg text-slate-700 font-medium" {}
+                                    input type="number" step="any"
+                                        "x-model.number"="currentSlide.selectedAnswer"
+                                        ":min"="currentSlide.min" ":max"="currentSlide.max"
+                                        "@keyup.enter"="$refs.numSubmitButton.click()"
+                                        ":disabled"="currentSlide.submitted"
+                                        placeholder=(t!("answer", locale=l))
+                                        ."w-full px-4 py-1.5 text-lg text-slate-700 font-medium ring-2 ring-slate-500 rounded-lg outline-hidden focus:ring-4 focus:ring-cyan-600 transition";
+                                    p ."mt-2 text-xs text-slate-500" x-text={ "'" (t!("numeric_bounds", locale=l)) " ' + currentSlide.min + ' – ' + currentSlide.max" } {}
+                                    div ."relative mt-5 h-10" {
+                                        button x-show="!currentSlide.submitted"
+                                            x-ref="numSubmitButton"
+                                            ":disabled"="!isVotingOpen || typeof currentSlide.selectedAnswer !== 'number' || currentSlide.selectedAnswer < currentSlide.min || currentSlide.selectedAnswer > currentSlide.max"
+                                            "@click"={ "submitNumericAnswer(" (poll_id_str) ")" }
+                                            ."absolute size-full inset-0 flex items-center justify-center text-white font-bold bg-cyan-600 rounded-full cursor-pointer disabled:cursor-default hover:bg-cyan-700 disabled:bg-slate-300"
+                                            { (t!("submit", locale=l)) }
+                                        div x-show="currentSlide.submitted"
+                                            ."absolute size-full inset-0 flex items-center justify-center gap-2 text-slate-500 text-sm"
+                                        {
+                                            (t!("answer_submitted", locale=l))
+                                            button x-show="currentSlide.allowAnswerChanges && isVotingOpen" "@click"="currentSlide.submitted = false"
+                                                ."text-cyan-600 underline cursor-pointer" { (t!("change_answer", locale=l)) }
+                                        }
+                                    }
+                                }
+                            }
+                            template x-if="results !== null" {
+                                div ."mt-8 p-4 ring-2 ring-slate-300 rounded-lg" {
+                                    h2 ."mb-3 text-sm text-slate-500 font-medium" { (t!("results", locale=l)) }
+                                    template x-if="currentSlide.slideType == 'mc'" {
+                                        div {
+                                            template x-for="(answer, answerIndex) in currentSlide.answers" {
+                                                div ."mb-2" {
+                                                    div ."flex justify-between gap-4 text-sm text-slate-700" {
+                                                        span x-text="answer.text" {}
+                                                        span x-text="results.counts[answerIndex]" ."font-medium" {}
+                                                    }
+                                                    div ."h-2 bg-slate-100 rounded-full" {
+                                                        div ."h-full bg-cyan-600 rounded-full" ":style"="`width: ${resultBarWidth(results.counts, answerIndex)}%`" {}
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                    template x-if="currentSlide.slideType == 'rating'" {
+                                        div {
+                                            template x-for="rating in currentSlide.scaleMax" {
+                                                div ."mb-2 flex items-center gap-3 text-sm text-slate-700" {
+                                                    span x-text="rating" ."w-4" {}
+                                                    div ."flex-1 h-2 bg-slate-100 rounded-full" {
+                                                        div ."h-full bg-cyan-600 rounded-full" ":style"="`width: ${resultBarWidth(results.counts, rating - 1)}%`" {}
+                                                    }
+                                                    span x-text="results.counts[rating - 1]" ."w-6 text-right font-medium" {}
+                                                }
+                                            }
+                                            p x-show="results.mean !== null" x-text={ "'" (t!("average", locale=l)) " ' + formatResult(results.mean)" } ."mt-3 text-sm text-slate-500" {}
+                                        }
+                                    }
+                                    template x-if="currentSlide.slideType == 'num'" {
+                                        div ."text-sm text-slate-700" {
+                                            p x-text={ "'" (t!("average", locale=l)) " ' + formatResult(results.mean)" } {}
+                                            p x-text={ "'" (t!("median", locale=l)) " ' + formatResult(results.median)" } {}
+                                        }
+                                    }
+                                    template x-if="currentSlide.slideType == 'rank'" {
+                                        ol ."list-decimal list-inside text-sm text-slate-700" {
+                                            template x-for="optionIndex in results.ranking" {
+                                                li ."mb-1" x-text="currentSlide.options[optionIndex]" {}
+                                            }
+                                        }
+                                    }
+                                    template x-if="currentSlide.slideType == 'ft'" {
+                                        div ."flex flex-wrap gap-2" {
+                                            template x-for="term in [...results.terms].sort((a, b) => b[1] - a[1]).slice(0, 10)" {
+                                                span ."px-2 py-0.5 text-sm text-slate-700 bg-slate-100 rounded-full" x-text="term[0] + ' (' + term[1] + ')'" {}
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            p x-show="requestError !== null && currentSlide.slideType != 'qa'" x-text="requestError" ."mt-4 text-sm text-center text-red-500" {}
                             hr ."mt-12 mb-5";
                             p ."mb-3 text-xs text-center text-slate-500" { (t!("your_reaction", locale=l)) }
                             div ."flex justify-center gap-4" {
@@ -169,7 +424,7 @@ pub async fn get_play_page(
                 html! {
                     (render_header(html!{}))
                     ."my-36 text-center text-slate-500" {
-                        "The participant limit for this poll was reached (" (LIVE_POLL_PARTICIPANT_LIMIT) " participants)."
+                        "The participant limit for this poll was reached (" (settings().live_poll_participant_limit) " participants)."
                     }
                 }
             }
@@ -182,7 +437,7 @@ pub async fn get_play_page(
 
 // These awesome SVG-avatars were obtained from dicebear.com (Adventurer Neutral by Lisa Wischofsky)
 // They are published under the CC BY 4.0 license (https://creativecommons.org/licenses/by/4.0/)
-const _AVATARS: &[(&'static str, &'static str)] = &[
+const AVATARS: &[(&str, &str)] = &[
     ("Rascal", include_str!("static/svgs/rascal_square.svg")),
     ("Chester", include_str!("static/svgs/chester_square.svg")),
     ("Coco", include_str!("static/svgs/coco_square.svg")),
@@ -205,16 +460,16 @@ const _AVATARS: &[(&'static str, &'static str)] = &[
     ("Jasmine", include_str!("static/svgs/jasmine_square.svg")),
 ];
 
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Player {
-    //generated_name: SmartString<Compact>,
-    //custom_name: Option<SmartString<Compact>>,
-    //avatar_index: usize,
+    generated_name: SmartString<Compact>,
+    custom_name: Option<SmartString<Compact>>,
+    avatar_index: usize,
 }
 
 impl Player {
-    pub fn new(_player_index: usize) -> Self {
-        return Player {};
-        /*let avatar_index = player_index % AVATARS.len();
+    pub fn new(player_index: usize) -> Self {
+        let avatar_index = player_index % AVATARS.len();
         let duplicate_name_number = ((player_index - avatar_index) / AVATARS.len()) + 1;
         let mut generated_name = SmartString::<Compact>::new();
 
@@ -232,26 +487,19 @@ impl Player {
             generated_name,
             custom_name: None,
             avatar_index,
-        };*/
+        };
     }
 
-    /*
-    pub fn get_name<'a>(&'a self) -> &'a SmartString<Compact> {
+    pub fn get_name(&self) -> &SmartString<Compact> {
         return match &self.custom_name {
             Some(name) => name,
             None => &self.generated_name,
         };
     }
 
-    pub fn set_name(&mut self, new_name: SmartString<Compact>) -> Result<(), AppError> {
-        let new_name = SmartString::from(new_name.trim());
-
-        if new_name.len() > CUSTOM_PLAYER_NAME_LENGTH_LIMIT {
-            return Err(AppError::BadRequest(format!(
-                "Name longer than custom name length limit ({})",
-                CUSTOM_PLAYER_NAME_LENGTH_LIMIT
-            )));
-        }
+    // An empty name resets the player to the generated name
+    pub fn set_name(&mut self, new_name: &str) -> Result<(), AppError> {
+        let new_name = Self::validate_name(new_name)?;
 
         if new_name.is_empty() {
             self.custom_name = None;
@@ -262,11 +510,26 @@ impl Player {
         return Ok(());
     }
 
-    pub fn get_generated_name<'a>(&'a self) -> &'a SmartString<Compact> {
-        return &self.generated_name;
+    pub fn validate_name(new_name: &str) -> Result<SmartString<Compact>, AppError> {
+        let new_name = SmartString::from(new_name.trim());
+
+        if new_name.chars().count() > CUSTOM_PLAYER_NAME_LENGTH_LIMIT {
+            return Err(AppError::BadRequest(format!(
+                "Name longer than custom name length limit ({})",
+                CUSTOM_PLAYER_NAME_LENGTH_LIMIT
+            )));
+        }
+
+        if new_name.chars().any(char::is_control) {
+            return Err(AppError::BadRequest(
+                "Name must not contain control characters".to_string(),
+            ));
+        }
+
+        return Ok(new_name);
     }
 
-    pub fn get_custom_name<'a>(&'a self) -> &'a Option<SmartString<Compact>> {
+    pub fn get_custom_name(&self) -> &Option<SmartString<Compact>> {
         return &self.custom_name;
     }
 
@@ -285,55 +548,65 @@ impl Player {
 
         return Ok(());
     }
+}
+
+pub async fn get_avatar(Path(avatar_index): Path<usize>) -> Result<Response, AppError> {
+    let (_name, svg) = AVATARS.get(avatar_index).ok_or(AppError::NotFound)?;
+
+    use axum::http::header;
+    let mut headers = header::HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, "image/svg+xml".parse().unwrap());
+    headers.insert(header::CACHE_CONTROL, "max-age=604800".parse().unwrap());
 
-    pub fn get_avatar_svg(&self) -> PreEscaped<&'static str> {
-        return PreEscaped(AVATARS[self.avatar_index].1);
-    }*/
+    return Ok((headers, *svg).into_response());
 }
 
 #[derive(Deserialize)]
-pub struct PostMCAnswerForm {
-    pub slide_index: usize,
-    pub answer_indices: ArrayVec<u8, POLL_MAX_MC_ANSWERS>,
+pub struct PostNameAvatarForm {
+    pub name: SmartString<Compact>,
+    pub avatar_index: usize,
 }
 
-pub async fn post_mc_answer(
+pub async fn post_name_avatar(
     Path(poll_id): Path<ShortID>,
     cookies: CookieJar,
-    Json(form): Json<PostMCAnswerForm>,
+    Json(form): Json<PostNameAvatarForm>,
 ) -> Result<Response, AppError> {
-    let live_poll = LIVE_POLL_STORE.get(poll_id).ok_or(AppError::NotFound)?;
+    let live_poll = live_poll_store().get(poll_id).ok_or(AppError::NotFound)?;
     let (session_id, _cookies) = session_id::get_or_create_session_id(cookies);
 
-    let mut live_poll = live_poll.lock().unwrap();
-    if form.slide_index >= live_poll.slides.len() {
-        return Err(AppError::BadRequest(
-            "slide_index out of bounds".to_string(),
-        ));
-    }
+    let player = live_poll
+        .set_player_profile(session_id, form.name, form.avatar_index)
+        .await?;
 
-    let player_index = live_poll.get_player_index(&session_id)?;
-    let start_time = live_poll.get_current_slide_start_time();
+    return Ok(Json(json!({
+        "name": player.get_name(),
+        "avatarIndex": player.get_avatar_index(),
+    }))
+    .into_response());
+}
 
-    let score = if let SlideType::MultipleChoice(mc_answers) =
-        &mut live_poll.slides[form.slide_index].slide_type
-    {
-        mc_answers.submit_answer(player_index, form.answer_indices, start_time)?
-    } else {
-        return Err(AppError::BadRequest(
-            "This is not a multiple choice item".to_string(),
-        ));
-    };
+#[derive(Deserialize)]
+pub struct PostMCAnswerForm {
+    pub slide_index: usize,
+    pub answer_indices: ArrayVec<u8, MC_ANSWERS_CAPACITY>,
+}
 
-    if score > 0 {
-        live_poll
-            .get_current_slide()
-            .submit_score(player_index, score);
-    }
+pub async fn post_mc_answer(
+    Path(poll_id): Path<ShortID>,
+    cookies: CookieJar,
+    Json(form): Json<PostMCAnswerForm>,
+) -> Result<Response, AppError> {
+    let live_poll = live_poll_store().get(poll_id).ok_or(AppError::NotFound)?;
+    let (session_id, _cookies) = session_id::get_or_create_session_id(cookies);
 
-    let _ = live_poll
-        .stats_change_notification_channel_sender
-        .send(form.slide_index);
+    live_poll
+        .submit_answer(
+            session_id,
+            form.slide_index,
+            Answer::MultipleChoice(form.answer_indices),
+        )
+        .await?;
 
     return Ok(html! {}.into_response());
 }
@@ -349,123 +622,100 @@ pub async fn post_ft_answer(
     cookies: CookieJar,
     Form(form): Form<PostFreeTextAnswerForm>,
 ) -> Result<Response, AppError> {
-    let live_poll = LIVE_POLL_STORE.get(poll_id).ok_or(AppError::NotFound)?;
+    let live_poll = live_poll_store().get(poll_id).ok_or(AppError::NotFound)?;
     let (session_id, _cookies) = session_id::get_or_create_session_id(cookies);
 
-    let mut live_poll = live_poll.lock().unwrap();
-    let player_index = live_poll.get_player_index(&session_id)?;
-    if form.slide_index >= live_poll.slides.len() {
-        return Err(AppError::BadRequest(
-            "slide_index out of bounds".to_string(),
-        ));
-    }
+    live_poll
+        .submit_answer(session_id, form.slide_index, Answer::FreeText(form.answer))
+        .await?;
 
-    if let SlideType::FreeText(ft_answers) = &mut live_poll.slides[form.slide_index].slide_type {
-        if ft_answers.player_answers[player_index].is_some() {
-            return Err(AppError::BadRequest(
-                "Already submitted an answer".to_string(),
-            ));
-        }
+    return Ok("Answer submitted".into_response());
+}
 
-        let trimmed_answer = SmartString::from(form.answer.trim());
+#[derive(Deserialize)]
+pub struct PostRatingForm {
+    pub slide_index: usize,
+    pub rating: u8,
+}
 
-        let lowercase_answer = trimmed_answer
-            .to_lowercase()
-            .chars()
-            .take(FREE_TEXT_MAX_CHAR_LENGTH)
-            .collect::<SmartString<Compact>>();
+pub async fn post_rating(
+    Path(poll_id): Path<ShortID>,
+    cookies: CookieJar,
+    Json(form): Json<PostRatingForm>,
+) -> Result<Response, AppError> {
+    let live_poll = live_poll_store().get(poll_id).ok_or(AppError::NotFound)?;
+    let (session_id, _cookies) = session_id::get_or_create_session_id(cookies);
 
-        ft_answers.player_answers[player_index] = Some(form.answer);
+    live_poll
+        .submit_answer(session_id, form.slide_index, Answer::Rating(form.rating))
+        .await?;
 
-        let term_index = ft_answers
-            .word_cloud_terms
-            .iter()
-            .position(|term| term.lowercase_text == lowercase_answer);
+    return Ok("Rating submitted".into_response());
+}
 
-        if let Some(term_index) = term_index {
-            let term = &mut ft_answers.word_cloud_terms[term_index];
-            term.count += 1;
-            if term.count > ft_answers.max_term_count {
-                ft_answers.max_term_count = term.count;
-            }
+#[derive(Deserialize)]
+pub struct PostNumericAnswerForm {
+    pub slide_index: usize,
+    pub answer: f64,
+}
 
-            if let Some(spelling_count) = term.spellings.get_mut(&trimmed_answer) {
-                *spelling_count += 1;
-                if *spelling_count > term.highest_spelling_count {
-                    term.highest_spelling_count = *spelling_count;
-                    term.preferred_spelling = trimmed_answer;
-                }
-            } else {
-                term.spellings.insert(trimmed_answer, 1);
-            }
-        } else {
-            let mut spellings = HashMap::new();
-            spellings.insert(trimmed_answer.clone(), 1);
-
-            ft_answers.word_cloud_terms.push(WordCloudTerm {
-                lowercase_text: lowercase_answer,
-                count: 1,
-                preferred_spelling: SmartString::from(trimmed_answer),
-                spellings,
-                highest_spelling_count: 1,
-            });
-        }
-    } else {
-        return Err(AppError::BadRequest(
-            "This is not a free text item".to_string(),
-        ));
-    };
+pub async fn post_numeric_answer(
+    Path(poll_id): Path<ShortID>,
+    cookies: CookieJar,
+    Json(form): Json<PostNumericAnswerForm>,
+) -> Result<Response, AppError> {
+    let live_poll = live_poll_store().get(poll_id).ok_or(AppError::NotFound)?;
+    let (session_id, _cookies) = session_id::get_or_create_session_id(cookies);
 
-    let _ = live_poll
-        .stats_change_notification_channel_sender
-        .send(form.slide_index);
+    live_poll
+        .submit_answer(
+            session_id,
+            form.slide_index,
+            Answer::NumericEstimate(form.answer),
+        )
+        .await?;
 
     return Ok("Answer submitted".into_response());
 }
 
 #[derive(Deserialize)]
-pub struct PostEmojiForm {
-    pub emoji: SmartString<Compact>,
+pub struct PostRankingForm {
     pub slide_index: usize,
+    pub ordering: ArrayVec<u8, MC_ANSWERS_CAPACITY>,
 }
 
-pub async fn post_emoji(
+pub async fn post_ranking(
     Path(poll_id): Path<ShortID>,
     cookies: CookieJar,
-    Form(form): Form<PostEmojiForm>,
+    Json(form): Json<PostRankingForm>,
 ) -> Result<Response, AppError> {
-    let live_poll = LIVE_POLL_STORE.get(poll_id).ok_or(AppError::NotFound)?;
+    let live_poll = live_poll_store().get(poll_id).ok_or(AppError::NotFound)?;
     let (session_id, _cookies) = session_id::get_or_create_session_id(cookies);
 
-    let mut live_poll = live_poll.lock().unwrap();
-    let player_index = live_poll.get_player_index(&session_id)?;
-    if form.slide_index >= live_poll.slides.len() {
-        return Err(AppError::BadRequest(
-            "slide_index out of bounds".to_string(),
-        ));
-    }
+    live_poll
+        .submit_answer(session_id, form.slide_index, Answer::Ranking(form.ordering))
+        .await?;
 
-    let slide = &mut live_poll.slides[form.slide_index];
-    if let Some(emoji) = slide.player_emojis.get_mut(player_index) {
-        if emoji.is_some() {
-            return Err(AppError::BadRequest("Emoji already submitted".to_string()));
-        }
+    return Ok("Ranking submitted".into_response());
+}
 
-        match form.emoji.as_str() {
-            "heart" => slide.heart_emojis += 1,
-            "thumbsUp" => slide.thumbs_up_emojis += 1,
-            "thumbsDown" => slide.thumbs_down_emojis += 1,
-            "smileyFace" => slide.smiley_face_emojis += 1,
-            "sadFace" => slide.sad_face_emojis += 1,
-            _ => return Err(AppError::BadRequest("Unknown emoji".to_string())),
-        }
+#[derive(Deserialize)]
+pub struct PostEmojiForm {
+    pub emoji: SmartString<Compact>,
+    pub slide_index: usize,
+}
 
-        *emoji = Some(form.emoji.clone());
+pub async fn post_emoji(
+    Path(poll_id): Path<ShortID>,
+    cookies: CookieJar,
+    Form(form): Form<PostEmojiForm>,
+) -> Result<Response, AppError> {
+    let live_poll = live_poll_store().get(poll_id).ok_or(AppError::NotFound)?;
+    let (session_id, _cookies) = session_id::get_or_create_session_id(cookies);
 
-        let _ = live_poll
-            .emoji_channel_sender
-            .send((form.slide_index, form.emoji));
-    }
+    live_poll
+        .submit_emoji(session_id, form.slide_index, form.emoji)
+        .await?;
 
     return Ok("Emoji submitted".into_response());
 }
@@ -475,53 +725,103 @@ pub async fn play_socket(
     Path(poll_id): Path<ShortID>,
     cookies: CookieJar,
 ) -> Result<Response, AppError> {
-    let live_poll = LIVE_POLL_STORE.get(poll_id).ok_or(AppError::NotFound)?;
+    let live_poll = live_poll_store().get(poll_id).ok_or(AppError::NotFound)?;
     let (session_id, _cookies) = session_id::get_or_create_session_id(cookies);
-    let player_index = live_poll.lock().unwrap().get_player_index(&session_id)?;
+    let player_index = live_poll
+        .read(move |live_poll| live_poll.get_player_index(&session_id))
+        .await??;
 
     return Ok(ws.on_upgrade(move |socket| handle_play_socket(socket, live_poll, player_index)));
 }
 
-async fn handle_play_socket(
-    mut socket: WebSocket,
-    live_poll: Arc<Mutex<LivePoll>>,
-    player_index: usize,
-) {
-    let mut slide_index_change_receiver = {
-        let live_poll = live_poll.lock().unwrap();
+async fn handle_play_socket(mut socket: WebSocket, live_poll: LivePollHandle, player_index: usize) {
+    let mut participant_receiver = live_poll.channels.participant_channel_sender.subscribe();
+    let mut questions_receiver = live_poll.channels.questions_channel_sender.subscribe();
+    let mut leaderboard_receiver = live_poll.channels.leaderboard_channel_sender.subscribe();
 
-        live_poll
-            .slide_change_notification_channel_receiver
-            .resubscribe()
+    let Ok(msgs) = live_poll
+        .read(move |live_poll| create_participant_snapshot(live_poll, player_index))
+        .await
+    else {
+        return;
     };
+    for msg in msgs {
+        let _ = socket.send(msg.into()).await;
+    }
 
-    let msg = {
-        let mut live_poll = live_poll.lock().unwrap();
-        let current_slide_index = live_poll.current_slide_index;
-        let slide = live_poll.get_current_slide();
-        create_slide_ws_message(current_slide_index, slide, player_index).into()
-    };
-    let _ = socket.send(msg).await;
+    let mut needs_resync = false;
 
     loop {
         select! {
+            _ = shutdown::requested() => {
+                let _ = socket.send(shutdown::create_server_restarting_ws_message().into()).await;
+                let _ = socket.send(Message::Close(None)).await;
+                return;
+            }
             msg = socket.recv() => {
                 if let Some(Ok(msg)) = msg {
-                    if let Some(_msg) = WSMessage::parse(msg) { }
+                    if let Some(msg) = WSMessage::parse(msg) {
+                        if let Err(e) = handle_participant_message(&live_poll, player_index, msg).await {
+                            let reason = match e {
+                                AppError::BadRequest(reason) => reason,
+                                e => e.to_string(),
+                            };
+                            let msg = WSMessage {
+                                cmd: SmartString::from("requestRejected"),
+                                data: json!({ "reason": reason }),
+                            }.into();
+                            let _  = socket.send(msg).await;
+                        }
+                    }
                 } else {
                     return;
                 }
             }
-            slide_index = slide_index_change_receiver.recv() => {
-                if let Ok(slide_index) = slide_index {
-                    let msg = {
-                        let mut live_poll = live_poll.lock().unwrap();
-                        let slide = live_poll.get_current_slide();
-                        create_slide_ws_message(slide_index, slide, player_index).into()
-                    };
-                    let _  = socket.send(msg).await;
-                } else {
-                    return;
+            questions = questions_receiver.recv() => {
+                match questions {
+                    Ok(mut questions) => {
+                        // Every update contains all questions, so only the newest queued one is sent
+                        loop {
+                            match questions_receiver.try_recv() {
+                                Ok(newer_questions) => questions = newer_questions,
+                                Err(TryRecvError::Lagged(_)) => {}
+                                Err(TryRecvError::Empty) => break,
+                                Err(TryRecvError::Closed) => return,
+                            }
+                        }
+
+                        let (slide_index, questions) = questions;
+                        let msg = format!(
+                            r#"{{"cmd":"updateQuestions","data":{{"slideIndex":{slide_index},"questions":{}}}}}"#,
+                            questions.to_json_string(player_index)
+                        );
+                        let _  = socket.send(Message::Text(msg)).await;
+                    }
+                    // The next update replaces the missed ones
+                    Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => return,
+                }
+            }
+            update = participant_receiver.recv() => {
+                match update {
+                    Ok(ParticipantUpdate::Slide(slide)) => {
+                        let _  = socket.send(slide.create_ws_message(player_index).into()).await;
+                    }
+                    Ok(ParticipantUpdate::VotingState(msg)) => {
+                        let _  = socket.send(msg.into()).await;
+                    }
+                    Err(RecvError::Lagged(_)) => needs_resync = true,
+                    Err(RecvError::Closed) => return,
+                }
+            }
+            leaderboard = leaderboard_receiver.recv() => {
+                match leaderboard {
+                    Ok(leaderboard) => {
+                        let _  = socket.send(leaderboard.create_ws_message(Some(player_index)).into()).await;
+                    }
+                    // The newer leaderboards are still queued, the next receive gets them
+                    Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => return,
                 }
             }
             _ = tokio::time::sleep(tokio::time::Duration::from_secs(15)) => {
@@ -530,62 +830,311 @@ async fn handle_play_socket(
                 }
             }
         };
+
+        // Updates were dropped for this socket, so the participant gets the current state instead.
+        // Subscribing again first makes sure no update falls between the snapshot and the receivers.
+        if needs_resync {
+            needs_resync = false;
+            participant_receiver = participant_receiver.resubscribe();
+            questions_receiver = questions_receiver.resubscribe();
+            leaderboard_receiver = leaderboard_receiver.resubscribe();
+
+            let Ok(msgs) = live_poll
+                .read(move |live_poll| create_participant_snapshot(live_poll, player_index))
+                .await
+            else {
+                return;
+            };
+            for msg in msgs {
+                let _ = socket.send(msg.into()).await;
+            }
+        }
     }
 }
 
-fn create_slide_ws_message(slide_index: usize, slide: &Slide, player_index: usize) -> WSMessage {
-    let emoji = match &slide.player_emojis[player_index] {
-        Some(emoji) => json! { emoji },
-        None => Value::Null,
-    };
+// Everything a participant needs to show the current slide, sent on connect and after lagging behind
+fn create_participant_snapshot(live_poll: &LivePoll, player_index: usize) -> Vec<WSMessage> {
+    let current_slide_index = live_poll.current_slide_index;
+
+    return vec![
+        ParticipantSlide::new(
+            current_slide_index,
+            live_poll.get_current_slide(),
+            Some(player_index),
+        )
+        .create_ws_message(player_index),
+        live_poll.create_voting_state_ws_message(current_slide_index),
+    ];
+}
 
-    let slide_json = match &slide.slide_type {
-        SlideType::MultipleChoice(answers) => {
-            let selected_answer = if answers.allow_multiple_answers {
-                json! { answers.player_answers[player_index].as_ref().unwrap_or(&ArrayVec::new()) }
-            } else {
-                json! {
-                answers.player_answers[player_index]
-                    .as_ref()
-                    .map(|answer_indices| {
-                        let mut s = SmartString::<Compact>::new();
-                        let _ = write!(&mut s, "{}", *answer_indices.get(0).unwrap_or(&0u8));
-                        s
-                    })
-                    .unwrap_or(SmartString::new()) }
-            };
+// Participants ask and upvote Q&A questions over their socket
+async fn handle_participant_message(
+    live_poll: &LivePollHandle,
+    player_index: usize,
+    msg: WSMessage,
+) -> Result<(), AppError> {
+    let slide_index = msg.data["slideIndex"].as_u64().unwrap_or(u64::MAX) as usize;
+
+    match msg.cmd.as_str() {
+        "submitQuestion" => {
+            let text = msg.data["text"].as_str().unwrap_or("").to_string();
+            return live_poll
+                .submit_question(player_index, slide_index, text)
+                .await;
+        }
+        "upvoteQuestion" => {
+            let question_index = msg.data["questionIndex"].as_u64().unwrap_or(u64::MAX) as usize;
+            return live_poll
+                .upvote_question(player_index, slide_index, question_index)
+                .await;
+        }
+        _ => return Err(AppError::BadRequest("Unknown command".to_string())),
+    }
+}
+
+// The current slide as participants see it, built once by the task and shared by the sockets of
+// all participants. Only the answer, emoji and questions of a participant differ.
+pub struct ParticipantSlide {
+    slide_index: usize,
+    // The fields that are the same for every participant
+    slide_json: Value,
+    results: Value,
+    // The selection of participants without an answer and the answers of the others, as the
+    // slide shows them. None for slides that can't be answered.
+    selections: Option<(Value, PlayerMap<Value>)>,
+    player_emojis: PlayerMap<Value>,
+    questions: Option<ParticipantQuestions>,
+}
 
-            json!({
-                "slideType": "mc",
-                "question": slide.question,
-                "answers": answers.answers.iter().map(|(answer_text, _is_correct)| json!({ "text": answer_text })).collect::<Vec<Value>>(),
-                "submitted": answers.player_answers[player_index].is_some(),
-                "selectedAnswer": selected_answer,
-                "allowMultipleMCAnswers": answers.allow_multiple_answers,
-                "emoji": emoji,
-            })
+// Sent in order through one channel, so a voting state never overtakes the slide it belongs to
+#[derive(Clone)]
+pub enum ParticipantUpdate {
+    Slide(Arc<ParticipantSlide>),
+    VotingState(WSMessage),
+}
+
+impl ParticipantSlide {
+    // Contains the answers of all participants, or only those of the given one
+    pub fn new(slide_index: usize, slide: &Slide, player_index: Option<usize>) -> Self {
+        let mut questions = None;
+
+        let (slide_json, selections) = match &slide.slide_type {
+            SlideType::MultipleChoice(answers) => {
+                let slide_json = json!({
+                    "slideType": "mc",
+                    "question": slide.question,
+                    "answers": answers.answers.iter().map(|(answer_text, _is_correct)| json!({ "text": answer_text })).collect::<Vec<Value>>(),
+                    "allowAnswerChanges": slide.allow_answer_changes,
+                    "allowMultipleMCAnswers": answers.allow_multiple_answers,
+                });
+
+                let selections = if answers.allow_multiple_answers {
+                    (
+                        json!([]),
+                        collect_player_values(
+                            &answers.player_answers,
+                            player_index,
+                            |answer_indices| json! { answer_indices },
+                        ),
+                    )
+                } else {
+                    (
+                        json!(""),
+                        collect_player_values(
+                            &answers.player_answers,
+                            player_index,
+                            |answer_indices| {
+                                let mut s = SmartString::<Compact>::new();
+                                let _ =
+                                    write!(&mut s, "{}", *answer_indices.first().unwrap_or(&0u8));
+                                json! { s }
+                            },
+                        ),
+                    )
+                };
+
+                (slide_json, Some(selections))
+            }
+            SlideType::FreeText(answers) => {
+                let slide_json = json!({
+                    "slideType": "ft",
+                    "question": slide.question,
+                    "allowAnswerChanges": slide.allow_answer_changes,
+                });
+
+                (
+                    slide_json,
+                    Some((
+                        json!(""),
+                        collect_player_values(
+                            &answers.player_answers,
+                            player_index,
+                            |answer| json! { answer },
+                        ),
+                    )),
+                )
+            }
+            SlideType::Rating(answers) => {
+                let slide_json = json!({
+                    "slideType": "rating",
+                    "question": slide.question,
+                    "scaleMax": answers.scale_max,
+                    "lowLabel": answers.low_label,
+                    "highLabel": answers.high_label,
+                    "allowAnswerChanges": slide.allow_answer_changes,
+                });
+
+                (
+                    slide_json,
+                    Some((
+                        Value::Null,
+                        collect_player_values(
+                            &answers.player_answers,
+                            player_index,
+                            |rating| json! { rating },
+                        ),
+                    )),
+                )
+            }
+            SlideType::Ranking(answers) => {
+                let slide_json = json!({
+                    "slideType": "rank",
+                    "question": slide.question,
+                    "options": answers.options,
+                    "allowAnswerChanges": slide.allow_answer_changes,
+                });
+
+                // Participants start with the options in their original order
+                let original_ordering = (0..answers.options.len()).collect::<Vec<_>>();
+                let orderings = collect_player_values(
+                    &answers.player_answers,
+                    player_index,
+                    |ordering| {
+                        json! { ordering.iter().map(|option_index| *option_index as usize).collect::<Vec<_>>() }
+                    },
+                );
+
+                (slide_json, Some((json! { original_ordering }, orderings)))
+            }
+            SlideType::Info(content) => {
+                let slide_json = json!({
+                    "slideType": "info",
+                    "question": slide.question,
+                    "html": content.html,
+                });
+
+                (slide_json, None)
+            }
+            SlideType::QandA(answers) => {
+                questions = Some(answers.create_participant_questions(player_index));
+
+                let slide_json = json!({
+                    "slideType": "qa",
+                    "question": slide.question,
+                });
+
+                (slide_json, None)
+            }
+            SlideType::NumericEstimate(answers) => {
+                let slide_json = json!({
+                    "slideType": "num",
+                    "question": slide.question,
+                    "min": answers.min,
+                    "max": answers.max,
+                    "allowAnswerChanges": slide.allow_answer_changes,
+                });
+
+                (
+                    slide_json,
+                    Some((
+                        Value::Null,
+                        collect_player_values(
+                            &answers.player_answers,
+                            player_index,
+                            |answer| json! { answer },
+                        ),
+                    )),
+                )
+            }
+            _ => {
+                let slide_json = json!({
+                    "slideType": "empty",
+                });
+
+                (slide_json, None)
+            }
+        };
+
+        let results = if slide.share_results_with_participants && slide.results_revealed {
+            slide.create_results_json()
+        } else {
+            Value::Null
+        };
+
+        return ParticipantSlide {
+            slide_index,
+            slide_json,
+            results,
+            selections,
+            player_emojis: collect_player_values(
+                &slide.player_emojis,
+                player_index,
+                |emoji| json! { emoji },
+            ),
+            questions,
+        };
+    }
+
+    pub fn create_ws_message(&self, player_index: usize) -> WSMessage {
+        let mut slide_json = self.slide_json.clone();
+
+        slide_json["emoji"] = self
+            .player_emojis
+            .get(&player_index)
+            .cloned()
+            .unwrap_or(Value::Null);
+
+        if let Some((unanswered_selection, selections)) = &self.selections {
+            let selection = selections.get(&player_index);
+            slide_json["submitted"] = json!(selection.is_some());
+            slide_json["selectedAnswer"] = selection.unwrap_or(unanswered_selection).clone();
+        }
+
+        if let Some(questions) = &self.questions {
+            slide_json["questions"] = questions.to_json(player_index);
         }
-        SlideType::FreeText(answers) => {
-            json!({
-                "slideType": "ft",
-                "question": slide.question,
-                "selectedAnswer": answers.player_answers[player_index].as_ref().unwrap_or(&SmartString::new()),
-                "submitted": answers.player_answers[player_index].is_some(),
-                "emoji": emoji,
-            })
+
+        return WSMessage {
+            cmd: SmartString::from("updateSlide"),
+            data: json!({
+                "slideIndex": self.slide_index,
+                "slide": slide_json,
+                "results": self.results,
+            }),
+        };
+    }
+}
+
+// The values of all players, or only the value of the given one
+fn collect_player_values<T>(
+    player_values: &PlayerMap<T>,
+    player_index: Option<usize>,
+    to_json: impl Fn(&T) -> Value,
+) -> PlayerMap<Value> {
+    let mut values = PlayerMap::new();
+
+    match player_index {
+        Some(player_index) => {
+            if let Some(value) = player_values.get(&player_index) {
+                values.insert(player_index, to_json(value));
+            }
         }
-        _ => {
-            json!({
-                "slideType": "empty",
-            })
+        None => {
+            for (player_index, value) in player_values.iter() {
+                values.insert(*player_index, to_json(value));
+            }
         }
-    };
+    }
 
-    return WSMessage {
-        cmd: SmartString::from("updateSlide"),
-        data: json!({
-            "slideIndex": slide_index,
-            "slide": slide_json,
-        }),
-    };
+    return values;
 }
@@ -0,0 +1,116 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+
+// Per-player data of a slide, keyed by player index. Only players that actually answered or
+// reacted have an entry, so joining a poll doesn't touch any slide and a slide with few answers
+// stays small no matter how many players joined.
+#[derive(Serialize, Clone)]
+#[serde(transparent)]
+pub struct PlayerMap<T>(BTreeMap<usize, T>);
+
+impl<T> PlayerMap<T> {
+    pub fn new() -> Self {
+        return PlayerMap(BTreeMap::new());
+    }
+}
+
+impl<T> Default for PlayerMap<T> {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl<T> Deref for PlayerMap<T> {
+    type Target = BTreeMap<usize, T>;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.0;
+    }
+}
+
+impl<T> DerefMut for PlayerMap<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        return &mut self.0;
+    }
+}
+
+// Snapshots and reports written before, stored one entry per player with null for players that
+// did not answer. Both forms are read, new ones are always written as a map.
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for PlayerMap<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PlayerMapVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for PlayerMapVisitor<T> {
+            type Value = PlayerMap<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                return formatter.write_str("a map or a sequence of per-player values");
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut map = PlayerMap::new();
+                while let Some((player_index, value)) = access.next_entry::<usize, T>()? {
+                    map.insert(player_index, value);
+                }
+                return Ok(map);
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut map = PlayerMap::new();
+                let mut player_index = 0usize;
+                while let Some(value) = access.next_element::<Option<T>>()? {
+                    if let Some(value) = value {
+                        map.insert(player_index, value);
+                    }
+                    player_index += 1;
+                }
+                return Ok(map);
+            }
+        }
+
+        return deserializer.deserialize_any(PlayerMapVisitor(PhantomData));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_dense_arrays_are_read() {
+        let player_map: PlayerMap<usize> = serde_json::from_str("[null, 3, null, 0]").unwrap();
+
+        assert_eq!(player_map.len(), 2);
+        assert_eq!(player_map.get(&1), Some(&3));
+        assert_eq!(player_map.get(&3), Some(&0));
+    }
+
+    #[test]
+    fn maps_are_written_and_read() {
+        let mut player_map = PlayerMap::new();
+        player_map.insert(7, "seven".to_string());
+        player_map.insert(2, "two".to_string());
+
+        let json = serde_json::to_string(&player_map).unwrap();
+        assert_eq!(json, r#"{"2":"two","7":"seven"}"#);
+
+        let player_map: PlayerMap<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(player_map.get(&7).map(String::as_str), Some("seven"));
+        assert_eq!(player_map.len(), 2);
+    }
+
+    #[test]
+    fn other_values_are_rejected() {
+        assert!(serde_json::from_str::<PlayerMap<usize>>("3").is_err());
+        assert!(serde_json::from_str::<PlayerMap<usize>>(r#"{"x": 1}"#).is_err());
+    }
+}
@@ -0,0 +1,415 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_error::AppError,
+    config::{
+        INFO_MAX_BODY_LENGTH, NUMERIC_MAX_BUCKETS, POLL_MAX_STR_LEN, RATING_MAX_SCALE,
+        RATING_MIN_SCALE,
+    },
+    markdown::render_markdown,
+    player_map::PlayerMap,
+    settings::settings,
+    slide::{
+        ChartType, FreeTextLiveAnswers, InfoContent, MultipleChoiceLiveAnswers, NumericLiveAnswers,
+        QandALiveAnswers, RankingLiveAnswers, RatingLiveAnswers, Slide, SlideType,
+    },
+};
+
+// Increment this when the format changes in a way older servers can't read anymore
+pub const POLL_DEFINITION_VERSION: u32 = 1;
+
+// The poll as it is created in the host page, sent to /start_poll and downloaded as .json file
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PollDefinition {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    pub slides: Vec<SlideDefinition>,
+    #[serde(default)]
+    pub enable_leaderboard: bool,
+    #[serde(default)]
+    pub allow_custom_names: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SlideDefinition {
+    #[serde(rename = "type")]
+    pub slide_type: SlideTypeDefinition,
+    pub question: String,
+    #[serde(default)]
+    pub mc_answers: Vec<MultipleChoiceAnswerDefinition>,
+    #[serde(default)]
+    pub allow_multiple_mc_answers: bool,
+    #[serde(default)]
+    pub mc_chart_type: ChartType,
+    #[serde(default = "default_rating_scale")]
+    pub rating_scale: u8,
+    #[serde(default)]
+    pub rating_low_label: String,
+    #[serde(default)]
+    pub rating_high_label: String,
+    #[serde(default)]
+    pub numeric_min: f64,
+    #[serde(default = "default_numeric_max")]
+    pub numeric_max: f64,
+    #[serde(default = "default_numeric_buckets")]
+    pub numeric_buckets: usize,
+    #[serde(default)]
+    pub info_body: String,
+    #[serde(default)]
+    pub hide_results: bool,
+    #[serde(default)]
+    pub share_results: bool,
+    #[serde(default)]
+    pub allow_answer_changes: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum SlideTypeDefinition {
+    #[serde(rename = "mc")]
+    MultipleChoice,
+    #[serde(rename = "ft")]
+    FreeText,
+    #[serde(rename = "rating")]
+    Rating,
+    #[serde(rename = "num")]
+    NumericEstimate,
+    #[serde(rename = "rank")]
+    Ranking,
+    #[serde(rename = "qa")]
+    QandA,
+    #[serde(rename = "info")]
+    Info,
+    #[serde(rename = "empty")]
+    Undefined,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultipleChoiceAnswerDefinition {
+    pub text: String,
+    pub is_correct: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PollValidationError {
+    pub slide_index: Option<usize>,
+    pub field: String,
+    pub reason: String,
+}
+
+fn default_version() -> u32 {
+    return POLL_DEFINITION_VERSION;
+}
+
+fn default_rating_scale() -> u8 {
+    return 5;
+}
+
+fn default_numeric_max() -> f64 {
+    return 100f64;
+}
+
+fn default_numeric_buckets() -> usize {
+    return 10;
+}
+
+impl PollDefinition {
+    pub fn parse(json: &str) -> Result<Self, AppError> {
+        let deserializer = &mut serde_json::Deserializer::from_str(json);
+        let definition: PollDefinition =
+            serde_path_to_error::deserialize(deserializer).map_err(|e| {
+                AppError::InvalidPollDefinition(vec![path_to_validation_error(
+                    e.path(),
+                    e.inner().to_string(),
+                )])
+            })?;
+
+        let errors = definition.validate();
+        if !errors.is_empty() {
+            return Err(AppError::InvalidPollDefinition(errors));
+        }
+
+        return Ok(definition);
+    }
+
+    pub fn validate(&self) -> Vec<PollValidationError> {
+        let mut errors = Vec::new();
+
+        if self.version > POLL_DEFINITION_VERSION {
+            errors.push(PollValidationError {
+                slide_index: None,
+                field: "version".to_string(),
+                reason: format!(
+                    "Unsupported version {}, this server supports up to version {}",
+                    self.version, POLL_DEFINITION_VERSION
+                ),
+            });
+        }
+
+        let max_slides = settings().poll_max_slides;
+        if self.slides.len() > max_slides {
+            errors.push(PollValidationError {
+                slide_index: None,
+                field: "slides".to_string(),
+                reason: format!("A poll can contain at most {max_slides} slides"),
+            });
+        }
+
+        for (slide_index, slide) in self.slides.iter().enumerate() {
+            slide.validate(slide_index, &mut errors);
+        }
+
+        return errors;
+    }
+
+    pub fn create_slides(&self) -> Vec<Slide> {
+        let mut slides: Vec<Slide> = self
+            .slides
+            .iter()
+            .map(SlideDefinition::create_slide)
+            .collect();
+
+        if slides.is_empty() {
+            slides.push(Slide::new(String::new(), SlideType::Undefined));
+        }
+
+        return slides;
+    }
+}
+
+impl SlideDefinition {
+    fn validate(&self, slide_index: usize, errors: &mut Vec<PollValidationError>) {
+        let mut error = |field: String, reason: String| {
+            errors.push(PollValidationError {
+                slide_index: Some(slide_index),
+                field,
+                reason,
+            })
+        };
+
+        if self.question.chars().count() > POLL_MAX_STR_LEN {
+            error(
+                "question".to_string(),
+                format!("Must not be longer than {POLL_MAX_STR_LEN} characters"),
+            );
+        }
+
+        if self.slide_type == SlideTypeDefinition::MultipleChoice
+            || self.slide_type == SlideTypeDefinition::Ranking
+        {
+            if self.slide_type == SlideTypeDefinition::Ranking && self.mc_answers.len() < 2 {
+                error(
+                    "mcAnswers".to_string(),
+                    "A ranking slide needs at least two options".to_string(),
+                );
+            } else if self.mc_answers.is_empty() {
+                error(
+                    "mcAnswers".to_string(),
+                    "A multiple choice slide needs at least one answer".to_string(),
+                );
+            }
+
+            let max_answers = settings().poll_max_mc_answers;
+            if self.mc_answers.len() > max_answers {
+                error(
+                    "mcAnswers".to_string(),
+                    format!("A slide can have at most {max_answers} answers"),
+                );
+            }
+
+            for (answer_index, answer) in self.mc_answers.iter().enumerate() {
+                if answer.text.chars().count() > POLL_MAX_STR_LEN {
+                    error(
+                        format!("mcAnswers[{answer_index}].text"),
+                        format!("Must not be longer than {POLL_MAX_STR_LEN} characters"),
+                    );
+                }
+            }
+        }
+
+        if self.slide_type == SlideTypeDefinition::Rating {
+            if !(RATING_MIN_SCALE..=RATING_MAX_SCALE).contains(&self.rating_scale) {
+                error(
+                    "ratingScale".to_string(),
+                    format!("Must be between {RATING_MIN_SCALE} and {RATING_MAX_SCALE}"),
+                );
+            }
+
+            for (field, label) in [
+                ("ratingLowLabel", &self.rating_low_label),
+                ("ratingHighLabel", &self.rating_high_label),
+            ] {
+                if label.chars().count() > POLL_MAX_STR_LEN {
+                    error(
+                        field.to_string(),
+                        format!("Must not be longer than {POLL_MAX_STR_LEN} characters"),
+                    );
+                }
+            }
+        }
+
+        if self.slide_type == SlideTypeDefinition::Info
+            && self.info_body.chars().count() > INFO_MAX_BODY_LENGTH
+        {
+            error(
+                "infoBody".to_string(),
+                format!("Must not be longer than {INFO_MAX_BODY_LENGTH} characters"),
+            );
+        }
+
+        if self.slide_type == SlideTypeDefinition::NumericEstimate {
+            if !self.numeric_min.is_finite() || !self.numeric_max.is_finite() {
+                error(
+                    "numericMin".to_string(),
+                    "The bounds have to be finite numbers".to_string(),
+                );
+            } else if self.numeric_min >= self.numeric_max {
+                error(
+                    "numericMax".to_string(),
+                    "Must be greater than the lower bound".to_string(),
+                );
+            }
+
+            if !(1..=NUMERIC_MAX_BUCKETS).contains(&self.numeric_buckets) {
+                error(
+                    "numericBuckets".to_string(),
+                    format!("Must be between 1 and {NUMERIC_MAX_BUCKETS}"),
+                );
+            }
+        }
+    }
+
+    fn create_slide(&self) -> Slide {
+        let slide_type = match self.slide_type {
+            SlideTypeDefinition::MultipleChoice => {
+                SlideType::MultipleChoice(MultipleChoiceLiveAnswers {
+                    answer_counts: vec![0usize; self.mc_answers.len()],
+                    answers: self
+                        .mc_answers
+                        .iter()
+                        .map(|answer| (answer.text.clone(), answer.is_correct))
+                        .collect(),
+                    player_answers: PlayerMap::new(),
+                    allow_multiple_answers: self.allow_multiple_mc_answers,
+                    chart_type: self.mc_chart_type,
+                })
+            }
+            SlideTypeDefinition::FreeText => SlideType::FreeText(FreeTextLiveAnswers::new()),
+            SlideTypeDefinition::Rating => SlideType::Rating(RatingLiveAnswers::new(
+                self.rating_scale,
+                self.rating_low_label.clone(),
+                self.rating_high_label.clone(),
+            )),
+            SlideTypeDefinition::Ranking => SlideType::Ranking(RankingLiveAnswers::new(
+                self.mc_answers
+                    .iter()
+                    .map(|answer| answer.text.clone())
+                    .collect(),
+            )),
+            SlideTypeDefinition::QandA => SlideType::QandA(QandALiveAnswers::new()),
+            SlideTypeDefinition::Info => SlideType::Info(InfoContent {
+                markdown: self.info_body.clone(),
+                html: render_markdown(&self.info_body),
+            }),
+            SlideTypeDefinition::NumericEstimate => SlideType::NumericEstimate(
+                NumericLiveAnswers::new(self.numeric_min, self.numeric_max, self.numeric_buckets),
+            ),
+            SlideTypeDefinition::Undefined => {
+                return Slide::new(String::new(), SlideType::Undefined);
+            }
+        };
+
+        let mut slide = Slide::new(self.question.clone(), slide_type);
+        slide.hide_results = self.hide_results;
+        slide.share_results_with_participants = self.hide_results && self.share_results;
+        slide.allow_answer_changes = self.allow_answer_changes;
+
+        return slide;
+    }
+}
+
+// Turns a serde path like "slides[2].mcAnswers[0].text" into a slide index and the field inside
+// of that slide, so the host page can show the error next to the right slide.
+fn path_to_validation_error(
+    path: &serde_path_to_error::Path,
+    reason: String,
+) -> PollValidationError {
+    use serde_path_to_error::Segment;
+
+    let segments = path.iter().collect::<Vec<_>>();
+    let (slide_index, field_segments) = match segments.as_slice() {
+        [Segment::Map { key }, Segment::Seq { index }, rest @ ..] if key == "slides" => {
+            (Some(*index), rest)
+        }
+        _ => (None, segments.as_slice()),
+    };
+
+    let mut field = String::new();
+    for segment in field_segments {
+        match segment {
+            Segment::Seq { index } => field.push_str(&format!("[{index}]")),
+            Segment::Map { key } => {
+                if !field.is_empty() {
+                    field.push('.');
+                }
+                field.push_str(key);
+            }
+            Segment::Enum { variant } => field.push_str(variant),
+            Segment::Unknown => {}
+        }
+    }
+
+    return PollValidationError {
+        slide_index,
+        field,
+        reason,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_slide_type_is_rejected() {
+        let json =
+            r#"{"slides": [{"type": "empty", "question": ""}, {"type": "poll", "question": "Q"}]}"#;
+
+        match PollDefinition::parse(json) {
+            Err(AppError::InvalidPollDefinition(errors)) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].slide_index, Some(1));
+                assert_eq!(errors[0].field, "type");
+                assert!(errors[0].reason.contains("unknown variant `poll`"));
+            }
+            _ => panic!("expected an invalid poll definition"),
+        }
+    }
+
+    #[test]
+    fn ranking_slide_without_options_gets_the_ranking_message() {
+        let json = r#"{"slides": [{"type": "rank", "question": "Q", "mcAnswers": []}]}"#;
+
+        match PollDefinition::parse(json) {
+            Err(AppError::InvalidPollDefinition(errors)) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].field, "mcAnswers");
+                assert_eq!(
+                    errors[0].reason,
+                    "A ranking slide needs at least two options"
+                );
+            }
+            _ => panic!("expected an invalid poll definition"),
+        }
+    }
+
+    #[test]
+    fn empty_slide_type_is_accepted() {
+        let slide: SlideDefinition =
+            serde_json::from_str(r#"{"type": "empty", "question": ""}"#).unwrap();
+        assert!(slide.slide_type == SlideTypeDefinition::Undefined);
+    }
+}
@@ -0,0 +1,146 @@
+use axum::{
+    extract::{Path, Query},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+};
+use qrcode::{render::svg, Color, EcLevel, QrCode};
+use serde::Deserialize;
+
+use crate::{
+    app_error::AppError,
+    config::{QR_CODE_DEFAULT_SIZE, QR_CODE_MAX_SIZE, QR_CODE_MIN_SIZE},
+    live_poll_store::{live_poll_store, ShortID},
+    settings::settings,
+};
+
+const DARK_COLOR: &str = "#334155";
+const DARK_COLOR_RGB: [u8; 3] = [0x33, 0x41, 0x55];
+const QUIET_ZONE_MODULES: usize = 4;
+
+#[derive(Deserialize)]
+pub struct QrCodeParams {
+    // Edge length of the image in pixels, the modules are scaled to whole pixels for png
+    size: Option<u32>,
+    // Error correction level L, M, Q or H. Higher levels survive more damage, e.g. on printouts.
+    ec: Option<String>,
+}
+
+enum ImageFormat {
+    Svg,
+    Png,
+}
+
+// Join link of a poll as QR code, e.g. /qr/1234.svg or /qr/1234.png?size=1024&ec=H
+pub async fn get_qr_code(
+    headers: HeaderMap,
+    Path(file_name): Path<String>,
+    Query(params): Query<QrCodeParams>,
+) -> Result<Response, AppError> {
+    let (poll_id, format) = match file_name.rsplit_once('.') {
+        Some((poll_id, "svg")) => (poll_id, ImageFormat::Svg),
+        Some((poll_id, "png")) => (poll_id, ImageFormat::Png),
+        _ => return Err(AppError::NotFound),
+    };
+    let poll_id = poll_id.parse::<ShortID>().map_err(|_| AppError::NotFound)?;
+
+    if live_poll_store().get(poll_id).is_none() {
+        return Err(AppError::NotFound);
+    }
+
+    let size = params.size.unwrap_or(QR_CODE_DEFAULT_SIZE);
+    if !(QR_CODE_MIN_SIZE..=QR_CODE_MAX_SIZE).contains(&size) {
+        return Err(AppError::BadRequest(format!(
+            "size has to be between {QR_CODE_MIN_SIZE} and {QR_CODE_MAX_SIZE}"
+        )));
+    }
+
+    let ec_level = match params.ec.as_deref().unwrap_or("M") {
+        "L" | "l" => EcLevel::L,
+        "M" | "m" => EcLevel::M,
+        "Q" | "q" => EcLevel::Q,
+        "H" | "h" => EcLevel::H,
+        _ => {
+            return Err(AppError::BadRequest(
+                "ec has to be one of L, M, Q or H".to_string(),
+            ))
+        }
+    };
+
+    let join_link = format!("{}/p?c={poll_id}", get_public_base_url(&headers));
+    let code = QrCode::with_error_correction_level(join_link.as_bytes(), ec_level)
+        .map_err(|e| AppError::OtherInternalServerError(format!("Error creating QR code: {e}")))?;
+
+    return match format {
+        ImageFormat::Svg => {
+            let image = code
+                .render::<svg::Color>()
+                .min_dimensions(size, size)
+                .max_dimensions(size, size)
+                .dark_color(svg::Color(DARK_COLOR))
+                .light_color(svg::Color("#ffffff"))
+                .build();
+
+            Ok(([(header::CONTENT_TYPE, "image/svg+xml")], image).into_response())
+        }
+        ImageFormat::Png => {
+            let image = render_png(&code, size)?;
+            Ok(([(header::CONTENT_TYPE, "image/png")], image).into_response())
+        }
+    };
+}
+
+// public_base_url is needed behind reverse proxies, otherwise the link is built from the request
+pub fn get_public_base_url(headers: &HeaderMap) -> String {
+    if let Some(base_url) = &settings().public_base_url {
+        return base_url.clone();
+    }
+
+    let host = headers
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .unwrap_or("localhost");
+    let scheme = headers
+        .get("x-forwarded-proto")
+        .and_then(|scheme| scheme.to_str().ok())
+        .unwrap_or("http");
+
+    return format!("{scheme}://{host}");
+}
+
+fn render_png(code: &QrCode, size: u32) -> Result<Vec<u8>, AppError> {
+    let num_modules = code.width() + 2 * QUIET_ZONE_MODULES;
+    let module_size = (size as usize / num_modules).max(1);
+    let image_size = num_modules * module_size;
+    let colors = code.to_colors();
+
+    let mut pixels = vec![0xffu8; image_size * image_size * 3];
+    for (module_index, color) in colors.iter().enumerate() {
+        if *color == Color::Light {
+            continue;
+        }
+
+        let module_x = module_index % code.width() + QUIET_ZONE_MODULES;
+        let module_y = module_index / code.width() + QUIET_ZONE_MODULES;
+        for y in module_y * module_size..(module_y + 1) * module_size {
+            let row_start = (y * image_size + module_x * module_size) * 3;
+            for pixel in pixels[row_start..row_start + module_size * 3].chunks_exact_mut(3) {
+                pixel.copy_from_slice(&DARK_COLOR_RGB);
+            }
+        }
+    }
+
+    let mut image = Vec::new();
+    let mut encoder = png::Encoder::new(&mut image, image_size as u32, image_size as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    encoder
+        .write_header()
+        .and_then(|mut writer| {
+            writer.write_image_data(&pixels)?;
+            writer.finish()
+        })
+        .map_err(|e| AppError::OtherInternalServerError(format!("Error encoding QR code: {e}")))?;
+
+    return Ok(image);
+}
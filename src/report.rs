@@ -0,0 +1,644 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{Path, Query},
+    http::HeaderMap,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::CookieJar;
+use maud::{html, Markup, PreEscaped, DOCTYPE};
+use serde::{Deserialize, Serialize};
+use smartstring::{Compact, SmartString};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    app_error::AppError,
+    charts,
+    config::LEADERBOARD_SIZE,
+    export::{self, ExportParams},
+    html_page::{self, render_header},
+    live_poll::LivePoll,
+    live_poll_store::{live_poll_store, ShortID},
+    markdown::MARKDOWN_CLASSES,
+    select_language, session_id,
+    settings::settings,
+    slide::{ChartType, ResultSnapshot, Slide, SlideType},
+    svg_icons::SvgIcon,
+};
+
+// The frozen results of a poll after it ended, kept for the configured retention time
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SessionReport {
+    pub poll_id: ShortID,
+    pub host_session_id: Uuid,
+    pub slides: Vec<Slide>,
+    pub num_participants: usize,
+    // (name, score) of the best players, only filled for polls in quiz mode
+    pub leaderboard: Vec<(SmartString<Compact>, usize)>,
+    // Shared reports can be opened by everyone with the link, otherwise only by the host
+    pub is_shared: bool,
+    #[serde(with = "time::serde::timestamp")]
+    pub ended_at: OffsetDateTime,
+    // Wall clock time, so the retention time keeps counting across restarts
+    #[serde(with = "time::serde::timestamp")]
+    pub expires_at: OffsetDateTime,
+}
+
+static REPORTS: Mutex<BTreeMap<Uuid, Arc<SessionReport>>> = Mutex::new(BTreeMap::new());
+// Held while a report is written to or deleted from the storage backend
+static REPORT_WRITE_LOCK: Mutex<()> = Mutex::new(());
+pub fn are_reports_enabled() -> bool {
+    return settings().report_retention_hours > 0;
+}
+
+fn remove_expired_reports(reports: &mut BTreeMap<Uuid, Arc<SessionReport>>) {
+    let now = OffsetDateTime::now_utc();
+    reports.retain(|report_id, report| {
+        let is_expired = report.expires_at <= now;
+        if is_expired {
+            sync_report(*report_id);
+        }
+        !is_expired
+    });
+}
+
+// Writes the current state of a report through the storage backend, or deletes it there if the
+// report is gone. Runs on the blocking pool and reads the report only once it holds the write
+// lock, so the last write always contains the latest state.
+fn sync_report(report_id: Uuid) {
+    tokio::task::spawn_blocking(move || {
+        let _write_lock = REPORT_WRITE_LOCK.lock().unwrap();
+
+        let report = REPORTS
+            .lock()
+            .unwrap()
+            .get(&report_id)
+            .map(|report| serde_json::to_vec(report.as_ref()));
+
+        match report {
+            Some(Ok(report)) => live_poll_store().write_report(&report_id, &report),
+            Some(Err(e)) => error!("Error serializing report {report_id}: {e}"),
+            None => live_poll_store().delete_report(&report_id),
+        }
+    });
+}
+
+// Loads the reports that were persisted before the last shutdown, expired ones are deleted
+pub fn restore_reports() {
+    let now = OffsetDateTime::now_utc();
+    let mut reports = REPORTS.lock().unwrap();
+
+    for (report_id, report) in live_poll_store().load_reports() {
+        match serde_json::from_slice::<SessionReport>(&report) {
+            Ok(report) if are_reports_enabled() && report.expires_at > now => {
+                reports.insert(report_id, Arc::new(report));
+            }
+            Ok(_) => live_poll_store().delete_report(&report_id),
+            Err(e) => error!("Error restoring report {report_id}: {e}"),
+        }
+    }
+
+    if !reports.is_empty() {
+        info!("Restored {} reports", reports.len());
+    }
+}
+
+pub fn store_report(poll_id: ShortID, live_poll: &LivePoll) {
+    if !are_reports_enabled() {
+        return;
+    }
+
+    let leaderboard = if live_poll.leaderboard_enabled {
+        live_poll
+            .get_leaderboard()
+            .into_iter()
+            .take(LEADERBOARD_SIZE)
+            .map(|(player_index, score)| {
+                (live_poll.get_player(player_index).get_name().clone(), score)
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let ended_at = OffsetDateTime::now_utc();
+    let report = SessionReport {
+        poll_id,
+        host_session_id: live_poll.host_session_id,
+        slides: live_poll.slides.clone(),
+        num_participants: live_poll.players.len(),
+        leaderboard,
+        is_shared: false,
+        ended_at,
+        expires_at: ended_at + settings().report_retention(),
+    };
+
+    let mut reports = REPORTS.lock().unwrap();
+    remove_expired_reports(&mut reports);
+    reports.insert(live_poll.report_id, Arc::new(report));
+    sync_report(live_poll.report_id);
+}
+
+// The report of the poll this host ended most recently, if it did not expire yet
+pub fn get_latest_report_id(host_session_id: &Uuid) -> Option<Uuid> {
+    let mut reports = REPORTS.lock().unwrap();
+    remove_expired_reports(&mut reports);
+
+    return reports
+        .iter()
+        .filter(|(_, report)| report.host_session_id == *host_session_id)
+        .max_by_key(|(_, report)| report.ended_at)
+        .map(|(report_id, _)| *report_id);
+}
+
+// Unknown and expired reports, as well as reports that are not shared with the requesting
+// session, are all treated as not found, so the link does not reveal whether a report exists.
+// The lock is only held for the lookup, pages are rendered from the shared report afterwards.
+fn get_report(
+    report_id: &Uuid,
+    session_id: &Uuid,
+    require_host: bool,
+) -> Result<(Arc<SessionReport>, bool), AppError> {
+    let mut reports = REPORTS.lock().unwrap();
+    remove_expired_reports(&mut reports);
+
+    let report = reports.get(report_id).ok_or(AppError::NotFound)?;
+    let is_host = report.host_session_id == *session_id;
+
+    if !is_host && (require_host || !report.is_shared) {
+        return Err(AppError::NotFound);
+    }
+
+    return Ok((report.clone(), is_host));
+}
+
+pub async fn get_report_page(
+    cookies: CookieJar,
+    headers: HeaderMap,
+    Path(report_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let l = select_language(&cookies, &headers);
+    let (session_id, cookies) = session_id::get_or_create_session_id(cookies);
+
+    let (report, is_host) = get_report(&report_id, &session_id, false)?;
+    let html = render_report_page(&report_id, &report, is_host, &l);
+
+    return Ok((cookies, html).into_response());
+}
+
+// Standalone page without scripts, meant to be printed or saved as PDF by the browser
+pub async fn get_report_print_page(
+    cookies: CookieJar,
+    headers: HeaderMap,
+    Path(report_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let l = select_language(&cookies, &headers);
+    let (session_id, cookies) = session_id::get_or_create_session_id(cookies);
+
+    let (report, _) = get_report(&report_id, &session_id, false)?;
+    let html = render_print_page(&report, &l);
+
+    return Ok((cookies, html).into_response());
+}
+
+pub async fn post_share_report(
+    cookies: CookieJar,
+    Path(report_id): Path<Uuid>,
+    body: String,
+) -> Result<Response, AppError> {
+    let (session_id, _cookies) = session_id::get_or_create_session_id(cookies);
+
+    let is_shared = match body.trim() {
+        "true" => true,
+        "false" => false,
+        _ => {
+            return Err(AppError::BadRequest(
+                "Body has to be true or false".to_string(),
+            ))
+        }
+    };
+
+    get_report(&report_id, &session_id, true)?;
+    if let Some(report) = REPORTS.lock().unwrap().get_mut(&report_id) {
+        Arc::make_mut(report).is_shared = is_shared;
+    }
+    sync_report(report_id);
+
+    return Ok("Updated successfully".into_response());
+}
+
+pub async fn get_report_export(
+    cookies: CookieJar,
+    Path((report_id, format)): Path<(Uuid, String)>,
+    Query(params): Query<ExportParams>,
+) -> Result<Response, AppError> {
+    let (session_id, _cookies) = session_id::get_or_create_session_id(cookies);
+
+    let (report, _) = get_report(&report_id, &session_id, true)?;
+
+    return export::create_export_response(
+        report.poll_id,
+        &report.slides,
+        report.num_participants,
+        &format,
+        params.slide,
+    );
+}
+
+fn format_date_time(date_time: &OffsetDateTime) -> String {
+    return format!(
+        "{}-{:02}-{:02} {:02}:{:02} UTC",
+        date_time.year(),
+        date_time.month() as u8,
+        date_time.day(),
+        date_time.hour(),
+        date_time.minute()
+    );
+}
+
+fn render_report_page(report_id: &Uuid, report: &SessionReport, is_host: bool, l: &str) -> Markup {
+    let expires_in_hours = (report.expires_at - OffsetDateTime::now_utc())
+        .whole_seconds()
+        .max(0)
+        .unsigned_abs()
+        .div_ceil(60 * 60);
+
+    return html_page::render_html_page(
+        "Svoote - Results",
+        l,
+        html! {
+            (render_header(html! {}))
+            div ."mx-6 sm:mx-14 mb-16 max-w-4xl lg:mx-auto" {
+                h1 ."text-3xl text-slate-900 font-bold tracking-tight" { (t!("report_title", locale=l)) }
+                p ."mt-2 text-sm text-slate-500" {
+                    (t!("report_ended_at", locale=l)) " " (format_date_time(&report.ended_at))
+                    " · " (report.num_participants) " " (t!("participants", locale=l))
+                }
+                a href={ "/report/" (report_id) "/print" } target="_blank"
+                    ."mt-3 inline-flex items-center gap-1.5 text-sm text-cyan-600 hover:underline"
+                {
+                    div ."size-4" { (SvgIcon::BarChart2.render()) }
+                    (t!("print_report", locale=l))
+                }
+                @if is_host {
+                    div ."mt-6 px-5 py-4 flex flex-wrap items-center justify-between gap-4 text-sm bg-slate-100 rounded-lg"
+                        x-data={ "{ isShared: " (report.is_shared) " }" }
+                    {
+                        div {
+                            label ."flex gap-2 items-center text-slate-700 font-medium" {
+                                input type="checkbox" x-model="isShared" ."accent-cyan-600"
+                                    "@change"={ "fetch('/report/" (report_id) "/share', { method: 'POST', body: String(isShared) })" };
+                                (t!("share_with_participants", locale=l))
+                            }
+                            p ."ml-6 mt-1 text-xs text-slate-500" {
+                                (t!("report_private_link_explanation", locale=l)) " "
+                                (t!("report_expires_in", locale=l, hours=expires_in_hours))
+                            }
+                        }
+                        div ."flex items-center gap-3 text-slate-500" {
+                            div ."size-4" { (SvgIcon::Download.render()) }
+                            a href={ "/report/" (report_id) "/export/csv" } download ."hover:text-slate-900" { "CSV" }
+                            a href={ "/report/" (report_id) "/export/json" } download ."hover:text-slate-900" { "JSON" }
+                        }
+                    }
+                }
+                @if !report.leaderboard.is_empty() {
+                    section ."mt-10" {
+                        h2 ."mb-3 text-xl text-slate-800 font-semibold" { (t!("leaderboard", locale=l)) }
+                        ol ."text-sm" {
+                            @for (position, (name, score)) in report.leaderboard.iter().enumerate() {
+                                li ."py-1.5 flex justify-between border-b" {
+                                    span { (position + 1) ". " (name) }
+                                    span ."text-slate-500" { (score) " " (t!("points", locale=l)) }
+                                }
+                            }
+                        }
+                    }
+                }
+                @for (slide_index, slide) in report.slides.iter().enumerate() {
+                    section ."mt-10 pt-6 border-t" {
+                        p ."text-xs text-slate-400 font-medium uppercase" { (t!("slide", locale=l)) " " (slide_index + 1) }
+                        h2 ."mt-1 mb-4 text-xl text-slate-800 font-semibold" { (slide.question) }
+                        (render_slide_results(slide, l))
+                        @for round in slide.rounds.iter().rev() {
+                            div ."mt-6" {
+                                h3 ."mb-2 text-sm text-slate-500 font-medium" {
+                                    (t!("round", locale=l)) " " (round.round_number)
+                                    " · " (round.num_responses) " " (t!("responses", locale=l))
+                                }
+                                (render_snapshot(&round.results))
+                            }
+                        }
+                    }
+                }
+            }
+        },
+        true,
+    );
+}
+
+fn render_bars(rows: &[(String, usize, bool)]) -> Markup {
+    let max_count = rows
+        .iter()
+        .map(|(_, count, _)| *count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    return html! {
+        div ."flex flex-col gap-2 text-sm" {
+            @for (row_index, (label, count, is_highlighted)) in rows.iter().enumerate() {
+                div ."grid grid-cols-[12rem_1fr_3rem] items-center gap-3" {
+                    span ."flex items-center gap-1.5 truncate" ."font-semibold"[*is_highlighted] {
+                        @if *is_highlighted { span ."size-4 shrink-0 text-green-600" { (SvgIcon::Check.render()) } }
+                        (label)
+                    }
+                    div ."h-4 bg-slate-100 rounded-sm" {
+                        div .{ "h-full rounded-sm " (settings().color_class(row_index)) }
+                            style={ "width: " (*count as f64 / max_count as f64 * 100f64) "%" } {}
+                    }
+                    span ."text-right text-slate-500" { (count) }
+                }
+            }
+        }
+    };
+}
+
+// Most mentioned terms first, scaled by their count
+fn render_terms(mut terms: Vec<(&str, usize)>) -> Markup {
+    terms.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let max_count = terms
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    return html! {
+        div ."flex flex-wrap items-baseline gap-x-4 gap-y-2" {
+            @for (text, count) in &terms {
+                span ."text-slate-700"
+                    style={ "font-size: " (0.875 + 1.125 * *count as f64 / max_count as f64) "rem" }
+                    title=(count)
+                    { (text) }
+            }
+        }
+    };
+}
+
+fn render_snapshot(snapshot: &ResultSnapshot) -> Markup {
+    return match snapshot {
+        ResultSnapshot::MultipleChoice {
+            answers,
+            answer_counts,
+        } => render_bars(
+            &answers
+                .iter()
+                .zip(answer_counts.iter())
+                .map(|(text, count)| (text.clone(), *count, false))
+                .collect::<Vec<_>>(),
+        ),
+        ResultSnapshot::FreeText { terms } => render_terms(
+            terms
+                .iter()
+                .map(|(_, spelling, count)| (spelling.as_str(), *count))
+                .collect(),
+        ),
+        ResultSnapshot::Other(_) => html! {},
+    };
+}
+
+fn render_slide_results(slide: &Slide, l: &str) -> Markup {
+    let num_responses = slide.get_num_responses();
+
+    return html! {
+        @if slide.has_results() {
+            p ."mb-3 text-sm text-slate-500" { (num_responses) " " (t!("responses", locale=l)) }
+        }
+        @match &slide.slide_type {
+            SlideType::MultipleChoice(answers) => {
+                (render_bars(&answers.answers.iter().zip(answers.answer_counts.iter()).map(|((text, is_correct), count)| (text.clone(), *count, *is_correct)).collect::<Vec<_>>()))
+            }
+            SlideType::FreeText(answers) => {
+                (render_terms(answers.word_cloud_terms.iter().map(|term| (term.preferred_spelling.as_str(), term.count)).collect()))
+            }
+            SlideType::Rating(answers) => {
+                (render_bars(&answers.rating_counts.iter().enumerate().map(|(rating, count)| ((rating + 1).to_string(), *count, false)).collect::<Vec<_>>()))
+                @if let Some(stats) = answers.get_stats() {
+                    p ."mt-3 text-sm text-slate-500" {
+                        (t!("mean", locale=l)) " " (format!("{:.2}", stats.mean)) " · "
+                        (t!("median", locale=l)) " " (stats.median) " · "
+                        (t!("standard_deviation", locale=l)) " " (format!("{:.2}", stats.standard_deviation))
+                    }
+                }
+            }
+            SlideType::NumericEstimate(answers) => {
+                @let bucket_width = (answers.max - answers.min) / answers.bucket_counts.len() as f64;
+                (render_bars(&answers.bucket_counts.iter().enumerate().map(|(bucket_index, count)| {
+                    let lower = answers.min + bucket_index as f64 * bucket_width;
+                    (format!("{} – {}", lower, lower + bucket_width), *count, false)
+                }).collect::<Vec<_>>()))
+                @if let Some(stats) = answers.get_stats() {
+                    p ."mt-3 text-sm text-slate-500" {
+                        (t!("min", locale=l)) " " (stats.min) " · "
+                        (t!("max", locale=l)) " " (stats.max) " · "
+                        (t!("mean", locale=l)) " " (format!("{:.2}", stats.mean)) " · "
+                        (t!("median", locale=l)) " " (stats.median)
+                    }
+                }
+            }
+            SlideType::Ranking(answers) => {
+                @let average_positions = answers.get_average_positions();
+                ol ."text-sm" {
+                    @for (position, option_index) in answers.get_aggregated_ranking().into_iter().enumerate() {
+                        li ."py-1.5 flex justify-between border-b" {
+                            span { (position + 1) ". " (answers.options[option_index]) }
+                            @if let Some(average_position) = average_positions[option_index] {
+                                span ."text-slate-500" { (t!("average_position", locale=l)) " " (format!("{:.2}", average_position)) }
+                            }
+                        }
+                    }
+                }
+            }
+            SlideType::QandA(answers) => {
+                ul ."text-sm" {
+                    @for question_index in answers.get_sorted_question_indices(false) {
+                        @let question = &answers.questions[question_index];
+                        li ."py-1.5 flex justify-between gap-4 border-b" {
+                            span ."text-slate-400"[question.is_answered] { (question.text) }
+                            span ."shrink-0 text-slate-500" { "▲ " (question.upvotes) }
+                        }
+                    }
+                }
+            }
+            SlideType::Info(content) => {
+                div .(MARKDOWN_CLASSES) { (PreEscaped(&content.html)) }
+            }
+            SlideType::Undefined => {}
+        }
+        p ."mt-4 flex gap-4 text-sm text-slate-500" {
+            span { "❤️ " (slide.heart_emojis) }
+            span { "👍 " (slide.thumbs_up_emojis) }
+            span { "👎 " (slide.thumbs_down_emojis) }
+            span { "😀 " (slide.smiley_face_emojis) }
+            span { "🙁 " (slide.sad_face_emojis) }
+        }
+    };
+}
+
+const PRINT_STYLES: &str = "
+    @page { size: A4; margin: 18mm 16mm; }
+    body { max-width: 180mm; margin: 0 auto; padding: 8mm 0; font-family: Arial, Helvetica, sans-serif; color: #334155; }
+    h1 { margin: 0; font-size: 22pt; color: #0f172a; }
+    h2 { margin: 2pt 0 8pt 0; font-size: 14pt; color: #1e293b; }
+    h3 { margin: 12pt 0 4pt 0; font-size: 10pt; color: #64748b; font-weight: normal; }
+    .meta { margin-top: 4pt; font-size: 10pt; color: #64748b; }
+    .slide-number { font-size: 8pt; color: #94a3b8; text-transform: uppercase; }
+    section { margin-top: 18pt; padding-top: 12pt; border-top: 1px solid #e2e8f0; break-inside: avoid; }
+    table { width: 100%; border-collapse: collapse; font-size: 10pt; }
+    td { padding: 4pt 0; border-bottom: 1px solid #e2e8f0; }
+    td.number { text-align: right; color: #64748b; }
+    .answered { color: #94a3b8; }
+    .print-button { margin-top: 12pt; padding: 6pt 14pt; font-size: 10pt; cursor: pointer; }
+    @media print { .print-button { display: none; } }
+";
+
+fn render_print_page(report: &SessionReport, l: &str) -> Markup {
+    return html! {
+        (DOCTYPE)
+        html lang=(l) {
+            head {
+                meta charset="UTF-8";
+                title { "Svoote - " (t!("report_title", locale=l)) " " (format_date_time(&report.ended_at)) }
+                style { (PreEscaped(PRINT_STYLES)) }
+            }
+            body {
+                h1 { (t!("report_title", locale=l)) }
+                p .meta {
+                    (t!("report_ended_at", locale=l)) " " (format_date_time(&report.ended_at))
+                    " · " (report.num_participants) " " (t!("participants", locale=l))
+                }
+                button .print-button onclick="window.print()" { (t!("print_report", locale=l)) }
+                @if !report.leaderboard.is_empty() {
+                    section {
+                        h2 { (t!("leaderboard", locale=l)) }
+                        table {
+                            @for (position, (name, score)) in report.leaderboard.iter().enumerate() {
+                                tr {
+                                    td { (position + 1) ". " (name) }
+                                    td .number { (score) " " (t!("points", locale=l)) }
+                                }
+                            }
+                        }
+                    }
+                }
+                @for (slide_index, slide) in report.slides.iter().enumerate() {
+                    section {
+                        div .slide-number { (t!("slide", locale=l)) " " (slide_index + 1) }
+                        h2 { (slide.question) }
+                        @if slide.has_results() {
+                            p .meta { (slide.get_num_responses()) " " (t!("responses", locale=l)) }
+                        }
+                        (render_slide_chart(slide, l))
+                        @for round in slide.rounds.iter().rev() {
+                            @if !matches!(round.results, ResultSnapshot::Other(_)) {
+                                h3 {
+                                    (t!("round", locale=l)) " " (round.round_number)
+                                    " · " (round.num_responses) " " (t!("responses", locale=l))
+                                }
+                                (render_snapshot_chart(&round.results))
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+fn render_snapshot_chart(snapshot: &ResultSnapshot) -> Markup {
+    return match snapshot {
+        ResultSnapshot::MultipleChoice {
+            answers,
+            answer_counts,
+        } => charts::render_bar_chart(
+            &answers
+                .iter()
+                .zip(answer_counts.iter())
+                .map(|(text, count)| (text.clone(), *count, false))
+                .collect::<Vec<_>>(),
+        ),
+        ResultSnapshot::FreeText { terms } => charts::render_word_cloud(
+            &terms
+                .iter()
+                .map(|(_, spelling, count)| (spelling.as_str(), *count))
+                .collect::<Vec<_>>(),
+        ),
+        ResultSnapshot::Other(_) => html! {},
+    };
+}
+
+fn render_slide_chart(slide: &Slide, l: &str) -> Markup {
+    return html! {
+        @match &slide.slide_type {
+            SlideType::MultipleChoice(answers) => {
+                @if answers.chart_type == ChartType::Pie {
+                    (charts::render_pie_chart(&answers.answers.iter().zip(answers.answer_counts.iter()).map(|((text, _), count)| (text.clone(), *count)).collect::<Vec<_>>()))
+                } @else {
+                    (charts::render_bar_chart(&answers.answers.iter().zip(answers.answer_counts.iter()).map(|((text, is_correct), count)| (text.clone(), *count, *is_correct)).collect::<Vec<_>>()))
+                }
+            }
+            SlideType::FreeText(answers) => {
+                (charts::render_word_cloud(&answers.word_cloud_terms.iter().map(|term| (term.preferred_spelling.as_str(), term.count)).collect::<Vec<_>>()))
+            }
+            SlideType::Rating(answers) => {
+                (charts::render_column_chart(&answers.rating_counts.iter().enumerate().map(|(rating, count)| ((rating + 1).to_string(), *count)).collect::<Vec<_>>()))
+                @if let Some(stats) = answers.get_stats() {
+                    p .meta {
+                        (t!("mean", locale=l)) " " (format!("{:.2}", stats.mean)) " · "
+                        (t!("median", locale=l)) " " (stats.median) " · "
+                        (t!("standard_deviation", locale=l)) " " (format!("{:.2}", stats.standard_deviation))
+                    }
+                }
+            }
+            SlideType::NumericEstimate(answers) => {
+                @let bucket_width = (answers.max - answers.min) / answers.bucket_counts.len() as f64;
+                (charts::render_column_chart(&answers.bucket_counts.iter().enumerate().map(|(bucket_index, count)| {
+                    (format!("{}", answers.min + bucket_index as f64 * bucket_width), *count)
+                }).collect::<Vec<_>>()))
+                @if let Some(stats) = answers.get_stats() {
+                    p .meta {
+                        (t!("min", locale=l)) " " (stats.min) " · "
+                        (t!("max", locale=l)) " " (stats.max) " · "
+                        (t!("mean", locale=l)) " " (format!("{:.2}", stats.mean)) " · "
+                        (t!("median", locale=l)) " " (stats.median)
+                    }
+                }
+            }
+            SlideType::Ranking(answers) => {
+                (charts::render_bar_chart(&answers.get_aggregated_ranking().into_iter().enumerate().map(|(position, option_index)| {
+                    (format!("{}. {}", position + 1, answers.options[option_index]), answers.borda_scores[option_index], false)
+                }).collect::<Vec<_>>()))
+            }
+            SlideType::QandA(answers) => {
+                table {
+                    @for question_index in answers.get_sorted_question_indices(false) {
+                        @let question = &answers.questions[question_index];
+                        tr {
+                            td .answered[question.is_answered] { (question.text) }
+                            td .number { "▲ " (question.upvotes) }
+                        }
+                    }
+                }
+            }
+            SlideType::Info(content) => {
+                div { (PreEscaped(&content.html)) }
+            }
+            SlideType::Undefined => {}
+        }
+    };
+}
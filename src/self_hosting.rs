@@ -0,0 +1,69 @@
+use std::{path::PathBuf, sync::OnceLock};
+
+use axum::http::HeaderMap;
+
+use crate::{markdown::render_markdown, qr_code::get_public_base_url, settings::settings};
+
+// Operators running their own instance set self_hosted or build with the self-hosted feature.
+// This leaves out everything tied to svoote.com: analytics, site verification, the cookie banner
+// and our legal pages. Their own legal pages can be supplied as markdown files instead.
+
+pub struct LegalPages {
+    pub imprint: Option<String>,
+    pub privacy_policy: Option<String>,
+    pub terms_of_service: Option<String>,
+}
+
+static LEGAL_PAGES: OnceLock<Option<LegalPages>> = OnceLock::new();
+
+pub fn init() -> Result<(), String> {
+    let settings = settings();
+
+    let legal_pages = if settings.is_self_hosted() {
+        info!("Running in self-hosted mode");
+
+        Some(LegalPages {
+            imprint: read_legal_page(&settings.imprint_file)?,
+            privacy_policy: read_legal_page(&settings.privacy_policy_file)?,
+            terms_of_service: read_legal_page(&settings.terms_of_service_file)?,
+        })
+    } else {
+        None
+    };
+
+    let _ = LEGAL_PAGES.set(legal_pages);
+    return Ok(());
+}
+
+fn read_legal_page(path: &Option<PathBuf>) -> Result<Option<String>, String> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+
+    let markdown =
+        std::fs::read_to_string(path).map_err(|e| format!("Error reading {:?}: {e}", path))?;
+
+    return Ok(Some(render_markdown(&markdown)));
+}
+
+pub fn is_self_hosted() -> bool {
+    return get_legal_pages().is_some();
+}
+
+// None on svoote.com, the pages of the operator otherwise. Pages that weren't supplied are None as well.
+pub fn get_legal_pages() -> Option<&'static LegalPages> {
+    return LEGAL_PAGES.get_or_init(|| None).as_ref();
+}
+
+// Shown below the QR code as the address participants type in
+pub fn get_join_host_name(headers: &HeaderMap) -> String {
+    if !is_self_hosted() {
+        return "svoote.com".to_string();
+    }
+
+    let base_url = get_public_base_url(headers);
+    return match base_url.split_once("://") {
+        Some((_scheme, host_name)) => host_name.to_string(),
+        None => base_url,
+    };
+}
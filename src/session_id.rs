@@ -6,8 +6,7 @@ use crate::app_error::AppError;
 pub fn get_or_create_session_id(cookies: CookieJar) -> (Uuid, CookieJar) {
     if let Some(session_id) = cookies
         .get("session_id")
-        .map(|cookie| cookie.value().parse::<Uuid>().ok())
-        .flatten()
+        .and_then(|cookie| cookie.value().parse::<Uuid>().ok())
     {
         return (session_id, cookies);
     } else {
@@ -0,0 +1,449 @@
+use std::{fmt::Display, net::SocketAddr, path::PathBuf, str::FromStr, sync::OnceLock};
+
+use serde::Deserialize;
+
+use crate::config::MC_ANSWERS_CAPACITY;
+
+// Limits and tunables that can differ per deployment. They are read once at startup from the
+// TOML file in CONFIG_FILE (svoote.toml by default, if it exists), then every setting can be
+// overridden by the environment variable of the same name in upper case.
+// Everything that is fixed by the code or the data format stays in config.rs.
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+const DEFAULT_CONFIG_FILE: &str = "svoote.toml";
+const MAX_CHANNEL_CAPACITY: usize = 65_536;
+const MAX_REPORT_RETENTION_HOURS: u64 = 365 * 24;
+
+#[derive(Deserialize, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub bind_address: SocketAddr,
+    pub live_poll_participant_limit: usize,
+    pub poll_max_slides: usize,
+    pub poll_max_mc_answers: usize,
+    pub free_text_max_char_length: usize,
+    pub poll_exit_timeout_secs: u64,
+    pub stats_update_throttle_ms: u64,
+    // How long open requests may take after SIGTERM before the server exits anyway
+    pub shutdown_grace_period_secs: u64,
+    // Updates a socket can fall behind on before it is resynchronized with the full state
+    pub notification_channel_capacity: usize,
+    // Commands that can wait for the task of a poll before handlers have to wait for a free slot
+    pub command_queue_capacity: usize,
+    // Tailwind classes for html elements, they have to be part of bundle.css
+    pub color_palette: Vec<String>,
+    // The same colors as hex values for charts
+    pub color_palette_rgb: Vec<String>,
+    pub live_poll_storage: LivePollStorageKind,
+    // Snapshots of running polls and reports of ended ones, used with the file storage
+    pub poll_data_dir: PathBuf,
+    // How long the report of an ended poll stays available, 0 disables reports
+    pub report_retention_hours: u64,
+    // Leaves out everything specific to svoote.com, always on with the self-hosted feature
+    pub self_hosted: bool,
+    // Markdown files with the legal pages of the operator, only used when self-hosted
+    pub imprint_file: Option<PathBuf>,
+    pub privacy_policy_file: Option<PathBuf>,
+    pub terms_of_service_file: Option<PathBuf>,
+    // Base of the join links and QR codes behind a reverse proxy, e.g. https://polls.example.com
+    pub public_base_url: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LivePollStorageKind {
+    File,
+    Memory,
+}
+
+impl FromStr for LivePollStorageKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "file" => Ok(LivePollStorageKind::File),
+            "memory" => Ok(LivePollStorageKind::Memory),
+            _ => Err("has to be file or memory".to_string()),
+        };
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        return Self {
+            bind_address: SocketAddr::from(([127, 0, 0, 1], 8080)),
+            live_poll_participant_limit: 100,
+            poll_max_slides: 32,
+            poll_max_mc_answers: 6,
+            free_text_max_char_length: 32,
+            poll_exit_timeout_secs: 2 * 60 * 60, // 2 hours
+            stats_update_throttle_ms: 2000,
+            shutdown_grace_period_secs: 10,
+            notification_channel_capacity: 16,
+            command_queue_capacity: 256,
+            color_palette: [
+                "bg-rose-500",
+                "bg-cyan-600",
+                "bg-lime-600",
+                "bg-fuchsia-600",
+                "bg-slate-600",
+                "bg-teal-600",
+            ]
+            .map(String::from)
+            .to_vec(),
+            color_palette_rgb: [
+                "#f43f5e", "#0891b2", "#65a30d", "#c026d3", "#475569", "#0d9488",
+            ]
+            .map(String::from)
+            .to_vec(),
+            live_poll_storage: LivePollStorageKind::File,
+            poll_data_dir: PathBuf::from("poll_data"),
+            report_retention_hours: 7 * 24, // 7 days
+            self_hosted: false,
+            imprint_file: None,
+            privacy_policy_file: None,
+            terms_of_service_file: None,
+            public_base_url: None,
+        };
+    }
+}
+
+impl Settings {
+    fn load() -> Result<Self, String> {
+        let (path, is_explicit) = match std::env::var("CONFIG_FILE") {
+            Ok(path) => (path, true),
+            Err(_) => (DEFAULT_CONFIG_FILE.to_string(), false),
+        };
+
+        let mut settings = match std::fs::read_to_string(&path) {
+            Ok(content) => toml::from_str::<Settings>(&content)
+                .map_err(|e| format!("Error parsing {path}: {e}"))?,
+            Err(e) if is_explicit || e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(format!("Error reading {path}: {e}"));
+            }
+            Err(_) => Settings::default(),
+        };
+
+        settings.apply_env_overrides()?;
+        settings.validate()?;
+
+        if let Some(public_base_url) = &mut settings.public_base_url {
+            *public_base_url = public_base_url.trim_end_matches('/').to_string();
+        }
+
+        return Ok(settings);
+    }
+
+    fn apply_env_overrides(&mut self) -> Result<(), String> {
+        override_from_env(&mut self.bind_address, "BIND_ADDRESS")?;
+        override_from_env(
+            &mut self.live_poll_participant_limit,
+            "LIVE_POLL_PARTICIPANT_LIMIT",
+        )?;
+        override_from_env(&mut self.poll_max_slides, "POLL_MAX_SLIDES")?;
+        override_from_env(&mut self.poll_max_mc_answers, "POLL_MAX_MC_ANSWERS")?;
+        override_from_env(
+            &mut self.free_text_max_char_length,
+            "FREE_TEXT_MAX_CHAR_LENGTH",
+        )?;
+        override_from_env(&mut self.poll_exit_timeout_secs, "POLL_EXIT_TIMEOUT_SECS")?;
+        override_from_env(
+            &mut self.stats_update_throttle_ms,
+            "STATS_UPDATE_THROTTLE_MS",
+        )?;
+        override_from_env(
+            &mut self.shutdown_grace_period_secs,
+            "SHUTDOWN_GRACE_PERIOD_SECS",
+        )?;
+        override_from_env(
+            &mut self.notification_channel_capacity,
+            "NOTIFICATION_CHANNEL_CAPACITY",
+        )?;
+        override_from_env(&mut self.command_queue_capacity, "COMMAND_QUEUE_CAPACITY")?;
+        override_list_from_env(&mut self.color_palette, "COLOR_PALETTE");
+        override_list_from_env(&mut self.color_palette_rgb, "COLOR_PALETTE_RGB");
+        override_from_env(&mut self.live_poll_storage, "LIVE_POLL_STORAGE")?;
+        override_from_env(&mut self.poll_data_dir, "POLL_DATA_DIR")?;
+        override_from_env(&mut self.report_retention_hours, "REPORT_RETENTION_HOURS")?;
+        override_from_env(&mut self.self_hosted, "SELF_HOSTED")?;
+        override_optional_from_env(&mut self.imprint_file, "IMPRINT_FILE")?;
+        override_optional_from_env(&mut self.privacy_policy_file, "PRIVACY_POLICY_FILE")?;
+        override_optional_from_env(&mut self.terms_of_service_file, "TERMS_OF_SERVICE_FILE")?;
+        override_optional_from_env(&mut self.public_base_url, "PUBLIC_BASE_URL")?;
+
+        return Ok(());
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.live_poll_participant_limit == 0 {
+            return Err("live_poll_participant_limit has to be at least 1".to_string());
+        }
+
+        if self.poll_max_slides == 0 {
+            return Err("poll_max_slides has to be at least 1".to_string());
+        }
+
+        // Answers are stored in fixed size arrays, so the capacity can't be raised at runtime
+        if !(2..=MC_ANSWERS_CAPACITY).contains(&self.poll_max_mc_answers) {
+            return Err(format!(
+                "poll_max_mc_answers has to be between 2 and {MC_ANSWERS_CAPACITY}"
+            ));
+        }
+
+        if self.free_text_max_char_length == 0 {
+            return Err("free_text_max_char_length has to be at least 1".to_string());
+        }
+
+        if self.poll_exit_timeout_secs == 0 {
+            return Err("poll_exit_timeout_secs has to be at least 1".to_string());
+        }
+
+        if self.stats_update_throttle_ms > 60_000 {
+            return Err("stats_update_throttle_ms can be at most 60000".to_string());
+        }
+
+        // Every socket of a poll holds the messages of its channels, so they are kept bounded
+        if !(1..=MAX_CHANNEL_CAPACITY).contains(&self.notification_channel_capacity) {
+            return Err(format!(
+                "notification_channel_capacity has to be between 1 and {MAX_CHANNEL_CAPACITY}"
+            ));
+        }
+
+        if !(1..=MAX_CHANNEL_CAPACITY).contains(&self.command_queue_capacity) {
+            return Err(format!(
+                "command_queue_capacity has to be between 1 and {MAX_CHANNEL_CAPACITY}"
+            ));
+        }
+
+        if self.color_palette.is_empty() {
+            return Err("color_palette can't be empty".to_string());
+        }
+
+        if self.color_palette.len() != self.color_palette_rgb.len() {
+            return Err(
+                "color_palette and color_palette_rgb need to have the same number of colors"
+                    .to_string(),
+            );
+        }
+
+        // Both palettes end up inside html attributes and javascript strings
+        if let Some(class) = self
+            .color_palette
+            .iter()
+            .find(|class| class.is_empty() || !class.chars().all(is_class_char))
+        {
+            return Err(format!("'{class}' in color_palette is not a valid class"));
+        }
+
+        if let Some(color) = self.color_palette_rgb.iter().find(|color| {
+            color.len() != 7
+                || !color.starts_with('#')
+                || !color[1..].chars().all(|c| c.is_ascii_hexdigit())
+        }) {
+            return Err(format!(
+                "'{color}' in color_palette_rgb is not a color like #0891b2"
+            ));
+        }
+
+        if self.poll_data_dir.as_os_str().is_empty() {
+            return Err("poll_data_dir can't be empty".to_string());
+        }
+
+        if self.report_retention_hours > MAX_REPORT_RETENTION_HOURS {
+            return Err(format!(
+                "report_retention_hours can be at most {MAX_REPORT_RETENTION_HOURS}"
+            ));
+        }
+
+        // The legal pages are read once at startup, a missing file stops the server right away
+        for (name, path) in [
+            ("imprint_file", &self.imprint_file),
+            ("privacy_policy_file", &self.privacy_policy_file),
+            ("terms_of_service_file", &self.terms_of_service_file),
+        ] {
+            if let Some(path) = path {
+                if self.is_self_hosted() && !path.is_file() {
+                    return Err(format!("{name} {:?} is not a readable file", path));
+                }
+            }
+        }
+
+        if let Some(public_base_url) = &self.public_base_url {
+            let host = public_base_url
+                .strip_prefix("https://")
+                .or_else(|| public_base_url.strip_prefix("http://"));
+            if host.is_none_or(|host| host.trim_end_matches('/').is_empty()) {
+                return Err(format!(
+                    "public_base_url '{public_base_url}' has to start with http:// or https://"
+                ));
+            }
+        }
+
+        return Ok(());
+    }
+
+    pub fn poll_exit_timeout(&self) -> tokio::time::Duration {
+        return tokio::time::Duration::from_secs(self.poll_exit_timeout_secs);
+    }
+
+    pub fn stats_update_throttle(&self) -> tokio::time::Duration {
+        return tokio::time::Duration::from_millis(self.stats_update_throttle_ms);
+    }
+
+    pub fn shutdown_grace_period(&self) -> tokio::time::Duration {
+        return tokio::time::Duration::from_secs(self.shutdown_grace_period_secs);
+    }
+
+    pub fn report_retention(&self) -> tokio::time::Duration {
+        return tokio::time::Duration::from_secs(self.report_retention_hours * 60 * 60);
+    }
+
+    pub fn is_self_hosted(&self) -> bool {
+        return cfg!(feature = "self-hosted") || self.self_hosted;
+    }
+
+    pub fn color_class(&self, index: usize) -> &str {
+        return &self.color_palette[index % self.color_palette.len()];
+    }
+
+    pub fn color_rgb(&self, index: usize) -> &str {
+        return &self.color_palette_rgb[index % self.color_palette_rgb.len()];
+    }
+}
+
+fn is_class_char(c: char) -> bool {
+    return c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '[' | ']' | '#' | '.' | '/');
+}
+
+fn override_from_env<T: FromStr>(value: &mut T, env_var: &str) -> Result<(), String>
+where
+    T::Err: Display,
+{
+    if let Ok(env_value) = std::env::var(env_var) {
+        *value = env_value
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid value for {env_var} ('{env_value}'): {e}"))?;
+    }
+
+    return Ok(());
+}
+
+// An empty value unsets the setting
+fn override_optional_from_env<T: FromStr>(
+    value: &mut Option<T>,
+    env_var: &str,
+) -> Result<(), String>
+where
+    T::Err: Display,
+{
+    if let Ok(env_value) = std::env::var(env_var) {
+        let env_value = env_value.trim();
+        *value = if env_value.is_empty() {
+            None
+        } else {
+            Some(
+                env_value
+                    .parse()
+                    .map_err(|e| format!("Invalid value for {env_var} ('{env_value}'): {e}"))?,
+            )
+        };
+    }
+
+    return Ok(());
+}
+
+// Lists are given as comma separated values, e.g. COLOR_PALETTE=bg-rose-500,bg-cyan-600
+fn override_list_from_env(value: &mut Vec<String>, env_var: &str) {
+    if let Ok(env_value) = std::env::var(env_var) {
+        *value = env_value
+            .split(',')
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect();
+    }
+}
+
+pub fn init() -> Result<(), String> {
+    let settings = Settings::load()?;
+    debug!("Settings: {settings:?}");
+
+    if SETTINGS.set(settings).is_err() {
+        panic!("settings::init() called more than once");
+    }
+
+    return Ok(());
+}
+
+pub fn settings() -> &'static Settings {
+    // Unit tests don't call init() and run with the defaults
+    #[cfg(test)]
+    return SETTINGS.get_or_init(Settings::default);
+
+    #[cfg(not(test))]
+    return SETTINGS
+        .get()
+        .expect("settings::init() needs to be called before accessing the settings");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_settings_are_valid() {
+        assert!(Settings::default().validate().is_ok());
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        let invalid_settings: [fn(&mut Settings); 8] = [
+            |settings| settings.live_poll_participant_limit = 0,
+            |settings| settings.poll_max_mc_answers = MC_ANSWERS_CAPACITY + 1,
+            |settings| settings.notification_channel_capacity = 0,
+            |settings| {
+                settings.color_palette_rgb.pop();
+            },
+            |settings| settings.color_palette[0] = "bg-rose-500\"".to_string(),
+            |settings| settings.color_palette_rgb[0] = "red".to_string(),
+            |settings| settings.report_retention_hours = u64::MAX,
+            |settings| settings.public_base_url = Some("polls.example.com".to_string()),
+        ];
+
+        for (index, make_invalid) in invalid_settings.iter().enumerate() {
+            let mut settings = Settings::default();
+            make_invalid(&mut settings);
+            assert!(settings.validate().is_err(), "case {index} was accepted");
+        }
+    }
+
+    #[test]
+    fn missing_legal_page_is_rejected_when_self_hosted() {
+        let settings = Settings {
+            self_hosted: true,
+            imprint_file: Some(PathBuf::from("/does/not/exist.md")),
+            ..Settings::default()
+        };
+
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn settings_are_read_from_toml() {
+        let settings: Settings = toml::from_str(
+            r#"
+            live_poll_storage = "memory"
+            report_retention_hours = 0
+            public_base_url = "https://polls.example.com"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(settings.live_poll_storage, LivePollStorageKind::Memory);
+        assert_eq!(settings.report_retention_hours, 0);
+        assert!(settings.validate().is_ok());
+        assert!(toml::from_str::<Settings>("unknown_setting = 1").is_err());
+        assert!(toml::from_str::<Settings>(r#"live_poll_storage = "disk""#).is_err());
+    }
+}
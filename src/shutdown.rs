@@ -0,0 +1,84 @@
+use std::sync::LazyLock;
+
+use serde_json::json;
+use smartstring::SmartString;
+use tokio::sync::watch;
+
+use crate::{
+    config::SHUTDOWN_RECONNECT_DELAY, live_poll_store::live_poll_store, settings::settings,
+    wsmessage::WSMessage,
+};
+
+// On SIGTERM or Ctrl+C the server stops accepting new polls and tells every connected host and
+// participant to reconnect after the restart. Open requests get a bounded time to finish, then
+// the state of all live polls is written out once more, so they can be restored on startup.
+
+static SHUTDOWN: LazyLock<watch::Sender<bool>> = LazyLock::new(|| watch::channel(false).0);
+
+pub fn is_shutting_down() -> bool {
+    return *SHUTDOWN.borrow();
+}
+
+// Resolves as soon as the shutdown started, the sockets select on this to say goodbye
+pub async fn requested() {
+    let mut receiver = SHUTDOWN.subscribe();
+    let _ = receiver
+        .wait_for(|is_shutting_down| *is_shutting_down)
+        .await;
+}
+
+pub async fn listen_for_signals() {
+    let ctrl_c = tokio::signal::ctrl_c();
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                error!("Error installing SIGTERM handler: {e}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+
+    info!(
+        "Shutting down, waiting up to {}s for open requests",
+        settings().shutdown_grace_period_secs
+    );
+    SHUTDOWN.send_replace(true);
+}
+
+// The grace period starts with the shutdown and bounds how long open requests may take
+pub async fn grace_period_elapsed() {
+    requested().await;
+    tokio::time::sleep(settings().shutdown_grace_period()).await;
+}
+
+pub fn create_server_restarting_ws_message() -> WSMessage {
+    return WSMessage {
+        cmd: SmartString::from("serverRestarting"),
+        data: json!({ "reconnectAfterMs": SHUTDOWN_RECONNECT_DELAY.as_millis() as u64 }),
+    };
+}
+
+pub async fn flush_live_polls() {
+    let live_polls = live_poll_store().get_all();
+
+    for (poll_id, live_poll) in &live_polls {
+        if let Err(e) = live_poll.persist().await {
+            error!("Error saving live poll {poll_id}: {e}");
+        }
+    }
+
+    info!("Saved {} live polls", live_polls.len());
+}
@@ -1,44 +1,195 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use arrayvec::ArrayVec;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use smartstring::{Compact, SmartString};
 
-use crate::{app_error::AppError, config::POLL_MAX_MC_ANSWERS};
+use crate::{
+    app_error::AppError,
+    config::{
+        MC_ANSWERS_CAPACITY, QA_MAX_QUESTIONS_PER_PLAYER, QA_MAX_QUESTION_LENGTH, QUIZ_MAX_POINTS,
+        QUIZ_MIN_POINTS, QUIZ_SCORING_TIME_LIMIT,
+    },
+    player_map::PlayerMap,
+    settings::settings,
+};
 
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Slide {
     pub question: String,
     pub slide_type: SlideType,
-    pub player_scores: Vec<usize>,
-    pub player_emojis: Vec<Option<SmartString<Compact>>>,
+    #[serde(default = "default_voting_open")]
+    pub is_voting_open: bool,
+    // Results of a hidden slide are still aggregated, but only sent out after the host revealed them
+    #[serde(default)]
+    pub hide_results: bool,
+    #[serde(default)]
+    pub results_revealed: bool,
+    #[serde(default)]
+    pub share_results_with_participants: bool,
+    // Players can revise their answer as long as voting for the slide is open
+    #[serde(default)]
+    pub allow_answer_changes: bool,
+    pub player_scores: PlayerMap<usize>,
+    pub player_emojis: PlayerMap<SmartString<Compact>>,
     pub heart_emojis: usize,
     pub thumbs_up_emojis: usize,
     pub thumbs_down_emojis: usize,
     pub smiley_face_emojis: usize,
     pub sad_face_emojis: usize,
+    // Results of earlier rounds, archived when the host reset the slide
+    #[serde(default)]
+    pub rounds: Vec<SlideRound>,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SlideRound {
+    pub round_number: usize,
+    pub player_scores: PlayerMap<usize>,
+    pub num_responses: usize,
+    pub results: ResultSnapshot,
+    pub heart_emojis: usize,
+    pub thumbs_up_emojis: usize,
+    pub thumbs_down_emojis: usize,
+    pub smiley_face_emojis: usize,
+    pub sad_face_emojis: usize,
+}
+
+// Aggregated results of a slide, separate from the live counters. Multiple choice and word cloud
+// results are kept in a form that can be compared between rounds, all other slide types keep the
+// results as they were shown in the host view.
+#[derive(Serialize, Deserialize, Clone)]
+pub enum ResultSnapshot {
+    MultipleChoice {
+        answers: Vec<String>,
+        answer_counts: Vec<usize>,
+    },
+    FreeText {
+        // (lowercase text, preferred spelling, count)
+        terms: Vec<(SmartString<Compact>, SmartString<Compact>, usize)>,
+    },
+    Other(Value),
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub enum SlideType {
     Undefined,
     MultipleChoice(MultipleChoiceLiveAnswers),
     FreeText(FreeTextLiveAnswers),
+    Rating(RatingLiveAnswers),
+    NumericEstimate(NumericLiveAnswers),
+    Ranking(RankingLiveAnswers),
+    QandA(QandALiveAnswers),
+    Info(InfoContent),
 }
 
+#[derive(Serialize, Deserialize, Clone)]
 pub struct MultipleChoiceLiveAnswers {
     pub answers: Vec<(String, bool)>,
     pub answer_counts: Vec<usize>,
-    pub player_answers: Vec<Option<ArrayVec<u8, POLL_MAX_MC_ANSWERS>>>,
+    pub player_answers: PlayerMap<ArrayVec<u8, MC_ANSWERS_CAPACITY>>,
     pub allow_multiple_answers: bool,
+    // How the host page shows the results, reports use the same chart
+    #[serde(default)]
+    pub chart_type: ChartType,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum ChartType {
+    #[default]
+    #[serde(rename = "bar")]
+    Bar,
+    #[serde(rename = "pie")]
+    Pie,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct FreeTextLiveAnswers {
     //pub correct_answers: Vec<SmartString<Compact>>,
-    pub player_answers: Vec<Option<SmartString<Compact>>>,
+    pub player_answers: PlayerMap<SmartString<Compact>>,
     pub word_cloud_terms: Vec<WordCloudTerm>,
     pub max_term_count: usize,
+    // Position of every term in word_cloud_terms by its lowercase text, rebuilt after loading
+    #[serde(skip)]
+    term_indices: HashMap<SmartString<Compact>, usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RatingLiveAnswers {
+    pub scale_max: u8,
+    pub low_label: String,
+    pub high_label: String,
+    // rating_counts[0] is the number of players that rated 1
+    pub rating_counts: Vec<usize>,
+    pub player_answers: PlayerMap<u8>,
+}
+
+pub struct RatingStats {
+    pub num_ratings: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub standard_deviation: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NumericLiveAnswers {
+    pub min: f64,
+    pub max: f64,
+    // The range between min and max is split into bucket_counts.len() equally sized buckets
+    pub bucket_counts: Vec<usize>,
+    pub player_answers: PlayerMap<f64>,
+    // All submitted answers in ascending order, for min, max and median
+    pub sorted_answers: Vec<f64>,
+    pub answer_sum: f64,
+}
+
+pub struct NumericStats {
+    pub num_answers: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RankingLiveAnswers {
+    pub options: Vec<String>,
+    // With n options, the option a player puts first gets n - 1 points, the last one 0 points
+    pub borda_scores: Vec<usize>,
+    // Sum of the 1-based positions each option was put at, for the average position
+    pub position_sums: Vec<usize>,
+    // The full ordering of every player as option indices, best first
+    pub player_answers: PlayerMap<ArrayVec<u8, MC_ANSWERS_CAPACITY>>,
+    pub num_answers: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct QandALiveAnswers {
+    // Questions are only ever appended, so their index doubles as a stable id
+    pub questions: Vec<AudienceQuestion>,
+    // The question indices every player upvoted
+    pub player_upvotes: PlayerMap<Vec<usize>>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AudienceQuestion {
+    pub player_index: usize,
+    pub text: String,
+    pub upvotes: usize,
+    pub is_answered: bool,
+    pub is_pinned: bool,
+    pub is_hidden: bool,
+}
+
+// Content-only slide, the html is rendered and sanitized once when the poll starts
+#[derive(Serialize, Deserialize, Clone)]
+pub struct InfoContent {
+    pub markdown: String,
+    pub html: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct WordCloudTerm {
     pub lowercase_text: SmartString<Compact>,
     pub count: usize,
@@ -47,24 +198,315 @@ pub struct WordCloudTerm {
     pub highest_spelling_count: usize,
 }
 
+// Slides persisted before voting could be closed were always open
+fn default_voting_open() -> bool {
+    return true;
+}
+
 impl Slide {
-    pub fn add_player(&mut self) {
-        self.player_scores.push(0usize);
-        self.player_emojis.push(None);
+    pub fn new(question: String, slide_type: SlideType) -> Self {
+        return Slide {
+            question,
+            slide_type,
+            is_voting_open: true,
+            hide_results: false,
+            results_revealed: false,
+            share_results_with_participants: false,
+            allow_answer_changes: false,
+            player_scores: PlayerMap::new(),
+            player_emojis: PlayerMap::new(),
+            heart_emojis: 0,
+            thumbs_up_emojis: 0,
+            thumbs_down_emojis: 0,
+            smiley_face_emojis: 0,
+            sad_face_emojis: 0,
+            rounds: Vec::new(),
+        };
+    }
+
+    pub fn submit_score(&mut self, player_index: usize, score: usize) {
+        self.player_scores.insert(player_index, score);
+    }
+
+    // The number of the round that is currently collecting answers, starting at 1
+    pub fn get_round_number(&self) -> usize {
+        return self.rounds.len() + 1;
+    }
 
-        match &mut self.slide_type {
-            SlideType::Undefined => {}
-            SlideType::MultipleChoice(mc_answers) => {
-                mc_answers.player_answers.push(None);
+    // The points of every round, points earned before the host reset the slide still count
+    pub fn get_player_scores(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        return std::iter::once(&self.player_scores)
+            .chain(self.rounds.iter().map(|round| &round.player_scores))
+            .flat_map(|player_scores| player_scores.iter())
+            .map(|(player_index, score)| (*player_index, *score));
+    }
+
+    // Archives the current results as a round and starts over with the same question
+    pub fn start_new_round(&mut self) {
+        let new_slide_type = match &self.slide_type {
+            SlideType::Undefined => SlideType::Undefined,
+            SlideType::MultipleChoice(answers) => {
+                SlideType::MultipleChoice(MultipleChoiceLiveAnswers {
+                    answers: answers.answers.clone(),
+                    answer_counts: vec![0usize; answers.answers.len()],
+                    player_answers: PlayerMap::new(),
+                    allow_multiple_answers: answers.allow_multiple_answers,
+                    chart_type: answers.chart_type,
+                })
             }
-            SlideType::FreeText(ft_answer) => {
-                ft_answer.player_answers.push(None);
+            SlideType::FreeText(_) => SlideType::FreeText(FreeTextLiveAnswers::new()),
+            SlideType::Rating(answers) => SlideType::Rating(RatingLiveAnswers::new(
+                answers.scale_max,
+                answers.low_label.clone(),
+                answers.high_label.clone(),
+            )),
+            SlideType::NumericEstimate(answers) => SlideType::NumericEstimate(
+                NumericLiveAnswers::new(answers.min, answers.max, answers.bucket_counts.len()),
+            ),
+            SlideType::Ranking(answers) => {
+                SlideType::Ranking(RankingLiveAnswers::new(answers.options.clone()))
             }
-        }
+            SlideType::QandA(_) => SlideType::QandA(QandALiveAnswers::new()),
+            SlideType::Info(content) => SlideType::Info(InfoContent {
+                markdown: content.markdown.clone(),
+                html: content.html.clone(),
+            }),
+        };
+
+        let round = SlideRound {
+            round_number: self.get_round_number(),
+            player_scores: std::mem::take(&mut self.player_scores),
+            num_responses: self.get_num_responses(),
+            results: self.create_result_snapshot(),
+            heart_emojis: std::mem::take(&mut self.heart_emojis),
+            thumbs_up_emojis: std::mem::take(&mut self.thumbs_up_emojis),
+            thumbs_down_emojis: std::mem::take(&mut self.thumbs_down_emojis),
+            smiley_face_emojis: std::mem::take(&mut self.smiley_face_emojis),
+            sad_face_emojis: std::mem::take(&mut self.sad_face_emojis),
+        };
+        self.rounds.push(round);
+        self.slide_type = new_slide_type;
+
+        self.player_emojis.clear();
+
+        self.results_revealed = false;
     }
 
-    pub fn submit_score(&mut self, player_index: usize, score: usize) {
-        self.player_scores[player_index] = score;
+    // Info and Q&A slides have no aggregated results that could bias the audience
+    pub fn has_results(&self) -> bool {
+        return matches!(
+            self.slide_type,
+            SlideType::MultipleChoice(_)
+                | SlideType::FreeText(_)
+                | SlideType::Rating(_)
+                | SlideType::NumericEstimate(_)
+                | SlideType::Ranking(_)
+        );
+    }
+
+    pub fn are_results_visible(&self) -> bool {
+        return !self.has_results() || !self.hide_results || self.results_revealed;
+    }
+
+    pub fn get_num_responses(&self) -> usize {
+        return match &self.slide_type {
+            SlideType::MultipleChoice(answers) => answers.player_answers.len(),
+            SlideType::FreeText(answers) => answers.player_answers.len(),
+            SlideType::Rating(answers) => answers.rating_counts.iter().sum(),
+            SlideType::NumericEstimate(answers) => answers.sorted_answers.len(),
+            SlideType::Ranking(answers) => answers.num_answers,
+            SlideType::QandA(answers) => answers.questions.len(),
+            SlideType::Undefined | SlideType::Info(_) => 0,
+        };
+    }
+
+    pub fn create_result_snapshot(&self) -> ResultSnapshot {
+        return match &self.slide_type {
+            SlideType::MultipleChoice(answers) => ResultSnapshot::MultipleChoice {
+                answers: answers
+                    .answers
+                    .iter()
+                    .map(|(text, _)| text.clone())
+                    .collect(),
+                answer_counts: answers.answer_counts.clone(),
+            },
+            SlideType::FreeText(answers) => ResultSnapshot::FreeText {
+                terms: answers
+                    .word_cloud_terms
+                    .iter()
+                    .map(|term| {
+                        (
+                            term.lowercase_text.clone(),
+                            term.preferred_spelling.clone(),
+                            term.count,
+                        )
+                    })
+                    .collect(),
+            },
+            SlideType::QandA(answers) => {
+                ResultSnapshot::Other(json!({ "questions": answers.create_questions_json(None) }))
+            }
+            _ => ResultSnapshot::Other(self.create_results_json()),
+        };
+    }
+
+    // Compares an archived round with a later round, or with the live results if `later_round`
+    // is None. Only multiple choice and word cloud results can be compared.
+    pub fn compare_rounds(
+        &self,
+        earlier_round: usize,
+        later_round: Option<usize>,
+    ) -> Option<Value> {
+        let get_round = |round_number: usize| {
+            self.rounds
+                .iter()
+                .find(|round| round.round_number == round_number)
+        };
+
+        let earlier = get_round(earlier_round)?;
+        let live_snapshot;
+        let (later_round, later_results, later_num_responses) = match later_round {
+            Some(round_number) => {
+                let later = get_round(round_number)?;
+                (later.round_number, &later.results, later.num_responses)
+            }
+            None => {
+                live_snapshot = self.create_result_snapshot();
+                (
+                    self.get_round_number(),
+                    &live_snapshot,
+                    self.get_num_responses(),
+                )
+            }
+        };
+
+        let comparison = match (&earlier.results, later_results) {
+            (
+                ResultSnapshot::MultipleChoice {
+                    answers,
+                    answer_counts: earlier_counts,
+                },
+                ResultSnapshot::MultipleChoice {
+                    answer_counts: later_counts,
+                    ..
+                },
+            ) => {
+                json!({
+                    "answers": answers
+                        .iter()
+                        .zip(earlier_counts.iter().zip(later_counts.iter()))
+                        .map(|(text, (before, after))| json!({
+                            "text": text,
+                            "before": before,
+                            "after": after,
+                            "delta": *after as i64 - *before as i64,
+                        }))
+                        .collect::<Vec<_>>(),
+                })
+            }
+            (
+                ResultSnapshot::FreeText {
+                    terms: earlier_terms,
+                },
+                ResultSnapshot::FreeText { terms: later_terms },
+            ) => {
+                let earlier_counts = earlier_terms
+                    .iter()
+                    .map(|(lowercase_text, _, count)| (lowercase_text, *count))
+                    .collect::<HashMap<_, _>>();
+                let later_texts = later_terms
+                    .iter()
+                    .map(|(lowercase_text, _, _)| lowercase_text)
+                    .collect::<HashSet<_>>();
+
+                // Terms of the later round first, then the ones that only occurred before
+                let mut terms = later_terms
+                    .iter()
+                    .map(|(lowercase_text, spelling, count)| {
+                        let before = earlier_counts.get(lowercase_text).copied().unwrap_or(0);
+                        (spelling, before, *count)
+                    })
+                    .chain(
+                        earlier_terms
+                            .iter()
+                            .filter(|(lowercase_text, _, _)| !later_texts.contains(lowercase_text))
+                            .map(|(_, spelling, count)| (spelling, *count, 0)),
+                    )
+                    .collect::<Vec<_>>();
+                terms.sort_by(|a, b| (b.2.max(b.1)).cmp(&a.2.max(a.1)).then(a.0.cmp(b.0)));
+
+                json!({
+                    "terms": terms
+                        .iter()
+                        .map(|(text, before, after)| json!({
+                            "text": text,
+                            "before": before,
+                            "after": after,
+                            "delta": *after as i64 - *before as i64,
+                        }))
+                        .collect::<Vec<_>>(),
+                    "appeared": terms.iter().filter(|(_, before, _)| *before == 0).map(|(text, _, _)| text).collect::<Vec<_>>(),
+                    "disappeared": terms.iter().filter(|(_, _, after)| *after == 0).map(|(text, _, _)| text).collect::<Vec<_>>(),
+                })
+            }
+            _ => return None,
+        };
+
+        return Some(json!({
+            "earlierRound": earlier.round_number,
+            "laterRound": later_round,
+            "earlierNumResponses": earlier.num_responses,
+            "laterNumResponses": later_num_responses,
+            "comparison": comparison,
+        }));
+    }
+
+    // The aggregated results as shown in the host view, without anything that identifies players
+    pub fn create_results_json(&self) -> Value {
+        return match &self.slide_type {
+            SlideType::MultipleChoice(answers) => {
+                json!({ "counts": answers.answer_counts })
+            }
+            SlideType::FreeText(answers) => {
+                json!({
+                    "terms": answers.word_cloud_terms
+                        .iter()
+                        .map(|term| (term.preferred_spelling.clone(), term.count))
+                        .collect::<Vec<_>>(),
+                    "maxCount": answers.max_term_count,
+                })
+            }
+            SlideType::Rating(answers) => {
+                let stats = answers.get_stats();
+                json!({
+                    "counts": answers.rating_counts,
+                    "numRatings": stats.as_ref().map(|stats| stats.num_ratings).unwrap_or(0),
+                    "mean": stats.as_ref().map(|stats| stats.mean),
+                    "median": stats.as_ref().map(|stats| stats.median),
+                    "standardDeviation": stats.as_ref().map(|stats| stats.standard_deviation),
+                })
+            }
+            SlideType::Ranking(answers) => {
+                json!({
+                    "bordaScores": answers.borda_scores,
+                    "averagePositions": answers.get_average_positions(),
+                    "ranking": answers.get_aggregated_ranking(),
+                    "numAnswers": answers.num_answers,
+                })
+            }
+            SlideType::NumericEstimate(answers) => {
+                let stats = answers.get_stats();
+                json!({
+                    "counts": answers.bucket_counts,
+                    "numAnswers": stats.as_ref().map(|stats| stats.num_answers).unwrap_or(0),
+                    "min": stats.as_ref().map(|stats| stats.min),
+                    "max": stats.as_ref().map(|stats| stats.max),
+                    "mean": stats.as_ref().map(|stats| stats.mean),
+                    "median": stats.as_ref().map(|stats| stats.median),
+                })
+            }
+            SlideType::Undefined | SlideType::Info(_) | SlideType::QandA(_) => Value::Null,
+        };
     }
 }
 
@@ -73,10 +515,11 @@ impl MultipleChoiceLiveAnswers {
     pub fn submit_answer(
         &mut self,
         player_index: usize,
-        answer_indices: ArrayVec<u8, POLL_MAX_MC_ANSWERS>,
-        _start_time: tokio::time::Instant,
+        answer_indices: ArrayVec<u8, MC_ANSWERS_CAPACITY>,
+        start_time: tokio::time::Instant,
+        allow_changes: bool,
     ) -> Result<usize, AppError> {
-        if answer_indices.len() == 0 {
+        if answer_indices.is_empty() {
             return Err(AppError::BadRequest(
                 "Can't submit an empty answer_indices array".to_string(),
             ));
@@ -88,36 +531,1091 @@ impl MultipleChoiceLiveAnswers {
             ));
         }
 
+        // Every answer can be selected at most once
+        let mut seen = [false; MC_ANSWERS_CAPACITY];
         for answer_index in &answer_indices {
-            if *answer_index as usize >= self.answers.len() {
+            let answer_index = *answer_index as usize;
+            if answer_index >= self.answers.len() {
                 return Err(AppError::BadRequest(
                     "Answer index out of bounds".to_string(),
                 ));
             }
+
+            if std::mem::replace(&mut seen[answer_index], true) {
+                return Err(AppError::BadRequest(
+                    "Can't submit the same answer more than once".to_string(),
+                ));
+            }
         }
 
-        if self.player_answers[player_index].is_some() {
-            return Err(AppError::BadRequest(
-                "Already submitted an answers".to_string(),
-            ));
+        if let Some(old_answer_indices) = self.player_answers.get(&player_index) {
+            if !allow_changes {
+                return Err(AppError::BadRequest(
+                    "Already submitted an answers".to_string(),
+                ));
+            }
+
+            for answer_index in old_answer_indices {
+                self.answer_counts[*answer_index as usize] -= 1;
+            }
         }
 
         for answer_index in &answer_indices {
             self.answer_counts[*answer_index as usize] += 1;
-            // if answer is correct
-            /*if self.answers[answer_index].1 {
-                let mut elapsed = tokio::time::Instant::now() - start_time;
-                if elapsed > tokio::time::Duration::from_secs(60) {
-                    elapsed = tokio::time::Duration::from_secs(60);
+        }
+
+        let score = if self.is_correct_answer(&answer_indices) {
+            // Correct answers get between QUIZ_MIN_POINTS and QUIZ_MAX_POINTS, the faster the more
+            let elapsed = (tokio::time::Instant::now() - start_time).min(QUIZ_SCORING_TIME_LIMIT);
+            let remaining_fraction =
+                1f32 - elapsed.as_secs_f32() / QUIZ_SCORING_TIME_LIMIT.as_secs_f32();
+            QUIZ_MIN_POINTS
+                + ((QUIZ_MAX_POINTS - QUIZ_MIN_POINTS) as f32 * remaining_fraction) as usize
+        } else {
+            0usize
+        };
+
+        self.player_answers.insert(player_index, answer_indices);
+
+        return Ok(score);
+    }
+
+    // An answer is correct if exactly the answers marked as correct were selected. Questions
+    // without any correct answer can't be answered correctly.
+    pub fn is_correct_answer(&self, answer_indices: &[u8]) -> bool {
+        let mut is_selected = vec![false; self.answers.len()];
+        for answer_index in answer_indices {
+            match is_selected.get_mut(*answer_index as usize) {
+                Some(is_selected) => *is_selected = true,
+                None => return false,
+            }
+        }
+
+        return self.answers.iter().any(|answer| answer.1)
+            && self
+                .answers
+                .iter()
+                .zip(is_selected)
+                .all(|(answer, is_selected)| answer.1 == is_selected);
+    }
+}
+
+impl FreeTextLiveAnswers {
+    pub fn new() -> Self {
+        return FreeTextLiveAnswers {
+            player_answers: PlayerMap::new(),
+            word_cloud_terms: Vec::new(),
+            max_term_count: 1usize,
+            term_indices: HashMap::new(),
+        };
+    }
+
+    pub fn submit_answer(
+        &mut self,
+        player_index: usize,
+        answer: SmartString<Compact>,
+        allow_changes: bool,
+    ) -> Result<(), AppError> {
+        if self.player_answers.contains_key(&player_index) && !allow_changes {
+            return Err(AppError::BadRequest(
+                "Already submitted an answer".to_string(),
+            ));
+        }
+
+        if let Some(old_answer) = self.player_answers.remove(&player_index) {
+            self.remove_from_word_cloud(&old_answer);
+        }
+
+        self.add_to_word_cloud(&answer);
+        self.player_answers.insert(player_index, answer);
+
+        return Ok(());
+    }
+
+    // Answers are grouped case-insensitively, every group remembers its most used spelling
+    fn get_word_cloud_key(answer: &str) -> (SmartString<Compact>, SmartString<Compact>) {
+        let trimmed_answer = SmartString::from(answer.trim());
+
+        let lowercase_answer = trimmed_answer
+            .to_lowercase()
+            .chars()
+            .take(settings().free_text_max_char_length)
+            .collect::<SmartString<Compact>>();
+
+        return (trimmed_answer, lowercase_answer);
+    }
+
+    fn get_term_index(&mut self, lowercase_answer: &str) -> Option<usize> {
+        // The index is not persisted, so it is missing after a poll was loaded from a snapshot
+        if self.term_indices.len() != self.word_cloud_terms.len() {
+            self.rebuild_term_indices();
+        }
+
+        return self.term_indices.get(lowercase_answer).copied();
+    }
+
+    fn rebuild_term_indices(&mut self) {
+        self.term_indices = self
+            .word_cloud_terms
+            .iter()
+            .enumerate()
+            .map(|(term_index, term)| (term.lowercase_text.clone(), term_index))
+            .collect();
+    }
+
+    fn add_to_word_cloud(&mut self, answer: &str) {
+        let (trimmed_answer, lowercase_answer) = Self::get_word_cloud_key(answer);
+
+        if let Some(term_index) = self.get_term_index(&lowercase_answer) {
+            let term = &mut self.word_cloud_terms[term_index];
+            term.count += 1;
+            if term.count > self.max_term_count {
+                self.max_term_count = term.count;
+            }
+
+            if let Some(spelling_count) = term.spellings.get_mut(&trimmed_answer) {
+                *spelling_count += 1;
+                if *spelling_count > term.highest_spelling_count {
+                    term.highest_spelling_count = *spelling_count;
+                    term.preferred_spelling = trimmed_answer;
+                }
+            } else {
+                term.spellings.insert(trimmed_answer, 1);
+            }
+        } else {
+            let mut spellings = HashMap::new();
+            spellings.insert(trimmed_answer.clone(), 1);
+
+            self.term_indices
+                .insert(lowercase_answer.clone(), self.word_cloud_terms.len());
+            self.word_cloud_terms.push(WordCloudTerm {
+                lowercase_text: lowercase_answer,
+                count: 1,
+                preferred_spelling: trimmed_answer,
+                spellings,
+                highest_spelling_count: 1,
+            });
+        }
+    }
+
+    fn remove_from_word_cloud(&mut self, answer: &str) {
+        let (trimmed_answer, lowercase_answer) = Self::get_word_cloud_key(answer);
+
+        let Some(term_index) = self.get_term_index(&lowercase_answer) else {
+            return;
+        };
+
+        let term = &mut self.word_cloud_terms[term_index];
+        let was_max_term = term.count == self.max_term_count;
+        term.count -= 1;
+
+        if term.count == 0 {
+            // Terms keep their order, so the word cloud doesn't reshuffle when an answer changes
+            self.word_cloud_terms.remove(term_index);
+            self.rebuild_term_indices();
+        } else {
+            if let Some(spelling_count) = term.spellings.get_mut(&trimmed_answer) {
+                *spelling_count -= 1;
+                if *spelling_count == 0 {
+                    term.spellings.remove(&trimmed_answer);
+                }
+            }
+
+            // The preferred spelling only changes if another spelling is used more often now
+            let highest_spelling_count = term.spellings.values().copied().max().unwrap_or(0);
+            if term.spellings.get(&term.preferred_spelling) != Some(&highest_spelling_count) {
+                if let Some(spelling) = term
+                    .spellings
+                    .iter()
+                    .filter(|(_, count)| **count == highest_spelling_count)
+                    .map(|(spelling, _)| spelling)
+                    .min()
+                {
+                    term.preferred_spelling = spelling.clone();
+                }
+            }
+            term.highest_spelling_count = highest_spelling_count;
+        }
+
+        // Only a term that had the highest count can lower the maximum
+        if was_max_term {
+            self.max_term_count = self
+                .word_cloud_terms
+                .iter()
+                .map(|term| term.count)
+                .max()
+                .unwrap_or(1);
+        }
+    }
+}
+
+impl RatingLiveAnswers {
+    pub fn new(scale_max: u8, low_label: String, high_label: String) -> Self {
+        return RatingLiveAnswers {
+            scale_max,
+            low_label,
+            high_label,
+            rating_counts: vec![0usize; scale_max as usize],
+            player_answers: PlayerMap::new(),
+        };
+    }
+
+    pub fn submit_answer(
+        &mut self,
+        player_index: usize,
+        rating: u8,
+        allow_changes: bool,
+    ) -> Result<(), AppError> {
+        if rating < 1 || rating > self.scale_max {
+            return Err(AppError::BadRequest(format!(
+                "Rating has to be between 1 and {}",
+                self.scale_max
+            )));
+        }
+
+        if let Some(old_rating) = self.player_answers.get(&player_index).copied() {
+            if !allow_changes {
+                return Err(AppError::BadRequest(
+                    "Already submitted a rating".to_string(),
+                ));
+            }
+
+            self.rating_counts[old_rating as usize - 1] -= 1;
+        }
+
+        self.rating_counts[rating as usize - 1] += 1;
+        self.player_answers.insert(player_index, rating);
+
+        return Ok(());
+    }
+
+    // Computed from the counts alone, so this stays cheap no matter how many players rated
+    pub fn get_stats(&self) -> Option<RatingStats> {
+        let num_ratings = self.rating_counts.iter().sum::<usize>();
+        if num_ratings == 0 {
+            return None;
+        }
+
+        let ratings = || {
+            self.rating_counts
+                .iter()
+                .enumerate()
+                .map(|(index, count)| ((index + 1) as f64, *count as f64))
+        };
+
+        let mean =
+            ratings().map(|(rating, count)| rating * count).sum::<f64>() / num_ratings as f64;
+        let variance = ratings()
+            .map(|(rating, count)| (rating - mean).powi(2) * count)
+            .sum::<f64>()
+            / num_ratings as f64;
+
+        // The rating at the given position if all ratings were sorted
+        let nth_rating = |position: usize| {
+            let mut seen = 0usize;
+            for (index, count) in self.rating_counts.iter().enumerate() {
+                seen += count;
+                if seen > position {
+                    return (index + 1) as f64;
+                }
+            }
+            return self.scale_max as f64;
+        };
+        let median = (nth_rating((num_ratings - 1) / 2) + nth_rating(num_ratings / 2)) / 2f64;
+
+        return Some(RatingStats {
+            num_ratings,
+            mean,
+            median,
+            standard_deviation: variance.sqrt(),
+        });
+    }
+}
+
+impl NumericLiveAnswers {
+    pub fn new(min: f64, max: f64, num_buckets: usize) -> Self {
+        return NumericLiveAnswers {
+            min,
+            max,
+            bucket_counts: vec![0usize; num_buckets],
+            player_answers: PlayerMap::new(),
+            sorted_answers: Vec::new(),
+            answer_sum: 0f64,
+        };
+    }
+
+    pub fn submit_answer(
+        &mut self,
+        player_index: usize,
+        answer: f64,
+        allow_changes: bool,
+    ) -> Result<(), AppError> {
+        if !answer.is_finite() || answer < self.min || answer > self.max {
+            return Err(AppError::BadRequest(format!(
+                "Answer has to be between {} and {}",
+                self.min, self.max
+            )));
+        }
+
+        if let Some(old_answer) = self.player_answers.get(&player_index).copied() {
+            if !allow_changes {
+                return Err(AppError::BadRequest(
+                    "Already submitted an answer".to_string(),
+                ));
+            }
+
+            let old_bucket_index = self.get_bucket_index(old_answer);
+            self.bucket_counts[old_bucket_index] -= 1;
+
+            let old_sorted_index = self
+                .sorted_answers
+                .partition_point(|other| *other < old_answer);
+            self.sorted_answers.remove(old_sorted_index);
+            self.answer_sum -= old_answer;
+        }
+
+        let bucket_index = self.get_bucket_index(answer);
+        self.bucket_counts[bucket_index] += 1;
+
+        let sorted_index = self.sorted_answers.partition_point(|other| *other < answer);
+        self.sorted_answers.insert(sorted_index, answer);
+        self.answer_sum += answer;
+
+        self.player_answers.insert(player_index, answer);
+
+        return Ok(());
+    }
+
+    fn get_bucket_index(&self, answer: f64) -> usize {
+        let num_buckets = self.bucket_counts.len();
+        let fraction = (answer - self.min) / (self.max - self.min);
+
+        // The upper bound itself belongs into the last bucket
+        return ((fraction * num_buckets as f64) as usize).min(num_buckets - 1);
+    }
+
+    pub fn get_stats(&self) -> Option<NumericStats> {
+        let num_answers = self.sorted_answers.len();
+        if num_answers == 0 {
+            return None;
+        }
+
+        let median = (self.sorted_answers[(num_answers - 1) / 2]
+            + self.sorted_answers[num_answers / 2])
+            / 2f64;
+
+        return Some(NumericStats {
+            num_answers,
+            min: self.sorted_answers[0],
+            max: self.sorted_answers[num_answers - 1],
+            mean: self.answer_sum / num_answers as f64,
+            median,
+        });
+    }
+}
+
+impl RankingLiveAnswers {
+    pub fn new(options: Vec<String>) -> Self {
+        return RankingLiveAnswers {
+            borda_scores: vec![0usize; options.len()],
+            position_sums: vec![0usize; options.len()],
+            options,
+            player_answers: PlayerMap::new(),
+            num_answers: 0,
+        };
+    }
+
+    pub fn submit_answer(
+        &mut self,
+        player_index: usize,
+        ordering: ArrayVec<u8, MC_ANSWERS_CAPACITY>,
+        allow_changes: bool,
+    ) -> Result<(), AppError> {
+        // The ordering has to contain every option exactly once
+        let mut seen = [false; MC_ANSWERS_CAPACITY];
+        let is_permutation = ordering.len() == self.options.len()
+            && ordering.iter().all(|option_index| {
+                let option_index = *option_index as usize;
+                option_index < self.options.len()
+                    && !std::mem::replace(&mut seen[option_index], true)
+            });
+
+        if !is_permutation {
+            return Err(AppError::BadRequest(
+                "The ordering has to contain every option exactly once".to_string(),
+            ));
+        }
+
+        let num_options = self.options.len();
+        if let Some(old_ordering) = self.player_answers.get(&player_index) {
+            if !allow_changes {
+                return Err(AppError::BadRequest(
+                    "Already submitted an ordering".to_string(),
+                ));
+            }
+
+            for (position, option_index) in old_ordering.iter().enumerate() {
+                self.borda_scores[*option_index as usize] -= num_options - 1 - position;
+                self.position_sums[*option_index as usize] -= position + 1;
+            }
+            self.num_answers -= 1;
+        }
+
+        for (position, option_index) in ordering.iter().enumerate() {
+            self.borda_scores[*option_index as usize] += num_options - 1 - position;
+            self.position_sums[*option_index as usize] += position + 1;
+        }
+
+        self.num_answers += 1;
+        self.player_answers.insert(player_index, ordering);
+
+        return Ok(());
+    }
+
+    // Option indices ordered by their Borda score, best first. Ties keep the original order.
+    pub fn get_aggregated_ranking(&self) -> Vec<usize> {
+        let mut ranking = (0..self.options.len()).collect::<Vec<_>>();
+        ranking.sort_by(|a, b| self.borda_scores[*b].cmp(&self.borda_scores[*a]));
+        return ranking;
+    }
+
+    pub fn get_average_positions(&self) -> Vec<Option<f64>> {
+        return self
+            .position_sums
+            .iter()
+            .map(|position_sum| {
+                if self.num_answers > 0 {
+                    Some(*position_sum as f64 / self.num_answers as f64)
+                } else {
+                    None
+                }
+            })
+            .collect();
+    }
+}
+
+impl QandALiveAnswers {
+    pub fn new() -> Self {
+        return QandALiveAnswers {
+            questions: Vec::new(),
+            player_upvotes: PlayerMap::new(),
+        };
+    }
+
+    pub fn submit_question(&mut self, player_index: usize, text: &str) -> Result<(), AppError> {
+        let text = text.trim();
+
+        if text.is_empty() {
+            return Err(AppError::BadRequest(
+                "Can't submit an empty question".to_string(),
+            ));
+        }
+
+        if text.chars().count() > QA_MAX_QUESTION_LENGTH {
+            return Err(AppError::BadRequest(format!(
+                "Questions can't be longer than {QA_MAX_QUESTION_LENGTH} characters"
+            )));
+        }
+
+        let num_own_questions = self
+            .questions
+            .iter()
+            .filter(|question| question.player_index == player_index)
+            .count();
+        if num_own_questions >= QA_MAX_QUESTIONS_PER_PLAYER {
+            return Err(AppError::BadRequest(format!(
+                "Can't submit more than {QA_MAX_QUESTIONS_PER_PLAYER} questions"
+            )));
+        }
+
+        self.questions.push(AudienceQuestion {
+            player_index,
+            text: text.to_string(),
+            upvotes: 0,
+            is_answered: false,
+            is_pinned: false,
+            is_hidden: false,
+        });
+
+        return Ok(());
+    }
+
+    // Upvotes the question, or takes the upvote back if the player already upvoted it
+    pub fn toggle_upvote(
+        &mut self,
+        player_index: usize,
+        question_index: usize,
+    ) -> Result<(), AppError> {
+        let question = self
+            .questions
+            .get_mut(question_index)
+            .filter(|question| !question.is_hidden)
+            .ok_or(AppError::BadRequest(
+                "Question index out of bounds".to_string(),
+            ))?;
+
+        if question.player_index == player_index {
+            return Err(AppError::BadRequest(
+                "Can't upvote your own question".to_string(),
+            ));
+        }
+
+        let upvotes = self.player_upvotes.entry(player_index).or_default();
+        if let Some(position) = upvotes.iter().position(|index| *index == question_index) {
+            upvotes.swap_remove(position);
+            question.upvotes -= 1;
+        } else {
+            upvotes.push(question_index);
+            question.upvotes += 1;
+        }
+
+        return Ok(());
+    }
+
+    pub fn moderate(&mut self, question_index: usize, action: &str) -> Result<(), AppError> {
+        let question = self
+            .questions
+            .get_mut(question_index)
+            .ok_or(AppError::BadRequest(
+                "Question index out of bounds".to_string(),
+            ))?;
+
+        match action {
+            "answer" => question.is_answered = !question.is_answered,
+            "pin" => question.is_pinned = !question.is_pinned,
+            "hide" => question.is_hidden = !question.is_hidden,
+            _ => {
+                return Err(AppError::BadRequest(
+                    "Unknown moderation action".to_string(),
+                ))
+            }
+        }
+
+        return Ok(());
+    }
+
+    // Pinned questions first, answered ones last, in between the most upvoted first
+    pub fn get_sorted_question_indices(&self, include_hidden: bool) -> Vec<usize> {
+        let mut indices = (0..self.questions.len())
+            .filter(|index| include_hidden || !self.questions[*index].is_hidden)
+            .collect::<Vec<_>>();
+
+        indices.sort_by(|a, b| {
+            let (a_question, b_question) = (&self.questions[*a], &self.questions[*b]);
+            b_question
+                .is_pinned
+                .cmp(&a_question.is_pinned)
+                .then(a_question.is_answered.cmp(&b_question.is_answered))
+                .then(b_question.upvotes.cmp(&a_question.upvotes))
+                .then(a.cmp(b))
+        });
+
+        return indices;
+    }
+
+    fn create_question_json(&self, question_index: usize) -> Value {
+        let question = &self.questions[question_index];
+        return json!({
+            "index": question_index,
+            "text": question.text,
+            "upvotes": question.upvotes,
+            "isAnswered": question.is_answered,
+            "isPinned": question.is_pinned,
+        });
+    }
+
+    // The host gets all questions including hidden ones, participants only see the visible
+    // questions and which of them they asked or upvoted
+    pub fn create_questions_json(&self, player_index: Option<usize>) -> Value {
+        return self
+            .get_sorted_question_indices(player_index.is_none())
+            .into_iter()
+            .map(|question_index| {
+                let question = &self.questions[question_index];
+                let mut question_json = self.create_question_json(question_index);
+
+                match player_index {
+                    Some(player_index) => {
+                        question_json["isOwn"] = json!(question.player_index == player_index);
+                        question_json["isUpvoted"] = json!(self
+                            .player_upvotes
+                            .get(&player_index)
+                            .is_some_and(|upvotes| upvotes.contains(&question_index)));
+                    }
+                    None => question_json["isHidden"] = json!(question.is_hidden),
+                }
+
+                question_json
+            })
+            .collect();
+    }
+
+    // Contains the upvotes of all participants, or only those of the given one
+    pub fn create_participant_questions(
+        &self,
+        player_index: Option<usize>,
+    ) -> ParticipantQuestions {
+        let questions = self
+            .get_sorted_question_indices(false)
+            .into_iter()
+            .map(|question_index| {
+                let variants = std::array::from_fn(|variant| {
+                    let mut question_json = self.create_question_json(question_index);
+                    question_json["isOwn"] = json!(variant & IS_OWN_VARIANT != 0);
+                    question_json["isUpvoted"] = json!(variant & IS_UPVOTED_VARIANT != 0);
+                    question_json.to_string()
+                });
+                (
+                    question_index,
+                    self.questions[question_index].player_index,
+                    variants,
+                )
+            })
+            .collect();
+
+        let player_upvotes = match player_index {
+            Some(player_index) => {
+                let mut player_upvotes = PlayerMap::new();
+                if let Some(upvotes) = self.player_upvotes.get(&player_index) {
+                    player_upvotes.insert(player_index, upvotes.clone());
                 }
+                player_upvotes
+            }
+            None => self.player_upvotes.clone(),
+        };
+
+        return ParticipantQuestions {
+            questions,
+            player_upvotes,
+        };
+    }
+}
+
+const IS_OWN_VARIANT: usize = 1;
+const IS_UPVOTED_VARIANT: usize = 2;
+
+// The visible questions as participants see them, serialized once per change and shared by the
+// sockets of all participants. Only whether a participant asked or upvoted a question differs,
+// so every question is serialized once for each combination of the two.
+pub struct ParticipantQuestions {
+    // (question index, player index of the author, question json by variant)
+    questions: Vec<(usize, usize, [String; 4])>,
+    player_upvotes: PlayerMap<Vec<usize>>,
+}
+
+impl ParticipantQuestions {
+    // The same json array as create_questions_json(Some(player_index)), serialized
+    pub fn to_json_string(&self, player_index: usize) -> String {
+        let upvotes = self.player_upvotes.get(&player_index);
+
+        let questions = self
+            .questions
+            .iter()
+            .map(|(question_index, author_index, variants)| {
+                let mut variant = 0;
+                if *author_index == player_index {
+                    variant |= IS_OWN_VARIANT;
+                }
+                if upvotes.is_some_and(|upvotes| upvotes.contains(question_index)) {
+                    variant |= IS_UPVOTED_VARIANT;
+                }
+                variants[variant].as_str()
+            })
+            .collect::<Vec<_>>();
+
+        return format!("[{}]", questions.join(","));
+    }
 
-                let fraction_points = (60_000 - elapsed.as_millis()) as f32 / 60_000f32;
-                return Ok(50usize + (fraction_points * 50f32) as usize);
-            }*/
+    pub fn to_json(&self, player_index: usize) -> Value {
+        return serde_json::from_str(&self.to_json_string(player_index)).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::time::Instant;
+
+    use super::*;
+
+    fn create_mc_answers(
+        correct: &[bool],
+        allow_multiple_answers: bool,
+    ) -> MultipleChoiceLiveAnswers {
+        return MultipleChoiceLiveAnswers {
+            answers: correct
+                .iter()
+                .enumerate()
+                .map(|(index, is_correct)| (format!("Answer {index}"), *is_correct))
+                .collect(),
+            answer_counts: vec![0usize; correct.len()],
+            player_answers: PlayerMap::new(),
+            allow_multiple_answers,
+            chart_type: ChartType::default(),
+        };
+    }
+
+    fn indices(indices: &[u8]) -> ArrayVec<u8, MC_ANSWERS_CAPACITY> {
+        return indices.iter().copied().collect();
+    }
+
+    #[test]
+    fn scores_of_archived_rounds_are_kept() {
+        let mut slide = Slide::new(
+            "Question".to_string(),
+            SlideType::MultipleChoice(create_mc_answers(&[true, false], false)),
+        );
+
+        slide.submit_score(0, 500);
+        slide.submit_score(1, 200);
+        slide.start_new_round();
+        slide.submit_score(0, 300);
+
+        let mut player_scores = [0usize; 2];
+        for (player_index, score) in slide.get_player_scores() {
+            player_scores[player_index] += score;
+        }
+        assert_eq!(player_scores, [800, 200]);
+        assert_eq!(slide.rounds[0].player_scores.get(&0), Some(&500));
+    }
+
+    #[test]
+    fn mc_duplicate_answer_indices_are_rejected() {
+        let mut mc_answers = create_mc_answers(&[true, true, false], true);
+
+        let result = mc_answers.submit_answer(0, indices(&[0, 0]), Instant::now(), false);
+
+        assert!(result.is_err());
+        assert_eq!(mc_answers.answer_counts, vec![0, 0, 0]);
+        assert!(mc_answers.player_answers.is_empty());
+    }
+
+    #[test]
+    fn mc_out_of_range_answer_index_is_rejected() {
+        let mut mc_answers = create_mc_answers(&[true, false], false);
+
+        assert!(mc_answers
+            .submit_answer(0, indices(&[2]), Instant::now(), false)
+            .is_err());
+        assert_eq!(mc_answers.answer_counts, vec![0, 0]);
+    }
+
+    #[test]
+    fn mc_correct_answer_needs_exactly_the_correct_set() {
+        let mc_answers = create_mc_answers(&[true, true, false], true);
+
+        assert!(mc_answers.is_correct_answer(&[0, 1]));
+        assert!(mc_answers.is_correct_answer(&[1, 0]));
+        assert!(!mc_answers.is_correct_answer(&[0, 0]));
+        assert!(!mc_answers.is_correct_answer(&[0]));
+        assert!(!mc_answers.is_correct_answer(&[0, 1, 2]));
+        assert!(!mc_answers.is_correct_answer(&[0, 5]));
+        assert!(!create_mc_answers(&[false, false], false).is_correct_answer(&[0]));
+    }
+
+    #[test]
+    fn mc_changed_answer_moves_the_counts() {
+        let mut mc_answers = create_mc_answers(&[true, false, false], true);
+
+        let score = mc_answers
+            .submit_answer(0, indices(&[0]), Instant::now(), true)
+            .unwrap();
+        assert!(score >= QUIZ_MIN_POINTS);
+
+        let score = mc_answers
+            .submit_answer(0, indices(&[1, 2]), Instant::now(), true)
+            .unwrap();
+        assert_eq!(score, 0);
+        assert_eq!(mc_answers.answer_counts, vec![0, 1, 1]);
+
+        assert!(mc_answers
+            .submit_answer(1, indices(&[0]), Instant::now(), false)
+            .is_ok());
+        assert!(mc_answers
+            .submit_answer(1, indices(&[1]), Instant::now(), false)
+            .is_err());
+        assert_eq!(mc_answers.answer_counts, vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn rating_stats_are_computed_from_the_counts() {
+        let mut rating_answers = RatingLiveAnswers::new(5, String::new(), String::new());
+        assert!(rating_answers.get_stats().is_none());
+
+        for (player_index, rating) in [1u8, 2, 2, 5].into_iter().enumerate() {
+            rating_answers
+                .submit_answer(player_index, rating, false)
+                .unwrap();
+        }
+
+        let stats = rating_answers.get_stats().unwrap();
+        assert_eq!(stats.num_ratings, 4);
+        assert_eq!(stats.mean, 2.5);
+        assert_eq!(stats.median, 2.0);
+        assert!((stats.standard_deviation - 1.5).abs() < 1e-9);
+
+        // An even number of ratings takes the mean of the two middle ones
+        rating_answers.submit_answer(3, 4, true).unwrap();
+        rating_answers.submit_answer(0, 3, true).unwrap();
+        assert_eq!(rating_answers.rating_counts, vec![0, 2, 1, 1, 0]);
+        assert_eq!(rating_answers.get_stats().unwrap().median, 2.5);
+    }
+
+    #[test]
+    fn rating_out_of_scale_is_rejected() {
+        let mut rating_answers = RatingLiveAnswers::new(5, String::new(), String::new());
+
+        assert!(rating_answers.submit_answer(0, 0, false).is_err());
+        assert!(rating_answers.submit_answer(0, 6, false).is_err());
+        assert!(rating_answers.get_stats().is_none());
+    }
+
+    #[test]
+    fn numeric_answers_are_bucketed_and_summarized() {
+        let mut numeric_answers = NumericLiveAnswers::new(0.0, 100.0, 10);
+
+        for (player_index, answer) in [0.0, 9.99, 10.0, 55.5, 100.0].into_iter().enumerate() {
+            numeric_answers
+                .submit_answer(player_index, answer, false)
+                .unwrap();
+        }
+
+        assert_eq!(
+            numeric_answers.bucket_counts,
+            vec![2, 1, 0, 0, 0, 1, 0, 0, 0, 1]
+        );
+
+        let stats = numeric_answers.get_stats().unwrap();
+        assert_eq!(stats.num_answers, 5);
+        assert_eq!(stats.min, 0.0);
+        assert_eq!(stats.max, 100.0);
+        assert_eq!(stats.median, 10.0);
+        assert!((stats.mean - 35.098).abs() < 1e-9);
+    }
+
+    #[test]
+    fn numeric_changed_answer_replaces_the_old_one() {
+        let mut numeric_answers = NumericLiveAnswers::new(-10.0, 10.0, 4);
+
+        numeric_answers.submit_answer(0, -10.0, true).unwrap();
+        numeric_answers.submit_answer(1, 4.0, true).unwrap();
+        numeric_answers.submit_answer(0, 8.0, true).unwrap();
+
+        assert_eq!(numeric_answers.bucket_counts, vec![0, 0, 1, 1]);
+        assert_eq!(numeric_answers.sorted_answers, vec![4.0, 8.0]);
+
+        let stats = numeric_answers.get_stats().unwrap();
+        assert_eq!(stats.median, 6.0);
+        assert_eq!(stats.mean, 6.0);
+    }
+
+    #[test]
+    fn numeric_answer_outside_the_range_is_rejected() {
+        let mut numeric_answers = NumericLiveAnswers::new(0.0, 1.0, 2);
+
+        for answer in [-0.1, 1.1, f64::NAN, f64::INFINITY] {
+            assert!(numeric_answers.submit_answer(0, answer, false).is_err());
+        }
+        assert!(numeric_answers.get_stats().is_none());
+    }
+
+    #[test]
+    fn ranking_is_aggregated_with_borda_count() {
+        let mut ranking_answers =
+            RankingLiveAnswers::new(vec!["A".to_string(), "B".to_string(), "C".to_string()]);
+
+        for (player_index, ordering) in [[0, 1, 2], [1, 0, 2], [1, 2, 0]].iter().enumerate() {
+            ranking_answers
+                .submit_answer(player_index, indices(ordering), false)
+                .unwrap();
+        }
+
+        assert_eq!(ranking_answers.borda_scores, vec![3, 5, 1]);
+        assert_eq!(ranking_answers.get_aggregated_ranking(), vec![1, 0, 2]);
+        assert_eq!(
+            ranking_answers.get_average_positions(),
+            vec![Some(2.0), Some(4.0 / 3.0), Some(8.0 / 3.0)]
+        );
+
+        // A changed ordering replaces the points of the old one
+        ranking_answers
+            .submit_answer(2, indices(&[2, 0, 1]), true)
+            .unwrap();
+        assert_eq!(ranking_answers.borda_scores, vec![4, 3, 2]);
+        assert_eq!(ranking_answers.get_aggregated_ranking(), vec![0, 1, 2]);
+        assert_eq!(ranking_answers.num_answers, 3);
+    }
+
+    #[test]
+    fn ranking_ties_keep_the_original_order() {
+        let mut ranking_answers = RankingLiveAnswers::new(vec!["A".to_string(), "B".to_string()]);
+
+        ranking_answers
+            .submit_answer(0, indices(&[1, 0]), false)
+            .unwrap();
+        ranking_answers
+            .submit_answer(1, indices(&[0, 1]), false)
+            .unwrap();
+
+        assert_eq!(ranking_answers.get_aggregated_ranking(), vec![0, 1]);
+    }
+
+    #[test]
+    fn ranking_has_to_be_a_permutation() {
+        let mut ranking_answers =
+            RankingLiveAnswers::new(vec!["A".to_string(), "B".to_string(), "C".to_string()]);
+
+        for ordering in [&[0, 1][..], &[0, 0, 1], &[0, 1, 3], &[0, 1, 2, 2]] {
+            assert!(ranking_answers
+                .submit_answer(0, indices(ordering), false)
+                .is_err());
+        }
+        assert_eq!(ranking_answers.borda_scores, vec![0, 0, 0]);
+        assert_eq!(
+            ranking_answers.get_average_positions(),
+            vec![None, None, None]
+        );
+    }
+
+    fn get_term<'a>(
+        ft_answers: &'a FreeTextLiveAnswers,
+        lowercase_text: &str,
+    ) -> Option<&'a WordCloudTerm> {
+        return ft_answers
+            .word_cloud_terms
+            .iter()
+            .find(|term| term.lowercase_text == lowercase_text);
+    }
+
+    #[test]
+    fn word_cloud_changed_answer_is_decremented() {
+        let mut ft_answers = FreeTextLiveAnswers::new();
+
+        for (player_index, answer) in ["Cat", "cat ", "cat", "Dog"].into_iter().enumerate() {
+            ft_answers
+                .submit_answer(player_index, SmartString::from(answer), true)
+                .unwrap();
         }
 
-        self.player_answers[player_index] = Some(answer_indices);
+        let cat = get_term(&ft_answers, "cat").unwrap();
+        assert_eq!(cat.count, 3);
+        assert_eq!(cat.preferred_spelling, "cat");
+
+        // A tie keeps the preferred spelling, it only changes once another one is used more
+        ft_answers
+            .submit_answer(2, SmartString::from("Bird"), true)
+            .unwrap();
+        let cat = get_term(&ft_answers, "cat").unwrap();
+        assert_eq!(cat.count, 2);
+        assert_eq!(cat.spellings.get("cat"), Some(&1));
+        assert_eq!(cat.preferred_spelling, "cat");
+
+        ft_answers
+            .submit_answer(1, SmartString::from("bird"), true)
+            .unwrap();
+        let cat = get_term(&ft_answers, "cat").unwrap();
+        assert_eq!(cat.count, 1);
+        assert_eq!(cat.spellings.len(), 1);
+        assert_eq!(cat.preferred_spelling, "Cat");
+        assert_eq!(get_term(&ft_answers, "bird").unwrap().count, 2);
+
+        // A term without answers disappears, the other terms keep their order
+        ft_answers
+            .submit_answer(3, SmartString::from("cat"), true)
+            .unwrap();
+        let texts = ft_answers
+            .word_cloud_terms
+            .iter()
+            .map(|term| term.lowercase_text.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(texts, vec!["cat", "bird"]);
+        assert_eq!(ft_answers.player_answers.len(), 4);
+    }
+
+    #[test]
+    fn word_cloud_answer_can_only_be_changed_if_allowed() {
+        let mut ft_answers = FreeTextLiveAnswers::new();
+
+        ft_answers
+            .submit_answer(0, SmartString::from("Cat"), false)
+            .unwrap();
+        assert!(ft_answers
+            .submit_answer(0, SmartString::from("Dog"), false)
+            .is_err());
+
+        assert_eq!(ft_answers.word_cloud_terms.len(), 1);
+        assert_eq!(ft_answers.player_answers.get(&0).unwrap(), "Cat");
+    }
+
+    #[test]
+    fn word_cloud_max_term_count_follows_removed_answers() {
+        let mut ft_answers = FreeTextLiveAnswers::new();
+
+        for (player_index, answer) in ["cat", "cat", "dog"].into_iter().enumerate() {
+            ft_answers
+                .submit_answer(player_index, SmartString::from(answer), true)
+                .unwrap();
+        }
+        assert_eq!(ft_answers.max_term_count, 2);
+
+        ft_answers
+            .submit_answer(0, SmartString::from("dog"), true)
+            .unwrap();
+        assert_eq!(ft_answers.max_term_count, 2);
+
+        ft_answers
+            .submit_answer(2, SmartString::from("bird"), true)
+            .unwrap();
+        assert_eq!(ft_answers.max_term_count, 1);
+    }
+
+    #[test]
+    fn word_cloud_term_indices_are_rebuilt_after_loading() {
+        let mut ft_answers = FreeTextLiveAnswers::new();
+        for (player_index, answer) in ["cat", "dog"].into_iter().enumerate() {
+            ft_answers
+                .submit_answer(player_index, SmartString::from(answer), false)
+                .unwrap();
+        }
+
+        let json = serde_json::to_string(&ft_answers).unwrap();
+        let mut ft_answers: FreeTextLiveAnswers = serde_json::from_str(&json).unwrap();
+
+        ft_answers
+            .submit_answer(2, SmartString::from("Dog"), false)
+            .unwrap();
+        assert_eq!(ft_answers.word_cloud_terms.len(), 2);
+        assert_eq!(get_term(&ft_answers, "dog").unwrap().count, 2);
+    }
+
+    #[test]
+    fn participant_questions_match_the_questions_json() {
+        let mut qa_answers = QandALiveAnswers::new();
+        qa_answers.submit_question(0, "First \"question\"").unwrap();
+        qa_answers.submit_question(1, "Second").unwrap();
+        qa_answers.submit_question(1, "Hidden").unwrap();
+        qa_answers.toggle_upvote(0, 1).unwrap();
+        qa_answers.toggle_upvote(2, 1).unwrap();
+        qa_answers.moderate(0, "pin").unwrap();
+        qa_answers.moderate(2, "hide").unwrap();
+
+        let participant_questions = qa_answers.create_participant_questions(None);
+        for player_index in 0..4 {
+            let json: Value =
+                serde_json::from_str(&participant_questions.to_json_string(player_index)).unwrap();
+            assert_eq!(json, qa_answers.create_questions_json(Some(player_index)));
+            assert_eq!(
+                qa_answers
+                    .create_participant_questions(Some(player_index))
+                    .to_json(player_index),
+                json
+            );
+        }
 
-        return Ok(0usize);
+        assert_eq!(
+            QandALiveAnswers::new()
+                .create_participant_questions(None)
+                .to_json_string(0),
+            "[]"
+        );
     }
 }
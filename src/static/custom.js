@@ -46,6 +46,31 @@ function homeFromTemplate(variant) {
   location.href = "/host";
 }
 
+// Counts down locally from the remaining time the server sent, so clock differences between
+// server and browser do not matter. Calls onTick with the whole seconds left, or null when done.
+function runCountdown(remainingSeconds, previousInterval, onTick) {
+  clearInterval(previousInterval);
+  if (remainingSeconds === null) {
+    onTick(null);
+    return null;
+  }
+
+  const end = Date.now() + remainingSeconds * 1000;
+  const tick = () => {
+    const secondsLeft = Math.max(0, Math.ceil((end - Date.now()) / 1000));
+    onTick(secondsLeft);
+    if (secondsLeft == 0) clearInterval(interval);
+  };
+  const interval = setInterval(tick, 250);
+  tick();
+
+  return interval;
+}
+
+function formatCountdown(seconds) {
+  return `${Math.floor(seconds / 60)}:${String(seconds % 60).padStart(2, "0")}`;
+}
+
 function createSlide(type) {
   return {
     type: type,
@@ -57,12 +82,24 @@ function createSlide(type) {
     allowMultipleMCAnswers: false,
     mcChartType: "bar",
     ftAnswers: [],
+    ratingScale: 5,
+    ratingLowLabel: "",
+    ratingHighLabel: "",
+    numericMin: 0,
+    numericMax: 100,
+    numericBuckets: 10,
+    infoBody: "",
+    infoPreview: "",
+    hideResults: false,
+    shareResults: false,
+    allowAnswerChanges: false,
     stats: null,
   };
 }
 
 function createPoll() {
   return {
+    version: 1,
     slides: [createSlide("mc")],
     enableLeaderboard: false,
     allowCustomNames: false,
@@ -78,6 +115,27 @@ function loadPollFromLocalStorage() {
       if (slide.mcChartType === undefined) {
         slide.mcChartType = "bar";
       }
+      if (slide.ratingScale === undefined) {
+        slide.ratingScale = 5;
+        slide.ratingLowLabel = "";
+        slide.ratingHighLabel = "";
+      }
+      if (slide.infoBody === undefined) {
+        slide.infoBody = "";
+        slide.infoPreview = "";
+      }
+      if (slide.hideResults === undefined) {
+        slide.hideResults = false;
+        slide.shareResults = false;
+      }
+      if (slide.allowAnswerChanges === undefined) {
+        slide.allowAnswerChanges = false;
+      }
+      if (slide.numericBuckets === undefined) {
+        slide.numericMin = 0;
+        slide.numericMax = 100;
+        slide.numericBuckets = 10;
+      }
     }
 
     if (poll.slides.length == 0) poll.slides.push(createSlide("mc"));
@@ -94,8 +152,17 @@ document.addEventListener("alpine:init", () => {
     isLive: false,
     isFullscreen: false,
     code: null,
+    reportUrl: document.lastReportUrl ?? null,
     socket: null,
+    serverRestarting: false,
     fontScale: 1.0,
+    startPollErrors: [],
+    leaderboard: null,
+    markdownPreviewTimeout: null,
+    showFTAnswerList: false,
+    votingState: { slideIndex: null, isOpen: true, remainingSeconds: null },
+    votingCountdown: null,
+    votingCountdownInterval: null,
 
     init() {
       addEventListener("keydown", (event) => {
@@ -110,6 +177,11 @@ document.addEventListener("alpine:init", () => {
 
       this.poll.slides.forEach((slide) => {
         slide.stats = null;
+        slide.resultsHidden = false;
+        slide.numResponses = 0;
+        slide.round = 1;
+        slide.comparison = null;
+        slide.comparisonRound = null;
       });
 
       if (document.pollAlreadyLive === true) {
@@ -147,6 +219,123 @@ document.addEventListener("alpine:init", () => {
       }
     },
 
+    // Height of a bar in percent of the highest bar, for rating and histogram charts
+    statsBarHeight(slide, index) {
+      if (slide.stats === null) return 2;
+
+      const maxCount = Math.max(...slide.stats.counts);
+      return maxCount > 0
+        ? Math.max(2, (100.0 * slide.stats.counts[index]) / maxCount)
+        : 2;
+    },
+
+    // Info slides are rendered by the server, the preview is debounced while typing
+    renderMarkdownPreview(slide) {
+      clearTimeout(this.markdownPreviewTimeout);
+      this.markdownPreviewTimeout = setTimeout(async () => {
+        let res = await fetch("/render_markdown", {
+          method: "POST",
+          body: slide.infoBody,
+        });
+
+        if (res.ok) slide.infoPreview = await res.text();
+      }, 300);
+    },
+
+    compareRounds(round) {
+      this.poll.slides.forEach((slide, slideIndex) => {
+        slide.comparisonRound =
+          slideIndex == this.poll.activeSlide ? round : null;
+        if (slideIndex != this.poll.activeSlide) slide.comparison = null;
+      });
+
+      this.socket.send(
+        JSON.stringify({
+          cmd: "compareRounds",
+          data: { slideIndex: this.poll.activeSlide, round: round },
+        }),
+      );
+    },
+
+    formatDelta(delta) {
+      return delta > 0 ? `+${delta}` : delta < 0 ? `−${-delta}` : "±0";
+    },
+
+    resetSlide() {
+      this.socket.send(
+        JSON.stringify({
+          cmd: "resetSlide",
+          data: { slideIndex: this.poll.activeSlide },
+        }),
+      );
+    },
+
+    revealResults() {
+      this.socket.send(
+        JSON.stringify({
+          cmd: "revealResults",
+          data: { slideIndex: this.poll.activeSlide },
+        }),
+      );
+    },
+
+    setVotingOpen(isOpen) {
+      this.socket.send(
+        JSON.stringify({
+          cmd: "setVotingOpen",
+          data: { slideIndex: this.poll.activeSlide, isOpen: isOpen },
+        }),
+      );
+    },
+
+    startCountdown(seconds) {
+      this.socket.send(
+        JSON.stringify({
+          cmd: "startCountdown",
+          data: { slideIndex: this.poll.activeSlide, seconds: seconds },
+        }),
+      );
+    },
+
+    moderateQuestion(questionIndex, action) {
+      this.socket.send(
+        JSON.stringify({
+          cmd: "moderateQuestion",
+          data: {
+            slideIndex: this.poll.activeSlide,
+            questionIndex: questionIndex,
+            action: action,
+          },
+        }),
+      );
+    },
+
+    // Option indices in the order of the aggregated ranking, or in their original order before any answer
+    rankingOrder(slide) {
+      if (slide.stats === null || slide.stats.numAnswers == 0)
+        return slide.mcAnswers.map((_answer, index) => index);
+      return slide.stats.ranking;
+    },
+
+    rankingBarWidth(slide, optionIndex) {
+      if (slide.stats === null) return 1;
+
+      const maxScore = Math.max(...slide.stats.bordaScores);
+      return maxScore > 0
+        ? Math.max(1, (100.0 * slide.stats.bordaScores[optionIndex]) / maxScore)
+        : 1;
+    },
+
+    formatNumber(number) {
+      return number.toLocaleString(undefined, { maximumFractionDigits: 2 });
+    },
+
+    numericBucketLabel(slide, bucketIndex) {
+      const bucketSize =
+        (slide.numericMax - slide.numericMin) / Math.max(1, slide.numericBuckets);
+      return `${this.formatNumber(slide.numericMin + bucketIndex * bucketSize)}–${this.formatNumber(slide.numericMin + (bucketIndex + 1) * bucketSize)}`;
+    },
+
     renderWordCloud(slideIndex) {
       let container = document.getElementById("word-cloud-" + slideIndex);
       if (!container || !this.poll.slides[slideIndex].stats) return;
@@ -157,6 +346,11 @@ document.addEventListener("alpine:init", () => {
       const HORIZONTAL_GAP = 36 * this.getFontScale();
       const VERTICAL_GAP = 20 * this.getFontScale();
 
+      // Terms disappear when participants change their answer
+      while (container.children.length > stats.terms.length) {
+        container.lastElementChild.remove();
+      }
+
       const sortedTerms = stats.terms
         .map((term, index) => {
           let c = container.children[index] || document.createElement("div");
@@ -424,7 +618,27 @@ document.addEventListener("alpine:init", () => {
       ctx.clearRect(0, 0, canvas.width, canvas.height);
     },
 
+    // In quiz mode, the leaderboard is shown between a scored slide and the next one
+    showsLeaderboardBefore(slideIndex) {
+      const slide = this.poll.slides[this.poll.activeSlide];
+      return (
+        this.isLive &&
+        this.poll.enableLeaderboard &&
+        this.leaderboard === null &&
+        slideIndex > this.poll.activeSlide &&
+        slide.type == "mc" &&
+        slide.mcAnswers.some((answer) => answer.isCorrect)
+      );
+    },
+
     gotoSlide(slideIndex) {
+      if (this.showsLeaderboardBefore(slideIndex)) {
+        this.leaderboard = { leaderboard: [], player: null };
+        this.socket.send(JSON.stringify({ cmd: "showLeaderboard", data: null }));
+        return;
+      }
+
+      this.leaderboard = null;
       slideIndex = Math.max(
         0,
         Math.min(slideIndex, this.poll.slides.length - 1),
@@ -464,7 +678,19 @@ document.addEventListener("alpine:init", () => {
         },
       });
 
+      if (
+        response.status == 400 &&
+        response.headers.get("Content-Type")?.startsWith("application/json")
+      ) {
+        this.startPollErrors = (await response.json()).errors;
+      }
+
+      if (response.status == 503) {
+        this.startPollErrors = [{ slideIndex: null, field: "", reason: await response.text() }];
+      }
+
       if (response.ok) {
+        this.startPollErrors = [];
         this.code = await response.text();
         this.isLive = true;
         document.querySelector("body").dataset.live = true;
@@ -481,6 +707,7 @@ document.addEventListener("alpine:init", () => {
 
         this.socket = new ReconnectingWebSocket(wsUrl);
         this.socket.onopen = (_e) => {
+          this.serverRestarting = false;
           // This does not change the current displayed slide, but rather initiates a slidechange websocket message
           // to the server, so it knows the current active slide
           this.gotoSlide(this.poll.activeSlide);
@@ -489,8 +716,19 @@ document.addEventListener("alpine:init", () => {
           let msg = JSON.parse(e.data);
 
           switch (msg.cmd) {
+            case "serverRestarting":
+              // The socket reconnects on its own, just not before the server is likely back
+              this.serverRestarting = true;
+              this.socket.reconnectInterval = msg.data.reconnectAfterMs;
+              break;
             case "updateStats":
               let slide = this.poll.slides[msg.data.slideIndex];
+              slide.resultsHidden = msg.data.resultsHidden;
+              slide.numResponses = msg.data.numResponses;
+              slide.round = msg.data.round;
+              slide.comparison = msg.data.comparison;
+              if (slide.resultsHidden) break;
+
               const oldStats = slide.stats;
               slide.stats = msg.data.stats;
               if (slide.type == "mc") {
@@ -543,6 +781,19 @@ document.addEventListener("alpine:init", () => {
             case "setEmojiCounts":
               this.poll.slides[msg.data.slideIndex].emojis = msg.data.emojis;
               break;
+            case "votingState":
+              if (msg.data.slideIndex == this.poll.activeSlide) {
+                this.votingState = msg.data;
+                this.votingCountdownInterval = runCountdown(
+                  msg.data.remainingSeconds,
+                  this.votingCountdownInterval,
+                  (secondsLeft) => (this.votingCountdown = secondsLeft),
+                );
+              }
+              break;
+            case "showLeaderboard":
+              this.leaderboard = msg.data;
+              break;
             case "newEmoji":
               this.poll.slides[msg.data.slideIndex].emojis[msg.data.emoji] += 1;
               setTimeout(() => {
@@ -598,8 +849,11 @@ document.addEventListener("alpine:init", () => {
       });
 
       if (response.ok) {
+        const reportUrl = await response.text();
+        this.reportUrl = reportUrl !== "" ? reportUrl : null;
         this.code = null;
         this.isLive = false;
+        this.leaderboard = null;
         this.socket.close();
         this.clearStatistics();
         document.querySelector("body").dataset.live = false;
@@ -786,25 +1040,75 @@ document.addEventListener("alpine:init", () => {
     },
     slideIndex: null,
     socket: null,
+    leaderboard: null,
+    draggedRankingPosition: null,
+    newQuestion: "",
+    requestError: null,
+    serverRestarting: false,
+    player: null,
+    isEditingPlayer: false,
+    playerForm: { name: "", avatarIndex: 0 },
+    playerFormError: null,
+    results: null,
+    isVotingOpen: true,
+    votingCountdown: null,
+    votingCountdownInterval: null,
 
     init() {
+      this.player = {
+        name: this.$el.dataset.name,
+        customName: this.$el.dataset.customName,
+        avatarIndex: Number(this.$el.dataset.avatarIndex),
+      };
+
       const wsUrl = `${window.location.protocol === "https:" ? "wss" : "ws"}://${window.location.host}/ws/p/${document.code}`;
 
       this.socket = new ReconnectingWebSocket(wsUrl);
-      this.socket.onopen = (_e) => {};
+      this.socket.onopen = (_e) => {
+        this.serverRestarting = false;
+      };
       this.socket.onmessage = (e) => {
         let msg = JSON.parse(e.data);
 
         switch (msg.cmd) {
+          case "serverRestarting":
+            this.serverRestarting = true;
+            this.socket.reconnectInterval = msg.data.reconnectAfterMs;
+            break;
           case "updateSlide":
             this.currentSlide = msg.data.slide;
             this.slideIndex = msg.data.slideIndex;
+            this.results = msg.data.results;
+            this.leaderboard = null;
+            this.requestError = null;
+            break;
+          case "votingState":
+            if (msg.data.slideIndex == this.slideIndex) {
+              this.isVotingOpen = msg.data.isOpen;
+              this.votingCountdownInterval = runCountdown(
+                msg.data.remainingSeconds,
+                this.votingCountdownInterval,
+                (secondsLeft) => (this.votingCountdown = secondsLeft),
+              );
+            }
+            break;
+          case "showLeaderboard":
+            this.leaderboard = msg.data;
+            break;
+          case "updateQuestions":
+            if (msg.data.slideIndex == this.slideIndex) {
+              this.currentSlide.questions = msg.data.questions;
+            }
+            break;
+          case "requestRejected":
+            this.requestError = msg.data.reason;
             break;
         }
       };
     },
 
     async submitMCAnswer(poll_id) {
+      this.requestError = null;
       let res = await fetch("/submit_mc_answer/" + poll_id, {
         method: "POST",
         headers: { "Content-Type": "application/json" },
@@ -817,9 +1121,11 @@ document.addEventListener("alpine:init", () => {
       });
 
       if (res.ok) this.currentSlide.submitted = true;
+      else this.requestError = await res.text();
     },
 
     async submitFTAnswer(poll_id) {
+      this.requestError = null;
       let res = await fetch("/submit_ft_answer/" + poll_id, {
         method: "POST",
         headers: { "Content-Type": "application/x-www-form-urlencoded" },
@@ -830,6 +1136,124 @@ document.addEventListener("alpine:init", () => {
       });
 
       if (res.ok) this.currentSlide.submitted = true;
+      else this.requestError = await res.text();
+    },
+
+    resultBarWidth(counts, index) {
+      const maxCount = Math.max(...counts);
+      return maxCount > 0 ? (100.0 * counts[index]) / maxCount : 0;
+    },
+
+    formatResult(value) {
+      return value === null
+        ? "–"
+        : value.toLocaleString(undefined, { maximumFractionDigits: 2 });
+    },
+
+    editPlayer() {
+      this.playerForm = {
+        name: this.player.customName,
+        avatarIndex: this.player.avatarIndex,
+      };
+      this.playerFormError = null;
+      this.isEditingPlayer = true;
+    },
+
+    async submitNameAvatar(poll_id) {
+      let res = await fetch("/name_avatar/" + poll_id, {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({
+          name: this.playerForm.name,
+          avatar_index: this.playerForm.avatarIndex,
+        }),
+      });
+
+      if (res.ok) {
+        const player = await res.json();
+        this.player = {
+          name: player.name,
+          customName: this.playerForm.name.trim(),
+          avatarIndex: player.avatarIndex,
+        };
+        this.isEditingPlayer = false;
+      } else {
+        this.playerFormError = await res.text();
+      }
+    },
+
+    async submitRating(poll_id) {
+      this.requestError = null;
+      let res = await fetch("/submit_rating/" + poll_id, {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({
+          rating: this.currentSlide.selectedAnswer,
+          slide_index: this.slideIndex,
+        }),
+      });
+
+      if (res.ok) this.currentSlide.submitted = true;
+      else this.requestError = await res.text();
+    },
+
+    async submitNumericAnswer(poll_id) {
+      this.requestError = null;
+      let res = await fetch("/submit_numeric_answer/" + poll_id, {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({
+          answer: this.currentSlide.selectedAnswer,
+          slide_index: this.slideIndex,
+        }),
+      });
+
+      if (res.ok) this.currentSlide.submitted = true;
+      else this.requestError = await res.text();
+    },
+
+    moveRankingOption(position, newPosition) {
+      const ordering = this.currentSlide.selectedAnswer;
+      if (newPosition < 0 || newPosition >= ordering.length) return;
+
+      const [optionIndex] = ordering.splice(position, 1);
+      ordering.splice(newPosition, 0, optionIndex);
+    },
+
+    async submitRanking(poll_id) {
+      this.requestError = null;
+      let res = await fetch("/submit_ranking/" + poll_id, {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({
+          ordering: this.currentSlide.selectedAnswer,
+          slide_index: this.slideIndex,
+        }),
+      });
+
+      if (res.ok) this.currentSlide.submitted = true;
+      else this.requestError = await res.text();
+    },
+
+    submitQuestion() {
+      this.requestError = null;
+      this.socket.send(
+        JSON.stringify({
+          cmd: "submitQuestion",
+          data: { slideIndex: this.slideIndex, text: this.newQuestion },
+        }),
+      );
+      this.newQuestion = "";
+    },
+
+    upvoteQuestion(questionIndex) {
+      this.requestError = null;
+      this.socket.send(
+        JSON.stringify({
+          cmd: "upvoteQuestion",
+          data: { slideIndex: this.slideIndex, questionIndex: questionIndex },
+        }),
+      );
     },
 
     async submitEmoji(poll_id, emoji) {
@@ -13,36 +13,29 @@ pub struct Bundle {
 }
 
 pub fn init() {
-    let mut bundles = Vec::with_capacity(3);
-
-    bundles.push(create_bundle(
-        "bundle.css",
-        "text/css",
-        &["./src/static/output.css"],
-    ));
-
-    bundles.push(create_bundle(
-        "app.js",
-        "text/javascript",
-        &[
-            "./src/static/reconnecting-websocket.min.js",
-            "./src/static/custom.js",
-            //"./src/static/alpine_3_14_3_collapse.min.js",
-            "./src/static/alpine_3_14_3.min.js",
-        ],
-    ));
-
-    bundles.push(create_bundle(
-        "tailwind_4_dev.js",
-        "text/javascript",
-        &["./src/static/tailwind_4_dev.js"],
-    ));
-
-    bundles.push(create_bundle(
-        "qrcode.js",
-        "text/javascript",
-        &["./src/static/qrcode.min.js"],
-    ));
+    let bundles = vec![
+        create_bundle("bundle.css", "text/css", &["./src/static/output.css"]),
+        create_bundle(
+            "app.js",
+            "text/javascript",
+            &[
+                "./src/static/reconnecting-websocket.min.js",
+                "./src/static/custom.js",
+                //"./src/static/alpine_3_14_3_collapse.min.js",
+                "./src/static/alpine_3_14_3.min.js",
+            ],
+        ),
+        create_bundle(
+            "tailwind_4_dev.js",
+            "text/javascript",
+            &["./src/static/tailwind_4_dev.js"],
+        ),
+        create_bundle(
+            "qrcode.js",
+            "text/javascript",
+            &["./src/static/qrcode.min.js"],
+        ),
+    ];
 
     let _ = BUNDLES.set(bundles);
 }
@@ -68,10 +61,10 @@ fn create_bundle(name: &str, mime_type: &str, files: &[&str]) -> Bundle {
     let mut content = String::new();
 
     for file in files {
-        match std::fs::read_to_string(&file) {
+        match std::fs::read_to_string(file) {
             Ok(file_content) => {
                 content.push_str(&file_content);
-                content.push_str("\n");
+                content.push('\n');
             }
             Err(e) => {
                 error!("Error loading file {} ({})", &file, e);
@@ -82,8 +75,8 @@ fn create_bundle(name: &str, mime_type: &str, files: &[&str]) -> Bundle {
     let hash = md5::compute(content.as_bytes());
 
     let mut path = format!("/static/{:x}", hash);
-    path.push_str(".");
-    path.push_str(&name);
+    path.push('.');
+    path.push_str(name);
 
     Bundle {
         name: name.to_string(),
@@ -3,15 +3,15 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use smartstring::{Compact, SmartString};
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct WSMessage {
     pub cmd: SmartString<Compact>,
     pub data: Value,
 }
 
-impl Into<Message> for WSMessage {
-    fn into(self) -> Message {
-        return Message::Text(serde_json::to_string(&self).unwrap());
+impl From<WSMessage> for Message {
+    fn from(msg: WSMessage) -> Message {
+        return Message::Text(serde_json::to_string(&msg).unwrap());
     }
 }
 